@@ -174,6 +174,7 @@ impl ChangeFixture {
                     file_id,
                     meta.edition,
                     Some(crate_name.clone().into()),
+                    meta.cfg.clone(),
                     meta.cfg,
                     meta.env,
                     Default::default(),
@@ -203,6 +204,7 @@ impl ChangeFixture {
                 crate_root,
                 Edition::Edition2018,
                 Some(CrateName::new("test").unwrap().into()),
+                default_cfg.clone(),
                 default_cfg,
                 Env::default(),
                 Default::default(),
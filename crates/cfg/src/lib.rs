@@ -41,6 +41,23 @@ impl CfgOptions {
         self.enabled.insert(CfgAtom::KeyValue { key, value });
     }
 
+    /// Returns the names of the flags and the keys of the key-value options.
+    pub fn get_cfg_keys(&self) -> impl Iterator<Item = &SmolStr> {
+        self.enabled.iter().map(|atom| match atom {
+            CfgAtom::Flag(key) | CfgAtom::KeyValue { key, .. } => key,
+        })
+    }
+
+    pub fn get_cfg_values<'a>(
+        &'a self,
+        cfg_key: &'a str,
+    ) -> impl Iterator<Item = &'a SmolStr> + 'a {
+        self.enabled.iter().filter_map(move |atom| match atom {
+            CfgAtom::KeyValue { key, value } if key == cfg_key => Some(value),
+            _ => None,
+        })
+    }
+
     pub fn apply_diff(&mut self, diff: CfgDiff) {
         for atom in diff.enable {
             self.enabled.insert(atom);
@@ -84,6 +84,7 @@ pub use crate::{
 // Generally, a refactoring which *removes* a name from this list is a good
 // idea!
 pub use {
+    cfg::CfgOptions,
     hir_def::{
         adt::StructKind,
         attr::{Attrs, Documentation},
@@ -168,6 +169,12 @@ impl Crate {
         db.crate_graph()[self.id].display_name.clone()
     }
 
+    /// The cfg options the crate could be built with, including the
+    /// disabled Cargo features.
+    pub fn potential_cfg(self, db: &dyn HirDatabase) -> CfgOptions {
+        db.crate_graph()[self.id].potential_cfg_options.clone()
+    }
+
     pub fn query_external_importables(
         self,
         db: &dyn DefDatabase,
@@ -846,6 +853,10 @@ impl Function {
         db.function_data(self.id).is_unsafe
     }
 
+    pub fn is_async(self, db: &dyn HirDatabase) -> bool {
+        db.function_data(self.id).is_async
+    }
+
     pub fn diagnostics(self, db: &dyn HirDatabase, sink: &mut DiagnosticSink) {
         let krate = self.module(db).id.krate();
         hir_def::diagnostics::validate_body(db.upcast(), self.id.into(), sink);
@@ -875,6 +886,7 @@ impl Function {
 }
 
 // Note: logically, this belongs to `hir_ty`, but we are not using it there yet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Access {
     Shared,
     Exclusive,
@@ -1529,6 +1541,11 @@ impl Impl {
         db.impl_data(self.id).target_trait.clone()
     }
 
+    pub fn trait_(self, db: &dyn HirDatabase) -> Option<Trait> {
+        let trait_ref = db.impl_trait(self.id)?;
+        Some(trait_ref.value.trait_.into())
+    }
+
     pub fn target_ty(self, db: &dyn HirDatabase) -> Type {
         let impl_data = db.impl_data(self.id);
         let resolver = self.id.resolver(db.upcast());
@@ -1627,6 +1644,10 @@ impl Type {
         matches!(self.ty.value.interned(&Interner), TyKind::Scalar(Scalar::Bool))
     }
 
+    pub fn is_float(&self) -> bool {
+        matches!(self.ty.value.interned(&Interner), TyKind::Scalar(Scalar::Float(_)))
+    }
+
     pub fn is_mutable_reference(&self) -> bool {
         matches!(self.ty.value.interned(&Interner), TyKind::Ref(hir_ty::Mutability::Mut, ..))
     }
@@ -599,7 +599,9 @@ impl ExprCollector<'_> {
                     let pat = self.collect_pat_opt(stmt.pat());
                     let type_ref = stmt.ty().map(|it| TypeRef::from_ast(&self.ctx(), it));
                     let initializer = stmt.initializer().map(|e| self.collect_expr(e));
-                    vec![Statement::Let { pat, type_ref, initializer }]
+                    let else_branch =
+                        stmt.let_else().map(|block| self.collect_expr(ast::Expr::BlockExpr(block)));
+                    vec![Statement::Let { pat, type_ref, initializer, else_branch }]
                 }
                 ast::Stmt::ExprStmt(stmt) => {
                     self.check_cfg(&stmt)?;
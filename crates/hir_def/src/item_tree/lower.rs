@@ -398,6 +398,7 @@ impl Ctx {
             has_self_param,
             has_body,
             is_unsafe: func.unsafe_token().is_some(),
+            is_async: func.async_token().is_some(),
             is_extern: false,
             params,
             is_varargs,
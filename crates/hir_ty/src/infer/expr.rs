@@ -760,7 +760,7 @@ impl<'a> InferenceContext<'a> {
     ) -> Ty {
         for stmt in statements {
             match stmt {
-                Statement::Let { pat, type_ref, initializer } => {
+                Statement::Let { pat, type_ref, initializer, else_branch } => {
                     let decl_ty =
                         type_ref.as_ref().map(|tr| self.make_ty(tr)).unwrap_or(self.err_ty());
 
@@ -775,6 +775,14 @@ impl<'a> InferenceContext<'a> {
                         }
                     }
 
+                    if let Some(expr) = else_branch {
+                        // The `else` block has to diverge, which doesn't make
+                        // the rest of the enclosing block unreachable.
+                        let prev_diverges = mem::replace(&mut self.diverges, Diverges::Maybe);
+                        self.infer_expr(*expr, &Expectation::none());
+                        self.diverges = prev_diverges;
+                    }
+
                     let ty = self.resolve_ty_as_possible(ty);
                     self.infer_pat(*pat, &ty, BindingMode::default());
                 }
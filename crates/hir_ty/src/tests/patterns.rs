@@ -803,3 +803,27 @@ fn foo(foo: Foo) {
         "#]],
     );
 }
+
+#[test]
+fn let_else_pattern() {
+    check_infer_with_mismatches(
+        r#"
+enum Option<T> { Some(T), None }
+use Option::*;
+fn foo(opt: Option<u32>) -> u32 {
+    let Some(x) = opt else { return 0 };
+    x
+}"#,
+        expect![[r#"
+            55..58 'opt': Option<u32>
+            80..130 '{     ...   x }': u32
+            90..97 'Some(x)': Option<u32>
+            95..96 'x': u32
+            100..103 'opt': Option<u32>
+            109..121 '{ return 0 }': !
+            111..119 'return 0': !
+            118..119 '0': u32
+            127..128 'x': u32
+        "#]],
+    );
+}
@@ -0,0 +1,402 @@
+//! Runs a single assist at every position where it applies, in a file, a
+//! module or a whole crate, and merges the results into one `SourceChange`.
+//!
+//! This is meant for mechanical migrations, like adding explicit types or
+//! qualifying paths across a code base.
+
+use hir::Semantics;
+use ide_assists::{Assist, AssistConfig};
+use ide_db::{
+    base_db::{FileId, FileRange},
+    helpers::insert_use::{insert_use, ImportScope},
+    source_change::SourceChange,
+    RootDatabase,
+};
+use rustc_hash::FxHashSet;
+use syntax::{
+    algo,
+    ast::{self, ModuleItemOwner},
+    AstNode, NodeOrToken, SourceFile, TextRange, TextSize,
+};
+use text_edit::{Indel, TextEdit};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssistScope {
+    /// Just the given file.
+    File,
+    /// The module defined by the given file, and all of its submodules.
+    Module,
+    /// The whole crate the given file belongs to.
+    Crate,
+}
+
+// Feature: Apply Assist Everywhere
+//
+// Applies an assist at every position it is applicable to, in the current
+// file, module or crate. Applications overlapping an earlier one, and
+// positions where the assist offers several alternatives, like `auto_import`
+// with multiple candidates, are skipped. The imports the applications add are
+// inserted once each.
+//
+// Available as the `experimental/applyAssistEverywhere` request and the
+// `rust-analyzer apply-assist` command.
+pub(crate) fn apply_assist_everywhere(
+    db: &RootDatabase,
+    config: &AssistConfig,
+    assist_id: &str,
+    file_id: FileId,
+    scope: AssistScope,
+) -> SourceChange {
+    let sema = Semantics::new(db);
+    // The edits are applied without user interaction, so there is nobody to
+    // fill in snippet placeholders.
+    let config = AssistConfig { snippet_cap: None, ..config.clone() };
+
+    let mut res = SourceChange::default();
+    let mut imports = Vec::new();
+    for file_id in files_in_scope(&sema, file_id, scope) {
+        let source_file = sema.parse(file_id);
+        let mut seen_targets = FxHashSet::default();
+        // The assist finds the same target again from the positions inside
+        // it, so those aren't tried.
+        let mut skip_until = TextSize::from(0);
+        let tokens = source_file
+            .syntax()
+            .descendants_with_tokens()
+            .filter_map(|it| it.into_token())
+            .filter(|it| !it.kind().is_trivia());
+        for token in tokens {
+            let offset = token.text_range().start();
+            if offset < skip_until {
+                continue;
+            }
+            let frange = FileRange { file_id, range: TextRange::empty(offset) };
+            let assists = Assist::get_by_id(db, &config, false, frange, assist_id);
+            let target = match assists.as_slice() {
+                [assist] => assist.target,
+                _ => continue,
+            };
+            skip_until = skip_until.max(target.end());
+            if !seen_targets.insert(target) {
+                continue;
+            }
+            let change = Assist::get_by_id(db, &config, true, frange, assist_id)
+                .into_iter()
+                .find_map(|assist| assist.source_change);
+            if let Some(change) = change {
+                merge(&mut res, &mut imports, change);
+            }
+        }
+    }
+    insert_imports(&sema, &config, &mut res, imports);
+    res
+}
+
+fn files_in_scope(
+    sema: &Semantics<RootDatabase>,
+    file_id: FileId,
+    scope: AssistScope,
+) -> Vec<FileId> {
+    let db = sema.db;
+    let module = match scope {
+        AssistScope::File => return vec![file_id],
+        AssistScope::Module => sema.to_module_def(file_id),
+        AssistScope::Crate => sema.to_module_def(file_id).map(|it| it.krate().root_module(db)),
+    };
+    let mut res = Vec::new();
+    let mut stack: Vec<_> = module.into_iter().collect();
+    while let Some(module) = stack.pop() {
+        let file_id = module.definition_source(db).file_id.original_file(db);
+        if !res.contains(&file_id) {
+            res.push(file_id);
+        }
+        stack.extend(module.children(db));
+    }
+    res
+}
+
+/// Adds `change` to `acc`, unless it overlaps with what is already there.
+///
+/// The `use` items the change inserts are collected in `imports` instead, as
+/// the independent applications would insert the same ones several times.
+fn merge(acc: &mut SourceChange, imports: &mut Vec<Import>, change: SourceChange) {
+    let mut merged = Vec::new();
+    let mut new_imports = Vec::new();
+    for (file_id, edit) in change.source_file_edits {
+        let mut file_imports = Vec::new();
+        let mut others = Vec::new();
+        for indel in edit {
+            match inserted_imports(&indel) {
+                Some(paths) => file_imports.extend(paths.into_iter().map(|path| Import {
+                    file_id,
+                    offset: indel.delete.start(),
+                    path,
+                })),
+                None => others.push(indel),
+            }
+        }
+        let mut rest = TextEdit::builder();
+        for indel in others {
+            // The whitespace around the imports is inserted along with them.
+            let is_import_whitespace = indel.delete.is_empty()
+                && indel.insert.trim().is_empty()
+                && file_imports.iter().any(|it| it.offset == indel.delete.start());
+            if !is_import_whitespace {
+                rest.replace(indel.delete, indel.insert);
+            }
+        }
+        new_imports.extend(file_imports);
+        let mut file_edit = acc.get_source_edit(file_id).cloned().unwrap_or_default();
+        if file_edit.union(rest.finish()).is_err() {
+            return;
+        }
+        merged.push((file_id, file_edit));
+    }
+    acc.source_file_edits.extend(merged);
+    acc.file_system_edits.extend(change.file_system_edits);
+    imports.extend(new_imports);
+}
+
+/// An import one of the applications inserted at `offset`.
+struct Import {
+    file_id: FileId,
+    offset: TextSize,
+    path: ast::Path,
+}
+
+/// The paths of the `use` items `indel` inserts, if that is all it does.
+fn inserted_imports(indel: &Indel) -> Option<Vec<ast::Path>> {
+    if !indel.delete.is_empty() {
+        return None;
+    }
+    let parse = SourceFile::parse(&indel.insert);
+    if !parse.errors().is_empty() {
+        return None;
+    }
+    let items = parse.tree().items().collect::<Vec<_>>();
+    if items.is_empty() {
+        return None;
+    }
+    items
+        .into_iter()
+        .map(|item| match item {
+            ast::Item::Use(it) => {
+                let use_tree = it.use_tree()?;
+                if use_tree.use_tree_list().is_some()
+                    || use_tree.star_token().is_some()
+                    || use_tree.rename().is_some()
+                {
+                    return None;
+                }
+                use_tree.path()
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Inserts each of the distinct `imports` once, into the scope the
+/// applications inserted it into.
+fn insert_imports(
+    sema: &Semantics<RootDatabase>,
+    config: &AssistConfig,
+    acc: &mut SourceChange,
+    imports: Vec<Import>,
+) {
+    let mut by_scope: Vec<(FileId, ImportScope, Vec<ast::Path>)> = Vec::new();
+    for import in imports {
+        let source_file = sema.parse(import.file_id);
+        let node = match source_file.syntax().covering_element(TextRange::empty(import.offset)) {
+            NodeOrToken::Node(it) => it,
+            NodeOrToken::Token(it) => it.parent(),
+        };
+        // An import inserted right before an item list is not inside of it.
+        let scope = sema.ancestors_with_macros(node).filter_map(ImportScope::from).find(|it| {
+            matches!(it, ImportScope::File(_))
+                || it.as_syntax_node().text_range().start() < import.offset
+        });
+        let scope = match scope {
+            Some(it) => it,
+            None => continue,
+        };
+        let paths = match by_scope.iter_mut().find(|(file_id, it, _)| {
+            *file_id == import.file_id && it.as_syntax_node() == scope.as_syntax_node()
+        }) {
+            Some((_, _, paths)) => paths,
+            None => {
+                by_scope.push((import.file_id, scope, Vec::new()));
+                &mut by_scope.last_mut().unwrap().2
+            }
+        };
+        let text = import.path.syntax().text().to_string();
+        if !paths.iter().any(|it| it.syntax().text() == text.as_str()) {
+            paths.push(import.path);
+        }
+    }
+
+    for (file_id, original_scope, paths) in by_scope {
+        // Each import is inserted into the result of the previous one, so that
+        // imports merged into the same `use` item don't conflict.
+        let mut scope = original_scope.clone();
+        for path in paths {
+            let rewriter = insert_use(&scope, path, config.insert_use);
+            scope = match ImportScope::from(rewriter.rewrite(scope.as_syntax_node())) {
+                Some(it) => it,
+                None => break,
+            };
+        }
+        let mut builder = TextEdit::builder();
+        algo::diff(original_scope.as_syntax_node(), scope.as_syntax_node())
+            .into_text_edit(&mut builder);
+        let mut file_edit = acc.get_source_edit(file_id).cloned().unwrap_or_default();
+        if file_edit.union(builder.finish()).is_ok() {
+            acc.source_file_edits.insert(file_id, file_edit);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{expect, Expect};
+    use ide_db::helpers::{insert_use::InsertUseConfig, SnippetCap};
+
+    use crate::fixture;
+
+    use super::*;
+
+    fn check(assist_id: &str, scope: AssistScope, ra_fixture: &str, expect: Expect) {
+        let (analysis, position) = fixture::position(ra_fixture);
+        let config = AssistConfig {
+            snippet_cap: SnippetCap::new(true),
+            allowed: None,
+            insert_use: InsertUseConfig {
+                merge: None,
+                prefix_kind: hir::PrefixKind::Plain,
+                group: true,
+            },
+        };
+        let change =
+            analysis.apply_assist_everywhere(&config, assist_id, position.file_id, scope).unwrap();
+        let mut edits: Vec<_> = change
+            .source_file_edits
+            .into_iter()
+            .map(|(file_id, edit)| {
+                let mut text = analysis.file_text(file_id).unwrap().to_string();
+                edit.apply(&mut text);
+                (file_id, text)
+            })
+            .collect();
+        edits.sort_by_key(|(file_id, _)| *file_id);
+        let actual = edits
+            .into_iter()
+            .map(|(file_id, text)| format!("{:?}\n{}", file_id, text))
+            .collect::<Vec<_>>()
+            .join("\n");
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn applies_in_file() {
+        check(
+            "add_explicit_type",
+            AssistScope::File,
+            r#"
+fn main() {
+    let a = 1u8;$0
+    let b = (a, 2u32);
+    let c: u8 = a;
+}
+"#,
+            expect![[r#"
+                FileId(0)
+                fn main() {
+                    let a: u8 = 1u8;
+                    let b: (u8, u32) = (a, 2u32);
+                    let c: u8 = a;
+                }
+            "#]],
+        );
+    }
+
+    #[test]
+    fn skips_overlapping_applications() {
+        check(
+            "flip_comma",
+            AssistScope::File,
+            r#"
+fn main() {
+    let x = (1, 2, 3);$0
+}
+"#,
+            expect![[r#"
+                FileId(0)
+                fn main() {
+                    let x = (2, 1, 3);
+                }
+            "#]],
+        );
+    }
+
+    #[test]
+    fn imports_each_path_once() {
+        check(
+            "auto_import",
+            AssistScope::File,
+            r#"
+mod m {
+    pub struct Thing;
+    pub struct Other;
+}
+fn f() -> Thing {$0
+    let _: Other = Other;
+    Thing
+}
+"#,
+            expect![[r#"
+                FileId(0)
+                use m::Other;
+                use m::Thing;
+
+                mod m {
+                    pub struct Thing;
+                    pub struct Other;
+                }
+                fn f() -> Thing {
+                    let _: Other = Other;
+                    Thing
+                }
+            "#]],
+        );
+    }
+
+    #[test]
+    fn applies_in_submodules() {
+        check(
+            "add_explicit_type",
+            AssistScope::Crate,
+            r#"
+//- /main.rs
+mod foo;
+fn main() {
+    let a = 1u8;
+}
+//- /foo.rs
+fn f() {
+    let b = 2u32;$0
+}
+"#,
+            expect![[r#"
+                FileId(0)
+                mod foo;
+                fn main() {
+                    let a: u8 = 1u8;
+                }
+
+                FileId(1)
+                fn f() {
+                    let b: u32 = 2u32;
+                }
+            "#]],
+        );
+    }
+}
@@ -0,0 +1,466 @@
+//! Reorders, adds and removes the parameters of a function, rewriting its
+//! callers and the rest of its trait along the way.
+use std::fmt;
+
+use hir::{AsAssocItem, AssocItem, AssocItemContainer, HasSource, Semantics};
+use ide_db::{
+    base_db::{FileId, SourceDatabaseExt},
+    defs::{Definition, NameClass, NameRefClass},
+    search::FileReference,
+    LineIndexDatabase, RootDatabase,
+};
+use itertools::Itertools;
+use rustc_hash::{FxHashMap, FxHashSet};
+use syntax::{
+    algo::find_node_at_range,
+    ast::{self, ArgListOwner},
+    AstNode, TextRange, TextSize,
+};
+use text_edit::TextEdit;
+
+use crate::{FilePosition, SourceChange};
+
+/// A parameter of the changed signature.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignatureParam {
+    /// The parameter currently at this index, not counting `self`.
+    Existing(usize),
+    /// A new parameter, for which callers pass `default`.
+    New { pattern: String, ty: String, default: String },
+}
+
+#[derive(Debug)]
+pub struct ChangeSignatureError(String);
+
+impl fmt::Display for ChangeSignatureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+macro_rules! bail {
+    ($($arg:tt)*) => { return Err(ChangeSignatureError(format!($($arg)*))) }
+}
+
+// Feature: Change Signature
+//
+// Reorders, adds and removes the parameters of the function under the cursor. Calls, including
+// method calls, get their arguments rearranged accordingly and pass the given default for the new
+// parameters. For trait methods, the trait and all of its impls are changed together.
+//
+// Parameters that are removed must not be used by any of the changed functions.
+//
+// This is only available through the `experimental/changeSignature` LSP request.
+pub(crate) fn change_signature(
+    db: &RootDatabase,
+    position: FilePosition,
+    params: &[SignatureParam],
+) -> Result<SourceChange, ChangeSignatureError> {
+    let sema = Semantics::new(db);
+    let func = find_function(&sema, position)?;
+
+    let mut edits: FxHashMap<FileId, Vec<ListEdit>> = FxHashMap::default();
+    let mut not_calls = Vec::new();
+    for func in trait_family(db, func) {
+        let (file_id, source) = fn_source(&sema, func)?;
+        let param_list = match source.param_list() {
+            Some(it) => it,
+            None => continue,
+        };
+        let old_params: Vec<ast::Param> = param_list.params().collect();
+        check_params(&sema, &old_params, params)?;
+
+        let mut pieces: Vec<Piece> = param_list
+            .self_param()
+            .map(|it| Piece::Source(it.syntax().text_range()))
+            .into_iter()
+            .collect();
+        pieces.extend(params.iter().map(|param| match param {
+            SignatureParam::Existing(idx) => Piece::Source(old_params[*idx].syntax().text_range()),
+            SignatureParam::New { pattern, ty, .. } => Piece::Text(format!("{}: {}", pattern, ty)),
+        }));
+        edits
+            .entry(file_id)
+            .or_default()
+            .push(ListEdit { range: param_list.syntax().text_range(), pieces });
+
+        let has_self = func.self_param(db).is_some();
+        for (file_id, references) in Definition::ModuleDef(func.into()).usages(&sema).all() {
+            let file_edits = edits.entry(file_id).or_default();
+            for reference in references {
+                match rewrite_call(&reference, has_self, params) {
+                    // Calls resolving to a trait method and to its impl are found twice.
+                    Some(edit) => {
+                        if file_edits.iter().all(|it| it.range != edit.range) {
+                            file_edits.push(edit);
+                        }
+                    }
+                    None if is_import(&reference) => (),
+                    None => not_calls.push((file_id, reference.range.start())),
+                }
+            }
+        }
+    }
+    if !not_calls.is_empty() {
+        let locations: Vec<String> = not_calls
+            .into_iter()
+            .sorted()
+            .dedup()
+            .map(|(file_id, offset)| {
+                let line_col = db.line_index(file_id).line_col(offset);
+                format!("{:?}:{}:{}", file_id, line_col.line + 1, line_col.col + 1)
+            })
+            .collect();
+        bail!(
+            "Cannot rewrite the uses of `{}` which aren't calls, at {}",
+            func.name(db),
+            locations.join(", ")
+        );
+    }
+
+    let mut source_change = SourceChange::default();
+    for (file_id, mut file_edits) in edits {
+        if file_edits.is_empty() {
+            continue;
+        }
+        file_edits.sort_by_key(|edit| edit.range.start());
+        let text = db.file_text(file_id);
+        let mut builder = TextEdit::builder();
+        let mut offset = TextSize::from(0);
+        for edit in &file_edits {
+            // Lists nested in another list are rendered as part of it.
+            if edit.range.start() < offset {
+                continue;
+            }
+            builder.replace(edit.range, edit.render(&text, &file_edits));
+            offset = edit.range.end();
+        }
+        source_change.insert_source_edit(file_id, builder.finish());
+    }
+    Ok(source_change)
+}
+
+/// An edit replacing a parameter or argument list, parentheses included.
+struct ListEdit {
+    range: TextRange,
+    pieces: Vec<Piece>,
+}
+
+enum Piece {
+    /// An item from the original source.
+    Source(TextRange),
+    /// A new item.
+    Text(String),
+}
+
+impl ListEdit {
+    fn render(&self, text: &str, edits: &[ListEdit]) -> String {
+        let items: Vec<String> = self
+            .pieces
+            .iter()
+            .map(|piece| match piece {
+                Piece::Source(range) => render_range(text, *range, edits),
+                Piece::Text(it) => it.clone(),
+            })
+            .collect();
+        format!("({})", items.join(", "))
+    }
+}
+
+/// Renders the source in `range`, with the edits inside it applied.
+fn render_range(text: &str, range: TextRange, edits: &[ListEdit]) -> String {
+    let mut buf = String::new();
+    let mut offset = range.start();
+    for edit in edits.iter().filter(|edit| range.contains_range(edit.range)) {
+        if edit.range.start() < offset {
+            continue;
+        }
+        buf.push_str(&text[TextRange::new(offset, edit.range.start())]);
+        buf.push_str(&edit.render(text, edits));
+        offset = edit.range.end();
+    }
+    buf.push_str(&text[TextRange::new(offset, range.end())]);
+    buf
+}
+
+fn find_function(
+    sema: &Semantics<RootDatabase>,
+    position: FilePosition,
+) -> Result<hir::Function, ChangeSignatureError> {
+    let source_file = sema.parse(position.file_id);
+    let def = match sema.find_node_at_offset_with_descend(source_file.syntax(), position.offset) {
+        Some(ast::NameLike::Name(name)) => {
+            NameClass::classify(sema, &name).map(|class| class.referenced_or_defined(sema.db))
+        }
+        Some(ast::NameLike::NameRef(name_ref)) => {
+            NameRefClass::classify(sema, &name_ref).map(|class| class.referenced(sema.db))
+        }
+        _ => None,
+    };
+    match def {
+        Some(Definition::ModuleDef(hir::ModuleDef::Function(it))) => Ok(it),
+        _ => bail!("No function found at position"),
+    }
+}
+
+/// The functions sharing a signature with `func`: for a method of a trait or
+/// of a trait impl, the trait's method and its implementations in all impls.
+fn trait_family(db: &RootDatabase, func: hir::Function) -> Vec<hir::Function> {
+    let trait_ = match func.as_assoc_item(db).map(|it| it.container(db)) {
+        Some(AssocItemContainer::Trait(it)) => it,
+        Some(AssocItemContainer::Impl(it)) => match it.trait_(db) {
+            Some(it) => it,
+            None => return vec![func],
+        },
+        None => return vec![func],
+    };
+    let name = func.name(db);
+    let same_name = |item: AssocItem| match item {
+        AssocItem::Function(it) if it.name(db) == name => Some(it),
+        _ => None,
+    };
+    let mut family: Vec<_> = trait_.items(db).into_iter().filter_map(same_name).collect();
+    for impl_ in hir::Impl::all_for_trait(db, trait_) {
+        family.extend(impl_.items(db).into_iter().filter_map(same_name));
+    }
+    family
+}
+
+fn fn_source(
+    sema: &Semantics<RootDatabase>,
+    func: hir::Function,
+) -> Result<(FileId, ast::Fn), ChangeSignatureError> {
+    let name = func.name(sema.db);
+    let src = match func.source(sema.db) {
+        Some(it) => it,
+        None => bail!("Cannot find the source of `{}`", name),
+    };
+    let file_id = src.file_id.original_file(sema.db);
+    let source_file = sema.parse(file_id);
+    match find_node_at_range::<ast::Fn>(source_file.syntax(), src.value.syntax().text_range()) {
+        Some(it) if it.syntax().text() == src.value.syntax().text() => Ok((file_id, it)),
+        _ => bail!("Cannot change the signature of `{}`, as it is defined by a macro", name),
+    }
+}
+
+fn check_params(
+    sema: &Semantics<RootDatabase>,
+    old_params: &[ast::Param],
+    params: &[SignatureParam],
+) -> Result<(), ChangeSignatureError> {
+    let mut kept = FxHashSet::default();
+    for param in params {
+        if let SignatureParam::Existing(idx) = param {
+            if *idx >= old_params.len() {
+                bail!("Parameter index {} is out of bounds", idx);
+            }
+            if !kept.insert(*idx) {
+                bail!("Parameter {} is listed twice", idx);
+            }
+        }
+    }
+    for (idx, param) in old_params.iter().enumerate() {
+        if kept.contains(&idx) {
+            continue;
+        }
+        let pats = param.pat().into_iter().flat_map(|pat| pat.syntax().descendants());
+        for pat in pats.filter_map(ast::IdentPat::cast) {
+            let used = sema
+                .to_def(&pat)
+                .map_or(false, |local| Definition::Local(local).usages(sema).at_least_one());
+            if used {
+                bail!("Cannot remove parameter `{}`, as it is still used", pat);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn is_import(reference: &FileReference) -> bool {
+    reference.name.syntax().ancestors().any(|it| ast::Use::can_cast(it.kind()))
+}
+
+/// Rearranges the arguments of the call to the function referenced at
+/// `reference`. `has_self` tells whether path calls like `S::f(s, ..)` pass
+/// the receiver as their first argument.
+fn rewrite_call(
+    reference: &FileReference,
+    has_self: bool,
+    params: &[SignatureParam],
+) -> Option<ListEdit> {
+    let name_ref = match &reference.name {
+        ast::NameLike::NameRef(it) if it.syntax().text_range() == reference.range => it,
+        _ => return None,
+    };
+    let parent = name_ref.syntax().parent()?;
+    let (arg_list, skip) = match ast::MethodCallExpr::cast(parent) {
+        Some(call) => (call.arg_list()?, 0),
+        None => {
+            let path = name_ref.syntax().ancestors().find_map(ast::PathExpr::cast)?;
+            let call = path.syntax().parent().and_then(ast::CallExpr::cast)?;
+            if call.expr()?.syntax() != path.syntax() {
+                return None;
+            }
+            (call.arg_list()?, if has_self { 1 } else { 0 })
+        }
+    };
+
+    let args: Vec<ast::Expr> = arg_list.args().collect();
+    let mut pieces: Vec<Piece> =
+        args.iter().take(skip).map(|arg| Piece::Source(arg.syntax().text_range())).collect();
+    for param in params {
+        let piece = match param {
+            SignatureParam::Existing(idx) => {
+                Piece::Source(args.get(skip + idx)?.syntax().text_range())
+            }
+            SignatureParam::New { default, .. } => Piece::Text(default.clone()),
+        };
+        pieces.push(piece);
+    }
+    Some(ListEdit { range: arg_list.syntax().text_range(), pieces })
+}
+
+#[cfg(test)]
+mod tests {
+    use stdx::trim_indent;
+    use test_utils::assert_eq_text;
+
+    use crate::fixture;
+
+    use super::SignatureParam::{self, Existing, New};
+
+    fn check(params: &[SignatureParam], ra_fixture_before: &str, ra_fixture_after: &str) {
+        let ra_fixture_after = &trim_indent(ra_fixture_after);
+        let (analysis, position) = fixture::position(ra_fixture_before);
+        let result = analysis.change_signature(position, params).unwrap();
+        match result {
+            Ok(source_change) => {
+                let mut result = analysis.file_text(position.file_id).unwrap().to_string();
+                for (_, edit) in source_change.source_file_edits {
+                    edit.apply(&mut result);
+                }
+                assert_eq_text!(ra_fixture_after, &*result);
+            }
+            Err(err) => {
+                let expected = ra_fixture_after
+                    .strip_prefix("error:")
+                    .unwrap_or_else(|| panic!("Change signature failed unexpectedly: {}", err));
+                assert_eq!(expected.trim(), err.to_string());
+            }
+        }
+    }
+
+    fn new(pattern: &str, ty: &str, default: &str) -> SignatureParam {
+        New { pattern: pattern.to_string(), ty: ty.to_string(), default: default.to_string() }
+    }
+
+    #[test]
+    fn reorders_and_adds_params() {
+        check(
+            &[Existing(1), new("flag", "bool", "false"), Existing(0)],
+            r#"
+fn foo$0(a: i32, b: u8) {}
+fn main() {
+    foo(1, 2);
+    foo(foo(1, 2), 3);
+}
+"#,
+            r#"
+fn foo(b: u8, flag: bool, a: i32) {}
+fn main() {
+    foo(2, false, 1);
+    foo(3, false, foo(2, false, 1));
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn removes_unused_param_from_method_calls() {
+        check(
+            &[Existing(1)],
+            r#"
+struct S;
+impl S {
+    fn f(&self, _unused: i32, b: i32) -> i32 { b }
+}
+fn main() {
+    S.f$0(1, 2);
+    S::f(&S, 1, 2);
+}
+"#,
+            r#"
+struct S;
+impl S {
+    fn f(&self, b: i32) -> i32 { b }
+}
+fn main() {
+    S.f(2);
+    S::f(&S, 2);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn changes_trait_and_impls_together() {
+        check(
+            &[Existing(1), Existing(0)],
+            r#"
+trait Tr {
+    fn f(&self, a: i32, b: bool);
+}
+struct S;
+impl Tr for S {
+    fn f$0(&self, a: i32, b: bool) {}
+}
+fn g(t: &dyn Tr) {
+    t.f(1, true);
+    S.f(2, false);
+}
+"#,
+            r#"
+trait Tr {
+    fn f(&self, b: bool, a: i32);
+}
+struct S;
+impl Tr for S {
+    fn f(&self, b: bool, a: i32) {}
+}
+fn g(t: &dyn Tr) {
+    t.f(true, 1);
+    S.f(false, 2);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn refuses_to_rewrite_uses_which_are_not_calls() {
+        check(
+            &[],
+            r#"
+mod m {
+    pub fn foo$0(_a: i32) {}
+}
+use m::foo;
+fn main() {
+    foo(1);
+    let f = foo;
+}
+"#,
+            "error: Cannot rewrite the uses of `foo` which aren't calls, at FileId(0):7:13",
+        );
+    }
+
+    #[test]
+    fn refuses_to_remove_used_param() {
+        check(
+            &[],
+            r#"
+fn foo$0(a: i32) -> i32 { a }
+"#,
+            "error: Cannot remove parameter `a`, as it is still used",
+        );
+    }
+}
@@ -168,6 +168,7 @@ impl ToNav for FileSymbol {
             file_id: self.file_id,
             name: self.name.clone(),
             kind: Some(match self.kind {
+                FileSymbolKind::Field => SymbolKind::Field,
                 FileSymbolKind::Function => SymbolKind::Function,
                 FileSymbolKind::Struct => SymbolKind::Struct,
                 FileSymbolKind::Enum => SymbolKind::Enum,
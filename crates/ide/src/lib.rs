@@ -23,7 +23,9 @@ mod prime_caches;
 mod display;
 
 mod annotations;
+mod apply_assist_everywhere;
 mod call_hierarchy;
+mod change_signature;
 mod diagnostics;
 mod expand_macro;
 mod extend_selection;
@@ -67,7 +69,9 @@ use crate::display::ToNav;
 
 pub use crate::{
     annotations::{Annotation, AnnotationConfig, AnnotationKind},
+    apply_assist_everywhere::AssistScope,
     call_hierarchy::CallItem,
+    change_signature::{ChangeSignatureError, SignatureParam},
     diagnostics::{Diagnostic, DiagnosticsConfig, Fix, Severity},
     display::navigation_target::NavigationTarget,
     expand_macro::ExpandedMacro,
@@ -88,7 +92,7 @@ pub use hir::{Documentation, Semantics};
 pub use ide_assists::{Assist, AssistConfig, AssistId, AssistKind};
 pub use ide_completion::{
     CompletionConfig, CompletionItem, CompletionItemKind, CompletionRelevance, ImportEdit,
-    InsertTextFormat,
+    InsertTextFormat, Snippet, SnippetScope,
 };
 pub use ide_db::{
     base_db::{
@@ -100,7 +104,7 @@ pub use ide_db::{
     line_index::{LineCol, LineColUtf16, LineIndex},
     search::{ReferenceAccess, SearchScope},
     source_change::{FileSystemEdit, SourceChange},
-    symbol_index::Query,
+    symbol_index::{FileSymbolKind, Query},
     RootDatabase, SymbolKind,
 };
 pub use ide_ssr::SsrError;
@@ -210,6 +214,7 @@ impl Analysis {
             file_id,
             Edition::Edition2018,
             None,
+            cfg_options.clone(),
             cfg_options,
             Env::default(),
             Default::default(),
@@ -520,6 +525,20 @@ impl Analysis {
         })
     }
 
+    /// Applies the assist with the given id everywhere it is applicable in
+    /// `scope`, which is relative to `file_id`.
+    pub fn apply_assist_everywhere(
+        &self,
+        config: &AssistConfig,
+        assist_id: &str,
+        file_id: FileId,
+        scope: AssistScope,
+    ) -> Cancelable<SourceChange> {
+        self.with_db(|db| {
+            apply_assist_everywhere::apply_assist_everywhere(db, config, assist_id, file_id, scope)
+        })
+    }
+
     /// Computes the set of diagnostics for the given file.
     pub fn diagnostics(
         &self,
@@ -554,6 +573,14 @@ impl Analysis {
         self.with_db(|db| references::rename::will_rename_file(db, file_id, new_name_stem))
     }
 
+    pub fn change_signature(
+        &self,
+        position: FilePosition,
+        params: &[SignatureParam],
+    ) -> Cancelable<Result<SourceChange, ChangeSignatureError>> {
+        self.with_db(|db| change_signature::change_signature(db, position, params))
+    }
+
     pub fn structural_search_replace(
         &self,
         query: &str,
@@ -77,6 +77,7 @@ impl<'a> AstTransform<'a> for Or<'a> {
 pub struct SubstituteTypeParams<'a> {
     source_scope: &'a SemanticsScope<'a>,
     substs: FxHashMap<hir::TypeParam, ast::Type>,
+    self_ty: Option<ast::Type>,
 }
 
 impl<'a> SubstituteTypeParams<'a> {
@@ -115,7 +116,7 @@ impl<'a> SubstituteTypeParams<'a> {
                 }
             })
             .collect();
-        return SubstituteTypeParams { source_scope, substs: substs_by_param };
+        return SubstituteTypeParams { source_scope, substs: substs_by_param, self_ty: None };
 
         // FIXME: It would probably be nicer if we could get this via HIR (i.e. get the
         // trait ref, and then go from the types in the substs back to the syntax).
@@ -142,6 +143,51 @@ impl<'a> SubstituteTypeParams<'a> {
     }
 }
 
+impl<'a> SubstituteTypeParams<'a> {
+    /// Substitutes the type parameters of an inherent impl, as well as `Self`,
+    /// for the ones of `self_ty`, a concrete instance of the impl's self type.
+    pub fn for_inherent_impl(
+        source_scope: &'a SemanticsScope<'a>,
+        impl_def: &ast::Impl,
+        self_ty: ast::Type,
+    ) -> SubstituteTypeParams<'a> {
+        let params = impl_def.self_ty().map(|ty| generic_args(&ty)).unwrap_or_default();
+        let substs = params
+            .into_iter()
+            .zip(generic_args(&self_ty))
+            .filter_map(|(param, arg)| {
+                let path = match param {
+                    ast::Type::PathType(it) => it.path()?,
+                    _ => return None,
+                };
+                match source_scope.speculative_resolve(&path)? {
+                    PathResolution::TypeParam(param) => Some((param, arg)),
+                    _ => None,
+                }
+            })
+            .collect();
+        return SubstituteTypeParams { source_scope, substs, self_ty: Some(self_ty) };
+
+        fn generic_args(ty: &ast::Type) -> Vec<ast::Type> {
+            let path = match ty {
+                ast::Type::PathType(it) => it.path(),
+                _ => None,
+            };
+            let generic_arg_list = match path.and_then(|it| it.segment()?.generic_arg_list()) {
+                Some(it) => it,
+                None => return Vec::new(),
+            };
+            generic_arg_list
+                .generic_args()
+                .filter_map(|arg| match arg {
+                    ast::GenericArg::TypeArg(it) => it.ty(),
+                    _ => None,
+                })
+                .collect()
+        }
+    }
+}
+
 impl<'a> AstTransform<'a> for SubstituteTypeParams<'a> {
     fn get_substitution(
         &self,
@@ -156,6 +202,7 @@ impl<'a> AstTransform<'a> for SubstituteTypeParams<'a> {
         let resolution = self.source_scope.speculative_resolve(&path)?;
         match resolution {
             hir::PathResolution::TypeParam(tp) => Some(self.substs.get(&tp)?.syntax().clone()),
+            hir::PathResolution::SelfType(_) => Some(self.self_ty.as_ref()?.syntax().clone()),
             _ => None,
         }
     }
@@ -0,0 +1,583 @@
+use either::Either;
+use hir::{Access, HirDisplay, Local, ModuleDef};
+use ide_db::defs::{Definition, NameRefClass};
+use itertools::Itertools;
+use stdx::format_to;
+use syntax::{
+    ast::{self, edit::IndentLevel, AstNode, GenericParamsOwner, NameOwner},
+    SyntaxKind::{BLOCK_EXPR, ITEM_LIST, SOURCE_FILE},
+    TextRange,
+};
+
+use crate::{
+    utils::{
+        extract_trivial_expression, range_with_surrounding_whitespace, reindent, usage_access,
+    },
+    AssistContext, AssistId, AssistKind, Assists, GroupLabel,
+};
+
+// Assist: convert_closure_to_fn
+//
+// Converts a closure into a named function, passing the captured variables
+// explicitly.
+//
+// ```
+// # #[lang = "copy"] pub trait Copy {}
+// # impl Copy for u32 {}
+// fn main() {
+//     let step = 2;
+//     let add = |$0x: u32| x + step;
+// }
+// ```
+// ->
+// ```
+// # #[lang = "copy"] pub trait Copy {}
+// # impl Copy for u32 {}
+// fn main() {
+//     let step = 2;
+//     fn $0fun_name(x: u32, step: u32) -> u32 {
+//         x + step
+//     }
+//
+//     let add = |x| fun_name(x, step);
+// }
+// ```
+pub(crate) fn convert_closure_to_fn(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let closure = ctx.find_node_at_offset::<ast::ClosureExpr>()?;
+    let param_list = closure.param_list()?;
+    let head = TextRange::new(
+        closure.syntax().text_range().start(),
+        param_list.syntax().text_range().end(),
+    );
+    if !head.contains_inclusive(ctx.offset()) || closure.async_token().is_some() {
+        return None;
+    }
+    let body = closure.body()?;
+    let db = ctx.db();
+    let module = ctx.sema.scope(closure.syntax()).module()?;
+    let callable = ctx.sema.type_of_expr(&closure.clone().into())?.as_callable(db)?;
+
+    let mut params = Vec::new();
+    let mut args = Vec::new();
+    for (idx, (param, (_, ty))) in param_list.params().zip(callable.params(db)).enumerate() {
+        let pat = param.pat()?;
+        let ty = match param.ty() {
+            Some(ty) => ty.to_string(),
+            None => ty.display_source_code(db, module.into()).ok()?,
+        };
+        params.push(format!("{}: {}", pat, ty));
+        args.push(match pat {
+            ast::Pat::IdentPat(it) if it.ref_token().is_none() => it.name()?.to_string(),
+            _ => format!("arg{}", idx),
+        });
+    }
+    let ret_type = match closure.ret_type() {
+        Some(it) => Some(it.to_string()),
+        None if callable.return_type().is_unit() => None,
+        None => Some(format!(
+            "-> {}",
+            callable.return_type().display_source_code(db, module.into()).ok()?
+        )),
+    };
+
+    let captures = captures(ctx, &closure, &body)?;
+    let mut edits = Vec::new();
+    for capture in &captures {
+        let ty = capture.local.ty(db).display_source_code(db, module.into()).ok()?;
+        let mut_ = if capture.needs_mut() { "mut " } else { "" };
+        match capture.kind {
+            CaptureKind::Value => params.push(format!("{}{}: {}", mut_, capture.name, ty)),
+            CaptureKind::SharedRef => params.push(format!("{}: &{}", capture.name, ty)),
+            CaptureKind::MutRef => params.push(format!("{}: &mut {}", capture.name, ty)),
+        }
+        args.push(format!("{}{}", capture.kind.prefix(), capture.name));
+        capture.deref_usages(&mut edits);
+    }
+
+    let closure_range = closure.syntax().text_range();
+    let mut body_text = body.syntax().to_string();
+    edits.sort_by_key(|(range, _)| range.start());
+    for (range, replacement) in edits.into_iter().rev() {
+        let range = range - body.syntax().text_range().start();
+        body_text.replace_range(std::ops::Range::<usize>::from(range), &replacement);
+    }
+    let closure_indent = IndentLevel::from_node(closure.syntax());
+
+    let call = if captures.is_empty() {
+        "fun_name".to_string()
+    } else {
+        let move_ = if closure.move_token().is_some() { "move " } else { "" };
+        let params = args.iter().take(param_list.params().count()).join(", ");
+        format!("{}|{}| fun_name({})", move_, params, args.iter().join(", "))
+    };
+    let render_fn = |indent: IndentLevel, name: &str| {
+        let mut buf = format!("fn {}({})", name, params.iter().join(", "));
+        if let Some(ret_type) = &ret_type {
+            format_to!(buf, " {}", ret_type);
+        }
+        match &body {
+            ast::Expr::BlockExpr(_) => {
+                format_to!(buf, " {}", reindent(&body_text, closure_indent, indent))
+            }
+            _ => format_to!(
+                buf,
+                " {{\n{}{}\n{}}}",
+                indent + 1,
+                reindent(&body_text, closure_indent, indent + 1),
+                indent
+            ),
+        }
+        buf
+    };
+    let fn_name = |cap: bool| if cap { "$0fun_name" } else { "fun_name" };
+
+    let group = GroupLabel("Convert closure to function".to_string());
+    if let Some(stmt) = closure
+        .syntax()
+        .ancestors()
+        .find(|it| it.parent().map_or(false, |it| it.kind() == BLOCK_EXPR))
+    {
+        acc.add_group(
+            &group,
+            AssistId("convert_closure_to_fn", AssistKind::RefactorRewrite),
+            "Convert closure to nested function",
+            closure_range,
+            |builder| {
+                let indent = IndentLevel::from_node(&stmt);
+                let offset = stmt.text_range().start();
+                builder.replace(closure_range, call.clone());
+                let text = |cap| format!("{}\n\n{}", render_fn(indent, fn_name(cap)), indent);
+                match ctx.config.snippet_cap {
+                    Some(cap) => builder.insert_snippet(cap, offset, text(true)),
+                    None => builder.insert(offset, text(false)),
+                }
+            },
+        );
+    }
+    let item = closure
+        .syntax()
+        .ancestors()
+        .find(|it| it.parent().map_or(false, |it| matches!(it.kind(), SOURCE_FILE | ITEM_LIST)))?;
+    acc.add_group(
+        &group,
+        AssistId("convert_closure_to_fn", AssistKind::RefactorRewrite),
+        "Convert closure to function",
+        closure_range,
+        |builder| {
+            let indent = IndentLevel::from_node(&item);
+            let offset = item.text_range().end();
+            builder.replace(closure_range, call.clone());
+            let text = |cap| format!("\n\n{}{}", indent, render_fn(indent, fn_name(cap)));
+            match ctx.config.snippet_cap {
+                Some(cap) => builder.insert_snippet(cap, offset, text(true)),
+                None => builder.insert(offset, text(false)),
+            }
+        },
+    )
+}
+
+// Assist: convert_fn_to_closure
+//
+// Inlines a local function into a closure at its only use.
+//
+// ```
+// fn main() {
+//     fn $0double(x: u32) -> u32 {
+//         x * 2
+//     }
+//     let v: Vec<u32> = vec![1, 2].into_iter().map(double).collect();
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     let v: Vec<u32> = vec![1, 2].into_iter().map(|x: u32| -> u32 {
+//         x * 2
+//     }).collect();
+// }
+// ```
+pub(crate) fn convert_fn_to_closure(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let name = ctx.find_node_at_offset::<ast::Name>()?;
+    let func = name.syntax().parent().and_then(ast::Fn::cast)?;
+    if func.syntax().parent()?.kind() != BLOCK_EXPR {
+        return None;
+    }
+    if func.generic_param_list().is_some()
+        || func.async_token().is_some()
+        || func.const_token().is_some()
+        || func.unsafe_token().is_some()
+        || func.abi().is_some()
+    {
+        return None;
+    }
+    let param_list = func.param_list()?;
+    if param_list.self_param().is_some() {
+        return None;
+    }
+    let body = func.body()?;
+
+    let def = ctx.sema.to_def(&func)?;
+    let usages = Definition::ModuleDef(ModuleDef::Function(def)).usages(&ctx.sema).all();
+    let mut references =
+        usages.into_iter().flat_map(|(file_id, refs)| refs.into_iter().map(move |r| (file_id, r)));
+    let (file_id, reference) = match (references.next(), references.next()) {
+        (Some(it), None) => it,
+        _ => {
+            cov_mark::hit!(convert_fn_to_closure_not_single_use);
+            return None;
+        }
+    };
+    let name_ref = match reference.name {
+        ast::NameLike::NameRef(it) if file_id == ctx.frange.file_id => it,
+        _ => return None,
+    };
+    let path = name_ref.syntax().parent().and_then(ast::PathSegment::cast)?.parent_path();
+    if path.qualifier().is_some() {
+        return None;
+    }
+    let path_expr = path.syntax().parent().and_then(ast::PathExpr::cast)?;
+    let is_callee = path_expr
+        .syntax()
+        .parent()
+        .and_then(ast::CallExpr::cast)
+        .and_then(|call| call.expr())
+        .map_or(false, |callee| callee.syntax() == path_expr.syntax());
+    if is_callee && path_expr.syntax().text_range().start() < func.syntax().text_range().start() {
+        return None;
+    }
+
+    let fn_range = func.syntax().text_range();
+    acc.add(
+        AssistId("convert_fn_to_closure", AssistKind::RefactorInline),
+        "Convert function to closure",
+        fn_range,
+        |builder| {
+            let indent = if is_callee {
+                IndentLevel::from_node(func.syntax())
+            } else {
+                IndentLevel::from_node(path_expr.syntax())
+            };
+            let params = param_list.params().map(|param| param.syntax().to_string()).join(", ");
+            let mut closure = format!("|{}|", params);
+            match (func.ret_type(), extract_trivial_expression(&body)) {
+                (None, Some(expr)) => {
+                    let expr = reindent(
+                        &expr.syntax().to_string(),
+                        IndentLevel::from_node(func.syntax()) + 1,
+                        indent,
+                    );
+                    format_to!(closure, " {}", expr);
+                }
+                (ret_type, _) => {
+                    if let Some(ret_type) = ret_type {
+                        format_to!(closure, " {}", ret_type);
+                    }
+                    let body = reindent(
+                        &body.syntax().to_string(),
+                        IndentLevel::from_node(func.syntax()),
+                        indent,
+                    );
+                    format_to!(closure, " {}", body);
+                }
+            }
+
+            if is_callee {
+                builder.replace(fn_range, format!("let {} = {};", name, closure));
+            } else {
+                builder.delete(range_with_surrounding_whitespace(func.syntax()));
+                builder.replace(path_expr.syntax().text_range(), closure);
+            }
+        },
+    )
+}
+
+/// How a captured variable is passed to the function.
+#[derive(Clone, Copy, PartialEq)]
+enum CaptureKind {
+    Value,
+    SharedRef,
+    MutRef,
+}
+
+impl CaptureKind {
+    fn prefix(self) -> &'static str {
+        match self {
+            CaptureKind::Value => "",
+            CaptureKind::SharedRef => "&",
+            CaptureKind::MutRef => "&mut ",
+        }
+    }
+}
+
+struct Capture {
+    local: Local,
+    name: String,
+    kind: CaptureKind,
+    usages: Vec<Usage>,
+}
+
+enum Usage {
+    Path(ast::PathExpr, Access),
+    /// `S { x }`, which uses `x` by value.
+    Shorthand(ast::NameRef),
+}
+
+impl Capture {
+    fn needs_mut(&self) -> bool {
+        self.kind == CaptureKind::Value
+            && self.usages.iter().any(|it| matches!(it, Usage::Path(_, Access::Exclusive)))
+    }
+
+    /// Dereferences the usages of a capture that became a reference.
+    fn deref_usages(&self, edits: &mut Vec<(TextRange, String)>) {
+        if self.kind == CaptureKind::Value {
+            return;
+        }
+        for usage in &self.usages {
+            let path_expr = match usage {
+                Usage::Path(it, _) => it,
+                Usage::Shorthand(name_ref) => {
+                    let replacement = format!("{}: *{}", self.name, self.name);
+                    edits.push((name_ref.syntax().text_range(), replacement));
+                    continue;
+                }
+            };
+            match path_expr.syntax().parent().and_then(ast::Expr::cast) {
+                Some(ast::Expr::MethodCallExpr(_)) | Some(ast::Expr::FieldExpr(_)) => (),
+                Some(ast::Expr::RefExpr(ref_expr))
+                    if ref_expr.mut_token().is_some() == (self.kind == CaptureKind::MutRef) =>
+                {
+                    edits.push((ref_expr.syntax().text_range(), self.name.clone()));
+                }
+                _ => edits.push((path_expr.syntax().text_range(), format!("*{}", self.name))),
+            }
+        }
+    }
+}
+
+/// Finds the variables declared outside of the closure that its body uses.
+fn captures(
+    ctx: &AssistContext,
+    closure: &ast::ClosureExpr,
+    body: &ast::Expr,
+) -> Option<Vec<Capture>> {
+    let db = ctx.db();
+    let mut captures: Vec<Capture> = Vec::new();
+    for name_ref in body.syntax().descendants().filter_map(ast::NameRef::cast) {
+        let (local, usage) = match NameRefClass::classify(&ctx.sema, &name_ref) {
+            Some(NameRefClass::FieldShorthand { local_ref, .. }) => {
+                (local_ref, Usage::Shorthand(name_ref))
+            }
+            Some(NameRefClass::Definition(Definition::Local(local))) => {
+                let path_expr =
+                    name_ref.syntax().ancestors().nth(3).and_then(ast::PathExpr::cast)?;
+                let access = usage_access(ctx, &path_expr.clone().into());
+                (local, Usage::Path(path_expr, access))
+            }
+            _ => continue,
+        };
+        let src = local.source(db);
+        let decl = match &src.value {
+            Either::Left(pat) => pat.syntax().text_range(),
+            Either::Right(_) => {
+                cov_mark::hit!(convert_closure_to_fn_self_capture);
+                return None;
+            }
+        };
+        if closure.syntax().text_range().contains_range(decl) {
+            continue;
+        }
+        match captures.iter_mut().find(|it| it.local == local) {
+            Some(capture) => capture.usages.push(usage),
+            None => captures.push(Capture {
+                local,
+                name: local.name(db)?.to_string(),
+                kind: CaptureKind::SharedRef,
+                usages: vec![usage],
+            }),
+        }
+    }
+
+    for capture in &mut captures {
+        let is_copy = capture.local.ty(db).is_copy(db);
+        let accesses = capture.usages.iter().map(|usage| match usage {
+            Usage::Path(_, access) => *access,
+            Usage::Shorthand(_) => Access::Owned,
+        });
+        let (mut owned, mut exclusive) = (false, false);
+        for access in accesses {
+            owned |= access == Access::Owned;
+            exclusive |= access == Access::Exclusive;
+        }
+        capture.kind = if owned && !is_copy {
+            CaptureKind::Value
+        } else if exclusive {
+            CaptureKind::MutRef
+        } else if is_copy {
+            CaptureKind::Value
+        } else {
+            CaptureKind::SharedRef
+        };
+    }
+    Some(captures)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_by_label, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn closure_without_captures_to_module_fn() {
+        check_assist_by_label(
+            convert_closure_to_fn,
+            r#"
+fn main() {
+    let f = $0|x: i32| {
+        let y = x + 1;
+        y * 2
+    };
+}
+"#,
+            r#"
+fn main() {
+    let f = fun_name;
+}
+
+fn $0fun_name(x: i32) -> i32 {
+    let y = x + 1;
+    y * 2
+}
+"#,
+            "Convert closure to function",
+        );
+    }
+
+    #[test]
+    fn closure_with_captures_by_reference() {
+        check_assist_by_label(
+            convert_closure_to_fn,
+            r#"
+struct Names;
+impl Names {
+    fn len(&self) -> usize { 0 }
+    fn push(&mut self, n: usize) {}
+}
+
+fn main() {
+    let names = Names;
+    let mut seen = Names;
+    let mut count = 0;
+    let mut f = $0|n| {
+        count += 1;
+        seen.push(n);
+        names.len() + n
+    };
+    f(1usize);
+}
+"#,
+            r#"
+struct Names;
+impl Names {
+    fn len(&self) -> usize { 0 }
+    fn push(&mut self, n: usize) {}
+}
+
+fn main() {
+    let names = Names;
+    let mut seen = Names;
+    let mut count = 0;
+    fn $0fun_name(n: usize, count: &mut i32, seen: &mut Names, names: &Names) -> usize {
+        *count += 1;
+        seen.push(n);
+        names.len() + n
+    }
+
+    let mut f = |n| fun_name(n, &mut count, &mut seen, &names);
+    f(1usize);
+}
+"#,
+            "Convert closure to nested function",
+        );
+    }
+
+    #[test]
+    fn closure_capturing_self_is_not_applicable() {
+        cov_mark::check!(convert_closure_to_fn_self_capture);
+        check_assist_not_applicable(
+            convert_closure_to_fn,
+            r#"
+struct S(i32);
+impl S {
+    fn f(&self) {
+        let g = $0|| self.0;
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn fn_passed_as_value_to_closure() {
+        check_assist(
+            convert_fn_to_closure,
+            r#"
+fn apply(f: impl Fn(i32) -> i32) {}
+
+fn main() {
+    fn $0inc(x: i32) -> i32 {
+        x + 1
+    }
+    apply(inc);
+}
+"#,
+            r#"
+fn apply(f: impl Fn(i32) -> i32) {}
+
+fn main() {
+    apply(|x: i32| -> i32 {
+        x + 1
+    });
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn called_fn_to_closure_binding() {
+        check_assist(
+            convert_fn_to_closure,
+            r#"
+fn main() {
+    fn $0inc(x: i32) {
+        x + 1;
+    }
+    inc(1);
+}
+"#,
+            r#"
+fn main() {
+    let inc = |x: i32| {
+        x + 1;
+    };
+    inc(1);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn fn_used_twice_is_not_applicable() {
+        cov_mark::check!(convert_fn_to_closure_not_single_use);
+        check_assist_not_applicable(
+            convert_fn_to_closure,
+            r#"
+fn main() {
+    fn $0inc(x: i32) -> i32 { x + 1 }
+    inc(inc(1));
+}
+"#,
+        );
+    }
+}
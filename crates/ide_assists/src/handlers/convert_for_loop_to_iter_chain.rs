@@ -0,0 +1,918 @@
+use ast::LoopBodyOwner;
+use hir::{Access, HirDisplay, PathResolution};
+use ide_db::{defs::Definition, helpers::FamousDefs, RootDatabase};
+use stdx::format_to;
+use syntax::{
+    ast::{self, edit::IndentLevel, ArgListOwner, AstNode, NameOwner},
+    SyntaxKind::EXPR_STMT,
+    TextRange,
+};
+
+use crate::{
+    utils::{impls_core_iter, is_ref_and_impls_iter_method, reindent, usage_access},
+    AssistContext, AssistId, AssistKind, Assists,
+};
+
+// Assist: convert_for_loop_to_iter_chain
+//
+// Converts a `for` loop filling a collection or computing a sum or a count
+// into a chain of iterator adapters.
+//
+// ```
+// # //- /lib.rs crate:core
+// # pub mod iter { pub mod traits { pub mod iterator { pub trait Iterator {} } } }
+// # pub struct SomeIter;
+// # impl self::iter::traits::iterator::Iterator for SomeIter {}
+// # //- /lib.rs crate:main deps:core
+// # use core::SomeIter;
+// fn main() {
+//     let mut doubled = Vec::new();
+//     for$0 x in SomeIter {
+//         doubled.push(x * 2);
+//     }
+// }
+// ```
+// ->
+// ```
+// # use core::SomeIter;
+// fn main() {
+//     let doubled: Vec<_> = SomeIter.map(|x| x * 2).collect();
+// }
+// ```
+pub(crate) fn convert_for_loop_to_iter_chain(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let for_loop = ctx.find_node_at_offset::<ast::ForExpr>()?;
+    let body = for_loop.loop_body()?;
+    if body.syntax().text_range().start() < ctx.offset() {
+        cov_mark::hit!(for_loop_to_iter_chain_in_body);
+        return None;
+    }
+    let pat = for_loop.pat()?;
+    let iter = match iterator_of(&ctx.sema, &for_loop.iterable()?) {
+        Some(it) => it,
+        None => {
+            cov_mark::hit!(for_loop_to_iter_chain_not_iterable);
+            return None;
+        }
+    };
+    let mut fold = LoopFold::new(ctx, &body)?;
+    let db = ctx.db();
+    let module = ctx.sema.scope(for_loop.syntax()).module()?;
+    // The type of a collected accumulator is left to inference.
+    let acc_ty_text = match fold.kind {
+        FoldKind::Push(_) => String::new(),
+        _ => fold.local.ty(db).display_source_code(db, module.into()).ok()?,
+    };
+
+    let mut chain = iter;
+    if let Some(cond) = &fold.cond {
+        let item_is_copy = ctx.sema.type_of_pat(&pat).map_or(false, |ty| ty.is_copy(db));
+        let (param, cond) = match &pat {
+            ast::Pat::IdentPat(_) | ast::Pat::TuplePat(_) if item_is_copy => {
+                (format!("&{}", pat), cond.to_string())
+            }
+            ast::Pat::IdentPat(ident) => {
+                (pat.to_string(), adjust_item_uses(ctx, ident, cond, true))
+            }
+            _ => (pat.to_string(), cond.to_string()),
+        };
+        format_to!(chain, ".filter(|{}| {})", param, cond);
+    }
+    if let FoldKind::Count(one) = &fold.kind {
+        if acc_ty_text != "usize" {
+            fold.kind = FoldKind::Sum(one.clone());
+        }
+    }
+    match &fold.kind {
+        FoldKind::Push(expr) | FoldKind::Sum(expr)
+            if expr.syntax().text() != pat.syntax().text() =>
+        {
+            format_to!(chain, ".map(|{}| {})", pat, expr)
+        }
+        _ => (),
+    }
+
+    let for_stmt = for_loop
+        .syntax()
+        .parent()
+        .filter(|it| it.kind() == EXPR_STMT)
+        .unwrap_or_else(|| for_loop.syntax().clone());
+    let init = for_stmt.prev_sibling().and_then(ast::LetStmt::cast).filter(|let_stmt| {
+        let declares_acc = match let_stmt.pat() {
+            Some(ast::Pat::IdentPat(it)) => ctx.sema.to_def(&it) == Some(fold.local),
+            _ => false,
+        };
+        declares_acc && let_stmt.initializer().map_or(false, |init| fold.kind.is_initial(&init))
+    });
+
+    let (range, replacement) = match init {
+        Some(let_stmt) => {
+            let pat = match let_stmt.pat()? {
+                ast::Pat::IdentPat(it) if !mutated_after(ctx, &fold, for_stmt.text_range()) => {
+                    it.name()?.to_string()
+                }
+                pat => pat.to_string(),
+            };
+            let ty = match (let_stmt.ty(), &fold.kind) {
+                (Some(ty), _) => format!(": {}", ty),
+                (None, FoldKind::Push(_)) => ": Vec<_>".to_string(),
+                (None, FoldKind::Sum(_)) => format!(": {}", acc_ty_text),
+                (None, FoldKind::Count(_)) => String::new(),
+            };
+            let range = let_stmt.syntax().text_range().cover(for_stmt.text_range());
+            (range, format!("let {}{} = {}.{}();", pat, ty, chain, fold.kind.terminal()))
+        }
+        None => {
+            let replacement = match fold.kind {
+                FoldKind::Push(_) => format!("{}.extend({});", fold.acc_name, chain),
+                FoldKind::Sum(_) => {
+                    format!("{} += {}.sum::<{}>();", fold.acc_name, chain, acc_ty_text)
+                }
+                FoldKind::Count(_) => format!("{} += {}.count();", fold.acc_name, chain),
+            };
+            (for_stmt.text_range(), replacement)
+        }
+    };
+
+    acc.add(
+        AssistId("convert_for_loop_to_iter_chain", AssistKind::RefactorRewrite),
+        "Convert for loop to iterator chain",
+        for_loop.syntax().text_range(),
+        |builder| builder.replace(range, replacement),
+    )
+}
+
+// Assist: convert_iter_chain_to_for_loop
+//
+// Expands a chain of `filter` and `map` adapters ending in `collect`, `sum`,
+// `count` or `for_each` into an explicit `for` loop.
+//
+// ```
+// # //- /lib.rs crate:core
+// # pub mod iter { pub mod traits { pub mod iterator { pub trait Iterator {} } } }
+// # pub struct SomeIter;
+// # impl self::iter::traits::iterator::Iterator for SomeIter {}
+// # //- /alloc.rs crate:alloc
+// # pub mod vec { pub struct Vec<T>(T); }
+// # //- /lib.rs crate:main deps:core,alloc
+// # use alloc::vec::Vec;
+// # use core::SomeIter;
+// fn main() {
+//     let doubled: Vec<i32> = SomeIter.map(|x| x * 2).coll$0ect();
+// }
+// ```
+// ->
+// ```
+// # use alloc::vec::Vec;
+// # use core::SomeIter;
+// fn main() {
+//     let mut doubled: Vec<i32> = Vec::new();
+//     for x in SomeIter {
+//         let x = x * 2;
+//         doubled.push(x);
+//     }
+// }
+// ```
+pub(crate) fn convert_iter_chain_to_for_loop(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let terminal = ctx.find_node_at_offset::<ast::MethodCallExpr>()?;
+    let name_ref = terminal.name_ref()?;
+    if !name_ref.syntax().text_range().contains_range(ctx.frange.range) {
+        return None;
+    }
+    let terminal_kind = match name_ref.text() {
+        "collect" => Terminal::Collect,
+        "sum" => Terminal::Sum,
+        "count" => Terminal::Count,
+        "for_each" => Terminal::ForEach(closure_arg(&terminal)?),
+        _ => return None,
+    };
+
+    let mut adapters = Vec::new();
+    let mut receiver = terminal.receiver()?;
+    while let ast::Expr::MethodCallExpr(call) = &receiver {
+        let is_filter = match call.name_ref()?.text() {
+            "filter" => true,
+            "map" => false,
+            _ => break,
+        };
+        adapters.push((is_filter, closure_arg(call)?));
+        receiver = call.receiver()?;
+    }
+    adapters.reverse();
+    if adapters.is_empty() && matches!(terminal_kind, Terminal::ForEach(_)) {
+        // `convert_iter_for_each_to_for` covers a bare `for_each`.
+        return None;
+    }
+    let (iterable, collection) = iterable_of(&receiver)?;
+    if !impls_into_iter(&ctx.sema, &collection) {
+        cov_mark::hit!(iter_chain_to_for_loop_not_iterable);
+        return None;
+    }
+
+    // All the closures have to agree on the name of the item.
+    let mut item = None;
+    let closures = adapters.iter().map(|(_, closure)| closure).chain(match &terminal_kind {
+        Terminal::ForEach(closure) => Some(closure),
+        _ => None,
+    });
+    for closure in closures {
+        let name = match closure_param(closure)? {
+            ast::Pat::IdentPat(it) => it.name()?,
+            ast::Pat::RefPat(it) => match it.pat()? {
+                ast::Pat::IdentPat(it) => it.name()?,
+                _ => return None,
+            },
+            _ => return None,
+        };
+        match &item {
+            Some(item) if *item != name.to_string() => return None,
+            Some(_) => (),
+            None => item = Some(name.to_string()),
+        }
+    }
+    let item = item.unwrap_or_else(|| "it".to_string());
+
+    let stmt = terminal.syntax().parent()?;
+    let (binding, stmt_range) = match &terminal_kind {
+        Terminal::ForEach(_) => match stmt.kind() {
+            EXPR_STMT => (None, stmt.text_range()),
+            _ => return None,
+        },
+        _ => {
+            let let_stmt = ast::LetStmt::cast(stmt.clone())?;
+            let pat = match let_stmt.pat()? {
+                ast::Pat::IdentPat(it) => it,
+                _ => return None,
+            };
+            let ty = let_stmt.ty().or_else(|| {
+                let generic_args =
+                    name_ref.syntax().parent()?.children().find_map(ast::GenericArgList::cast)?;
+                match generic_args.generic_args().next()? {
+                    ast::GenericArg::TypeArg(it) => it.ty(),
+                    _ => None,
+                }
+            });
+            let acc_ty = ctx.sema.type_of_pat(&ast::Pat::IdentPat(pat.clone()))?;
+            let famous_defs = FamousDefs(&ctx.sema, ctx.sema.scope(&stmt).krate());
+            let init = match (&terminal_kind, &ty) {
+                // Without a type, a plain `0` would be inferred as `i32`.
+                (Terminal::Count, None) => "0usize",
+                _ => terminal_kind.initial(&famous_defs, &acc_ty)?,
+            };
+            let ty = ty.map_or(String::new(), |ty| format!(": {}", ty));
+            let binding = format!("let mut {}{} = {};", pat.name()?, ty, init);
+            (Some((pat.name()?.to_string(), binding)), let_stmt.syntax().text_range())
+        }
+    };
+
+    acc.add(
+        AssistId("convert_iter_chain_to_for_loop", AssistKind::RefactorRewrite),
+        "Convert iterator chain to for loop",
+        terminal.syntax().text_range(),
+        |builder| {
+            let indent = IndentLevel::from_node(&stmt);
+            let mut buf = String::new();
+            if let Some((_, binding)) = &binding {
+                format_to!(buf, "{}\n{}", binding, indent);
+            }
+            format_to!(buf, "for {} in {} {{\n", item, iterable);
+
+            let mut depth = indent + 1;
+            for (is_filter, closure) in &adapters {
+                let body = match closure.body() {
+                    Some(it) => it,
+                    None => continue,
+                };
+                if *is_filter {
+                    let cond = match closure_param(closure) {
+                        Some(ast::Pat::IdentPat(it)) => adjust_item_uses(ctx, &it, &body, false),
+                        _ => body.syntax().to_string(),
+                    };
+                    let cond = reindent(&cond, indent, depth);
+                    format_to!(buf, "{}if {} {{\n", depth, cond);
+                    depth = depth + 1;
+                } else {
+                    let text = reindent(&body.syntax().to_string(), indent, depth);
+                    format_to!(buf, "{}let {} = {};\n", depth, item, text);
+                }
+            }
+            match (&terminal_kind, &binding) {
+                (Terminal::Collect, Some((acc, _))) => {
+                    format_to!(buf, "{}{}.push({});\n", depth, acc, item)
+                }
+                (Terminal::Sum, Some((acc, _))) => {
+                    format_to!(buf, "{}{} += {};\n", depth, acc, item)
+                }
+                (Terminal::Count, Some((acc, _))) => format_to!(buf, "{}{} += 1;\n", depth, acc),
+                (Terminal::ForEach(closure), _) => match closure.body() {
+                    Some(ast::Expr::BlockExpr(block)) => {
+                        for stmt in block.statements() {
+                            let text = reindent(&stmt.syntax().to_string(), indent + 1, depth);
+                            format_to!(buf, "{}{}\n", depth, text);
+                        }
+                        if let Some(expr) = block.tail_expr() {
+                            let text = reindent(&expr.syntax().to_string(), indent + 1, depth);
+                            format_to!(buf, "{}{};\n", depth, text);
+                        }
+                    }
+                    Some(expr) => {
+                        let text = reindent(&expr.syntax().to_string(), indent, depth);
+                        format_to!(buf, "{}{};\n", depth, text);
+                    }
+                    None => (),
+                },
+                _ => (),
+            }
+            while depth.0 > indent.0 {
+                depth = IndentLevel(depth.0 - 1);
+                format_to!(buf, "{}}}", depth);
+                if depth.0 > indent.0 {
+                    buf.push('\n');
+                }
+            }
+            builder.replace(stmt_range, buf);
+        },
+    )
+}
+
+/// The single statement of a loop body the chain is built from.
+struct LoopFold {
+    acc_name: String,
+    local: hir::Local,
+    cond: Option<ast::Expr>,
+    kind: FoldKind,
+}
+
+enum FoldKind {
+    /// `acc.push(expr)`
+    Push(ast::Expr),
+    /// `acc += expr`
+    Sum(ast::Expr),
+    /// `acc += 1`
+    Count(ast::Expr),
+}
+
+impl FoldKind {
+    fn terminal(&self) -> &'static str {
+        match self {
+            FoldKind::Push(_) => "collect",
+            FoldKind::Sum(_) => "sum",
+            FoldKind::Count(_) => "count",
+        }
+    }
+
+    /// Whether `init` is the value the accumulator starts with.
+    fn is_initial(&self, init: &ast::Expr) -> bool {
+        match self {
+            FoldKind::Push(_) => {
+                let text = init.syntax().text().to_string();
+                text == "vec![]" || text == "Vec::new()"
+            }
+            FoldKind::Sum(_) | FoldKind::Count(_) => match init {
+                ast::Expr::Literal(lit) => {
+                    let text = lit.syntax().text().to_string();
+                    let digits = text.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+                    !digits.is_empty() && digits.chars().all(|c| c == '0' || c == '.')
+                }
+                _ => false,
+            },
+        }
+    }
+}
+
+impl LoopFold {
+    fn new(ctx: &AssistContext, body: &ast::BlockExpr) -> Option<LoopFold> {
+        let mut cond = None;
+        let mut action = single_expr(body)?;
+        if let ast::Expr::IfExpr(if_expr) = &action {
+            let condition = if_expr.condition()?;
+            if condition.pat().is_some() || if_expr.else_branch().is_some() {
+                return None;
+            }
+            cond = Some(condition.expr()?);
+            action = single_expr(&if_expr.then_branch()?)?;
+        }
+
+        let (acc, kind) = match &action {
+            ast::Expr::MethodCallExpr(call) if call.name_ref()?.text() == "push" => {
+                let mut args = call.arg_list()?.args();
+                match (args.next()?, args.next()) {
+                    (arg, None) => (call.receiver()?, FoldKind::Push(arg)),
+                    _ => return None,
+                }
+            }
+            ast::Expr::BinExpr(bin) if bin.op_kind()? == ast::BinOp::AddAssign => {
+                let rhs = bin.rhs()?;
+                let kind = if rhs.syntax().text() == "1" {
+                    FoldKind::Count(rhs)
+                } else {
+                    FoldKind::Sum(rhs)
+                };
+                (bin.lhs()?, kind)
+            }
+            _ => return None,
+        };
+        let path = match acc {
+            ast::Expr::PathExpr(it) => it.path()?,
+            _ => return None,
+        };
+        let local = match ctx.sema.resolve_path(&path)? {
+            PathResolution::Local(it) => it,
+            _ => return None,
+        };
+        let acc_name = path.to_string();
+
+        // The accumulator can't be used while it is being built.
+        let uses_acc = |expr: &ast::Expr| {
+            expr.syntax()
+                .descendants()
+                .filter_map(ast::NameRef::cast)
+                .any(|name_ref| name_ref.text() == acc_name)
+        };
+        let value = match &kind {
+            FoldKind::Push(it) | FoldKind::Sum(it) => Some(it),
+            FoldKind::Count(_) => None,
+        };
+        if cond.iter().chain(value).any(uses_acc) {
+            return None;
+        }
+        Some(LoopFold { acc_name, local, cond, kind })
+    }
+}
+
+enum Terminal {
+    Collect,
+    Sum,
+    Count,
+    ForEach(ast::ClosureExpr),
+}
+
+impl Terminal {
+    /// The value the accumulator of a `let` binding of type `ty` starts with.
+    fn initial(&self, famous_defs: &FamousDefs, ty: &hir::Type) -> Option<&'static str> {
+        match self {
+            Terminal::Collect => {
+                let vec = famous_defs.alloc_vec_Vec()?;
+                if ty.as_adt() == Some(hir::Adt::Struct(vec)) {
+                    Some("Vec::new()")
+                } else {
+                    None
+                }
+            }
+            Terminal::Sum if ty.is_float() => Some("0.0"),
+            Terminal::Sum | Terminal::Count if !ty.is_unknown() => Some("0"),
+            _ => None,
+        }
+    }
+}
+
+fn single_expr(block: &ast::BlockExpr) -> Option<ast::Expr> {
+    let mut stmts = block.statements();
+    match (stmts.next(), stmts.next(), block.tail_expr()) {
+        (None, None, Some(expr)) => Some(expr),
+        (Some(ast::Stmt::ExprStmt(stmt)), None, None) => stmt.expr(),
+        _ => None,
+    }
+}
+
+/// Renders the expression iterating over `iterable`, which has to implement
+/// `IntoIterator`.
+fn iterator_of(sema: &hir::Semantics<RootDatabase>, iterable: &ast::Expr) -> Option<String> {
+    if let Some((expr_behind_ref, method)) = is_ref_and_impls_iter_method(sema, iterable) {
+        return Some(format!("{}.{}()", expr_behind_ref, method));
+    }
+    if impls_core_iter(sema, iterable) {
+        return Some(iterable.to_string());
+    }
+    if !impls_into_iter(sema, iterable) {
+        return None;
+    }
+    match iterable {
+        ast::Expr::RefExpr(_) => Some(format!("({}).into_iter()", iterable)),
+        _ => Some(format!("{}.into_iter()", iterable)),
+    }
+}
+
+fn impls_into_iter(sema: &hir::Semantics<RootDatabase>, expr: &ast::Expr) -> bool {
+    let ty = match sema.type_of_expr(expr) {
+        Some(it) => it,
+        None => return false,
+    };
+    let krate = sema.scope(expr.syntax()).module().map(|it| it.krate());
+    let famous_defs = FamousDefs(sema, krate);
+    [famous_defs.core_iter_Iterator(), famous_defs.core_iter_IntoIterator()]
+        .iter()
+        .flatten()
+        .any(|trait_| ty.impls_trait(sema.db, *trait_, &[]))
+}
+
+/// The expression a `for` loop iterates over to yield the same items as
+/// `receiver`, and the collection it comes from.
+fn iterable_of(receiver: &ast::Expr) -> Option<(String, ast::Expr)> {
+    if let ast::Expr::MethodCallExpr(call) = receiver {
+        if call.arg_list()?.args().next().is_none() {
+            let inner = call.receiver()?;
+            match call.name_ref()?.text() {
+                "iter" => return Some((format!("&{}", inner), inner)),
+                "iter_mut" => return Some((format!("&mut {}", inner), inner)),
+                "into_iter" => return Some((inner.to_string(), inner)),
+                _ => (),
+            }
+        }
+    }
+    Some((receiver.to_string(), receiver.clone()))
+}
+
+fn closure_arg(call: &ast::MethodCallExpr) -> Option<ast::ClosureExpr> {
+    let mut args = call.arg_list()?.args();
+    match (args.next()?, args.next()) {
+        (ast::Expr::ClosureExpr(it), None) => Some(it),
+        _ => None,
+    }
+}
+
+fn closure_param(closure: &ast::ClosureExpr) -> Option<ast::Pat> {
+    let mut params = closure.param_list()?.params();
+    match (params.next()?, params.next()) {
+        (param, None) => param.pat(),
+        _ => None,
+    }
+}
+
+/// `filter` hands its closure a reference to the item, while the loop binds
+/// the item itself. Going to the closure (`to_ref`), plain uses of the item
+/// get dereferenced and borrows become plain uses; going to the loop, it is
+/// the other way around. Method receivers and field accesses are left alone.
+fn adjust_item_uses(
+    ctx: &AssistContext,
+    item: &ast::IdentPat,
+    expr: &ast::Expr,
+    to_ref: bool,
+) -> String {
+    let local = ctx.sema.to_def(item);
+    let mut edits = Vec::new();
+    for path_expr in expr.syntax().descendants().filter_map(ast::PathExpr::cast) {
+        let resolution = path_expr.path().and_then(|path| ctx.sema.resolve_path(&path));
+        if !matches!(resolution, Some(PathResolution::Local(it)) if Some(it) == local) {
+            continue;
+        }
+        let name = path_expr.syntax().text().to_string();
+        let (range, replacement) = match path_expr.syntax().parent().and_then(ast::Expr::cast) {
+            Some(ast::Expr::MethodCallExpr(_)) | Some(ast::Expr::FieldExpr(_)) => continue,
+            Some(ast::Expr::PrefixExpr(prefix))
+                if !to_ref && prefix.op_kind() == Some(ast::PrefixOp::Deref) =>
+            {
+                (prefix.syntax().text_range(), name)
+            }
+            Some(ast::Expr::RefExpr(ref_expr)) if to_ref && ref_expr.mut_token().is_none() => {
+                (ref_expr.syntax().text_range(), name)
+            }
+            _ if to_ref => (path_expr.syntax().text_range(), format!("*{}", name)),
+            _ => (path_expr.syntax().text_range(), format!("&{}", name)),
+        };
+        edits.push((range, replacement));
+    }
+    let mut text = expr.syntax().to_string();
+    let offset = expr.syntax().text_range().start();
+    for (range, replacement) in edits.into_iter().rev() {
+        text.replace_range(std::ops::Range::<usize>::from(range - offset), &replacement);
+    }
+    text
+}
+
+/// Whether the accumulator still needs to be mutable once the loop is gone.
+fn mutated_after(ctx: &AssistContext, fold: &LoopFold, loop_range: TextRange) -> bool {
+    let usages = Definition::Local(fold.local).usages(&ctx.sema).all();
+    usages.references.values().flatten().any(|reference| {
+        if reference.range.start() < loop_range.end() {
+            return false;
+        }
+        let path_expr = match &reference.name {
+            ast::NameLike::NameRef(name_ref) => {
+                name_ref.syntax().ancestors().find_map(ast::PathExpr::cast).map(ast::Expr::PathExpr)
+            }
+            _ => None,
+        };
+        match path_expr {
+            Some(it) => usage_access(ctx, &it) == Access::Exclusive,
+            None => true,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    const VEC_FIXTURE: &'static str = r#"
+//- /alloc.rs deps:core crate:alloc
+pub mod vec {
+    pub struct Vec<T>(T);
+    impl<T> Vec<T> {
+        pub fn new() -> Vec<T> { loop {} }
+        pub fn push(&mut self, item: T) {}
+        pub fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {}
+    }
+    impl<T> IntoIterator for Vec<T> {
+        type Item = T;
+    }
+}
+
+#[lang = "copy"]
+pub trait Copy {}
+impl Copy for u32 {}
+"#;
+
+    fn with_fixtures(ra_fixture: &str) -> String {
+        format!(
+            "//- /main.rs crate:main deps:core,alloc{}{}{}",
+            ra_fixture,
+            FamousDefs::FIXTURE,
+            VEC_FIXTURE
+        )
+    }
+
+    #[test]
+    fn push_to_collect() {
+        check_assist(
+            convert_for_loop_to_iter_chain,
+            &with_fixtures(
+                r"
+use alloc::vec::Vec;
+fn main() {
+    let xs: Vec<u32> = Vec::new();
+    let mut evens = Vec::new();
+    $0for x in xs {
+        if x % 2 == 0 {
+            evens.push(x * 10);
+        }
+    }
+}
+",
+            ),
+            r"
+use alloc::vec::Vec;
+fn main() {
+    let xs: Vec<u32> = Vec::new();
+    let evens: Vec<_> = xs.into_iter().filter(|&x| x % 2 == 0).map(|x| x * 10).collect();
+}
+",
+        );
+    }
+
+    #[test]
+    fn push_without_initializer_extends() {
+        check_assist(
+            convert_for_loop_to_iter_chain,
+            &with_fixtures(
+                r"
+use alloc::vec::Vec;
+fn fill(evens: &mut Vec<u32>, xs: Vec<u32>) {
+    for$0 x in xs {
+        evens.push(x);
+    }
+}
+",
+            ),
+            r"
+use alloc::vec::Vec;
+fn fill(evens: &mut Vec<u32>, xs: Vec<u32>) {
+    evens.extend(xs.into_iter());
+}
+",
+        );
+    }
+
+    #[test]
+    fn count_keeps_mut_when_mutated_later() {
+        check_assist(
+            convert_for_loop_to_iter_chain,
+            &with_fixtures(
+                r"
+use alloc::vec::Vec;
+fn main() {
+    let xs: Vec<u32> = Vec::new();
+    let mut n = 0usize;
+    $0for x in xs {
+        if x > 2 {
+            n += 1;
+        }
+    }
+    n += 1;
+}
+",
+            ),
+            r"
+use alloc::vec::Vec;
+fn main() {
+    let xs: Vec<u32> = Vec::new();
+    let mut n = xs.into_iter().filter(|&x| x > 2).count();
+    n += 1;
+}
+",
+        );
+    }
+
+    #[test]
+    fn sum_of_non_copy_items_borrows_in_filter() {
+        check_assist(
+            convert_for_loop_to_iter_chain,
+            &with_fixtures(
+                r"
+use alloc::vec::Vec;
+struct Big(u32);
+fn total(xs: Vec<Big>) -> u32 {
+    let mut total = 0;
+    $0for x in xs {
+        if x.0 > 1 && keep(&x) {
+            total += x.0;
+        }
+    }
+    total
+}
+fn keep(x: &Big) -> bool { true }
+",
+            ),
+            r"
+use alloc::vec::Vec;
+struct Big(u32);
+fn total(xs: Vec<Big>) -> u32 {
+    let total: u32 = xs.into_iter().filter(|x| x.0 > 1 && keep(x)).map(|x| x.0).sum();
+    total
+}
+fn keep(x: &Big) -> bool { true }
+",
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_non_iterable() {
+        cov_mark::check!(for_loop_to_iter_chain_not_iterable);
+        check_assist_not_applicable(
+            convert_for_loop_to_iter_chain,
+            &with_fixtures(
+                r"
+struct NotIter;
+fn main() {
+    let mut total = 0;
+    $0for x in NotIter {
+        total += x;
+    }
+}
+",
+            ),
+        );
+    }
+
+    #[test]
+    fn not_applicable_in_body() {
+        cov_mark::check!(for_loop_to_iter_chain_in_body);
+        check_assist_not_applicable(
+            convert_for_loop_to_iter_chain,
+            &with_fixtures(
+                r"
+use alloc::vec::Vec;
+fn main() {
+    let xs: Vec<u32> = Vec::new();
+    let mut total = 0;
+    for x in xs {
+        total += $0x;
+    }
+}
+",
+            ),
+        );
+    }
+
+    #[test]
+    fn collect_to_for_loop() {
+        check_assist(
+            convert_iter_chain_to_for_loop,
+            &with_fixtures(
+                r"
+use alloc::vec::Vec;
+fn main() {
+    let xs: Vec<u32> = Vec::new();
+    let evens: Vec<u32> = xs.into_iter().filter(|x| *x % 2 == 0).map(|x| x * 10).coll$0ect();
+}
+",
+            ),
+            r"
+use alloc::vec::Vec;
+fn main() {
+    let xs: Vec<u32> = Vec::new();
+    let mut evens: Vec<u32> = Vec::new();
+    for x in xs {
+        if x % 2 == 0 {
+            let x = x * 10;
+            evens.push(x);
+        }
+    }
+}
+",
+        );
+    }
+
+    #[test]
+    fn count_to_for_loop() {
+        check_assist(
+            convert_iter_chain_to_for_loop,
+            &with_fixtures(
+                r"
+use alloc::vec::Vec;
+fn main() {
+    let xs: Vec<u32> = Vec::new();
+    let n = xs.into_iter().filter(|&x| x > 2).cou$0nt();
+}
+",
+            ),
+            r"
+use alloc::vec::Vec;
+fn main() {
+    let xs: Vec<u32> = Vec::new();
+    let mut n = 0usize;
+    for x in xs {
+        if x > 2 {
+            n += 1;
+        }
+    }
+}
+",
+        );
+    }
+
+    #[test]
+    fn float_sum_to_for_loop() {
+        check_assist(
+            convert_iter_chain_to_for_loop,
+            &with_fixtures(
+                r"
+use alloc::vec::Vec;
+fn main() {
+    let xs: Vec<f64> = Vec::new();
+    let total: f64 = xs.into_iter().map(|x| x * 2.0).s$0um();
+}
+",
+            ),
+            r"
+use alloc::vec::Vec;
+fn main() {
+    let xs: Vec<f64> = Vec::new();
+    let mut total: f64 = 0.0;
+    for x in xs {
+        let x = x * 2.0;
+        total += x;
+    }
+}
+",
+        );
+    }
+
+    #[test]
+    fn for_each_to_for_loop() {
+        check_assist(
+            convert_iter_chain_to_for_loop,
+            &with_fixtures(
+                r"
+use alloc::vec::Vec;
+fn main() {
+    let xs: Vec<u32> = Vec::new();
+    xs.into_iter().map(|x| x + 1).for_e$0ach(|x| {
+        let y = x;
+    });
+}
+",
+            ),
+            r"
+use alloc::vec::Vec;
+fn main() {
+    let xs: Vec<u32> = Vec::new();
+    for x in xs {
+        let x = x + 1;
+        let y = x;
+    }
+}
+",
+        );
+    }
+
+    #[test]
+    fn chain_not_applicable_to_non_iterable() {
+        cov_mark::check!(iter_chain_to_for_loop_not_iterable);
+        check_assist_not_applicable(
+            convert_iter_chain_to_for_loop,
+            &with_fixtures(
+                r"
+struct NotIter;
+impl NotIter {
+    fn count(self) -> usize { 0 }
+}
+fn main() {
+    let n = NotIter.cou$0nt();
+}
+",
+            ),
+        );
+    }
+}
@@ -0,0 +1,772 @@
+use std::{cmp::Reverse, iter, ops};
+
+use hir::{Adt, HasSource, ModuleDef, PathResolution};
+use ide_db::{base_db::FileId, defs::Definition, search::FileReference};
+use itertools::Itertools;
+use rustc_hash::FxHashMap;
+use stdx::format_to;
+use syntax::{
+    algo::{find_node_at_offset, find_node_at_range},
+    ast::{self, edit::IndentLevel, ArgListOwner, AstNode, GenericParamsOwner, NameOwner},
+    match_ast,
+    SyntaxKind::{COMMENT, VISIBILITY, WHITESPACE},
+    SyntaxNode, TextRange,
+};
+
+use crate::{assist_context::AssistBuilder, AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: convert_tuple_struct_to_named_struct
+//
+// Converts a tuple struct into a struct with named fields, updating its constructors,
+// patterns and field accesses.
+//
+// ```
+// struct Point$0(f32, f32);
+//
+// fn origin() -> Point {
+//     let p = Point(0.0, 0.0);
+//     Point(p.0, p.1)
+// }
+// ```
+// ->
+// ```
+// struct Point { field1: f32, field2: f32 }
+//
+// fn origin() -> Point {
+//     let p = Point { field1: 0.0, field2: 0.0 };
+//     Point { field1: p.field1, field2: p.field2 }
+// }
+// ```
+pub(crate) fn convert_tuple_struct_to_named_struct(
+    acc: &mut Assists,
+    ctx: &AssistContext,
+) -> Option<()> {
+    let strukt = ctx.find_node_at_offset::<ast::Struct>()?;
+    let field_list = match strukt.field_list()? {
+        ast::FieldList::TupleFieldList(it) => it,
+        ast::FieldList::RecordFieldList(_) => return None,
+    };
+    if ctx.offset() > field_list.syntax().text_range().start() {
+        return None;
+    }
+    let strukt_def = ctx.sema.to_def(&strukt)?;
+
+    let target = strukt.syntax().text_range();
+    acc.add(
+        AssistId("convert_tuple_struct_to_named_struct", AssistKind::RefactorRewrite),
+        "Convert to named struct",
+        target,
+        |builder| {
+            let fields = strukt_def.fields(ctx.db());
+            let names: Vec<String> =
+                (1..=fields.len()).map(|idx| format!("field{}", idx)).collect();
+
+            let mut edits = FxHashMap::<FileId, FileEdits>::default();
+            rename_index_accesses(ctx, &fields, &names, &mut edits);
+            for (file_id, usages) in struct_usages(ctx, strukt_def) {
+                let edits = edits.entry(file_id).or_default();
+                for usage in usages {
+                    if let Some(text) = tuple_usage_to_named(ctx, strukt_def, &names, edits, &usage)
+                    {
+                        edits.replace(usage.text_range(), text);
+                    }
+                }
+            }
+
+            let mut def = String::new();
+            if let Some(where_clause) = strukt.where_clause() {
+                format_to!(def, " {}", where_clause);
+            }
+            let fields = field_list.fields().zip(&names).filter_map(|(field, name)| {
+                let ty = field.ty()?;
+                let (prefix, vis) = prefix_before(field.syntax(), ty.syntax());
+                Some((prefix, format!("{}{}: {}", vis, name, ty)))
+            });
+            format_to!(def, " {}", fields_def(&strukt, fields.collect(), true));
+            let range = TextRange::new(
+                field_list.syntax().text_range().start(),
+                strukt.syntax().text_range().end(),
+            );
+            edits.entry(ctx.frange.file_id).or_default().replace(range, def);
+
+            apply(builder, edits);
+        },
+    )
+}
+
+// Assist: convert_named_struct_to_tuple_struct
+//
+// Converts a struct with named fields into a tuple struct, updating its constructors,
+// patterns and field accesses.
+//
+// ```
+// struct Point$0 { x: f32, y: f32 }
+//
+// fn origin() -> Point {
+//     let p = Point { x: 0.0, y: 0.0 };
+//     Point { y: p.y, x: p.x }
+// }
+// ```
+// ->
+// ```
+// struct Point(f32, f32);
+//
+// fn origin() -> Point {
+//     let p = Point(0.0, 0.0);
+//     Point(p.0, p.1)
+// }
+// ```
+pub(crate) fn convert_named_struct_to_tuple_struct(
+    acc: &mut Assists,
+    ctx: &AssistContext,
+) -> Option<()> {
+    let strukt = ctx.find_node_at_offset::<ast::Struct>()?;
+    let field_list = match strukt.field_list()? {
+        ast::FieldList::RecordFieldList(it) => it,
+        ast::FieldList::TupleFieldList(_) => return None,
+    };
+    if ctx.offset() > field_list.syntax().text_range().start() {
+        return None;
+    }
+    let strukt_def = ctx.sema.to_def(&strukt)?;
+    let def_start = match strukt.generic_param_list() {
+        Some(it) => it.syntax().text_range().end(),
+        None => strukt.name()?.syntax().text_range().end(),
+    };
+
+    let target = strukt.syntax().text_range();
+    acc.add(
+        AssistId("convert_named_struct_to_tuple_struct", AssistKind::RefactorRewrite),
+        "Convert to tuple struct",
+        target,
+        |builder| {
+            let fields = strukt_def.fields(ctx.db());
+
+            let mut edits = FxHashMap::<FileId, FileEdits>::default();
+            for (idx, field) in fields.iter().enumerate() {
+                for (file_id, references) in Definition::Field(*field).usages(&ctx.sema).all() {
+                    let source_file = ctx.sema.parse(file_id);
+                    let edits = edits.entry(file_id).or_default();
+                    for FileReference { range, .. } in references {
+                        // Record literals and patterns are rewritten as a whole below.
+                        let field_expr = match find_node_at_offset::<ast::FieldExpr>(
+                            source_file.syntax(),
+                            range.start(),
+                        ) {
+                            Some(it) => it,
+                            None => continue,
+                        };
+                        if let Some(name_ref) = field_expr.name_ref() {
+                            if name_ref.syntax().text_range() == range {
+                                edits.replace(range, idx.to_string());
+                            }
+                        }
+                    }
+                }
+            }
+            for (file_id, usages) in struct_usages(ctx, strukt_def) {
+                let edits = edits.entry(file_id).or_default();
+                for usage in usages {
+                    if let Some(text) = named_usage_to_tuple(ctx, &fields, edits, &usage) {
+                        edits.replace(usage.text_range(), text);
+                    }
+                }
+            }
+
+            let fields = field_list.fields().filter_map(|field| {
+                let name = field.name()?;
+                let (prefix, vis) = prefix_before(field.syntax(), name.syntax());
+                Some((prefix, format!("{}{}", vis, field.ty()?)))
+            });
+            let mut def = fields_def(&strukt, fields.collect(), false);
+            if let Some(where_clause) = strukt.where_clause() {
+                format_to!(def, " {}", where_clause);
+            }
+            def.push(';');
+            let range = TextRange::new(def_start, strukt.syntax().text_range().end());
+            edits.entry(ctx.frange.file_id).or_default().replace(range, def);
+
+            apply(builder, edits);
+        },
+    )
+}
+
+/// Text edits for a single file. Usages can nest (`Foo(Foo(0).0)`), so a
+/// rewrite of an outer node replaces the edits recorded inside of it, and has
+/// to be rendered with [`FileEdits::text_of`] to keep them.
+#[derive(Default)]
+struct FileEdits {
+    edits: Vec<(TextRange, String)>,
+}
+
+impl FileEdits {
+    fn replace(&mut self, range: TextRange, text: String) {
+        self.edits.retain(|(it, _)| !range.contains_range(*it));
+        self.edits.push((range, text));
+    }
+
+    fn text_of(&self, node: &SyntaxNode) -> String {
+        let node_range = node.text_range();
+        let mut text = node.to_string();
+        let inner = self
+            .edits
+            .iter()
+            .filter(|(range, _)| node_range.contains_range(*range))
+            .sorted_by_key(|(range, _)| Reverse(range.start()));
+        for (range, replacement) in inner {
+            text.replace_range(ops::Range::<usize>::from(*range - node_range.start()), replacement);
+        }
+        text
+    }
+}
+
+fn apply(builder: &mut AssistBuilder, edits: FxHashMap<FileId, FileEdits>) {
+    for (file_id, edits) in edits.into_iter().sorted_by_key(|(file_id, _)| *file_id) {
+        if edits.edits.is_empty() {
+            continue;
+        }
+        builder.edit_file(file_id);
+        for (range, text) in edits.edits {
+            builder.replace(range, text);
+        }
+    }
+}
+
+/// Finds the call expressions, record literals and patterns that construct or
+/// destructure `strukt`, and the paths using it as a function, either by name
+/// or through `Self`. Inner usages come before the outer ones.
+fn struct_usages(ctx: &AssistContext, strukt: hir::Struct) -> FxHashMap<FileId, Vec<SyntaxNode>> {
+    let mut res = FxHashMap::<FileId, Vec<SyntaxNode>>::default();
+    let usages = Definition::ModuleDef(ModuleDef::Adt(Adt::Struct(strukt))).usages(&ctx.sema).all();
+    for (file_id, references) in usages {
+        let source_file = ctx.sema.parse(file_id);
+        let nodes = references.into_iter().filter_map(|reference| {
+            let name_ref =
+                find_node_at_offset::<ast::NameRef>(source_file.syntax(), reference.range.start())?;
+            constructor_or_pattern(name_ref.syntax().ancestors().find_map(ast::Path::cast)?)
+        });
+        res.entry(file_id).or_default().extend(nodes);
+    }
+
+    for impl_ in hir::Impl::all_for_type(ctx.db(), strukt.ty(ctx.db())) {
+        let src = match impl_.source(ctx.db()) {
+            Some(it) => it,
+            None => continue,
+        };
+        let frange = src.syntax().original_file_range(ctx.db());
+        let source_file = ctx.sema.parse(frange.file_id);
+        let impl_node = match find_node_at_range::<ast::Impl>(source_file.syntax(), frange.range) {
+            Some(it) if it.syntax().text_range() == frange.range => it,
+            _ => continue,
+        };
+        let nodes = impl_node
+            .syntax()
+            .descendants()
+            .filter_map(ast::Path::cast)
+            .filter(|path| path.qualifier().is_none() && path.syntax().text() == "Self")
+            .filter(|path| is_struct_path(ctx, path, strukt))
+            .filter_map(constructor_or_pattern);
+        res.entry(frange.file_id).or_default().extend(nodes);
+    }
+
+    for nodes in res.values_mut() {
+        nodes.sort_by_key(|node| (node.text_range().len(), node.text_range().start()));
+        nodes.dedup();
+    }
+    res
+}
+
+fn constructor_or_pattern(path: ast::Path) -> Option<SyntaxNode> {
+    let parent = path.syntax().parent()?;
+    match_ast! {
+        match parent {
+            ast::PathExpr(path_expr) => {
+                // A tuple struct can also be used as a function, like in `.map(Foo)`.
+                match path_expr.syntax().parent().and_then(ast::CallExpr::cast) {
+                    Some(call) if call.expr()?.syntax() == path_expr.syntax() => {
+                        Some(call.syntax().clone())
+                    }
+                    _ => Some(path_expr.syntax().clone()),
+                }
+            },
+            ast::TupleStructPat(it) => Some(it.syntax().clone()),
+            ast::RecordExpr(it) => Some(it.syntax().clone()),
+            ast::RecordPat(it) => Some(it.syntax().clone()),
+            _ => None,
+        }
+    }
+}
+
+fn is_struct_path(ctx: &AssistContext, path: &ast::Path, strukt: hir::Struct) -> bool {
+    match ctx.sema.resolve_path(path) {
+        Some(PathResolution::Def(ModuleDef::Adt(Adt::Struct(it)))) => it == strukt,
+        Some(PathResolution::SelfType(impl_)) => {
+            impl_.target_ty(ctx.db()).as_adt() == Some(Adt::Struct(strukt))
+        }
+        _ => false,
+    }
+}
+
+/// Renames the `.0` accesses of the tuple fields, and the `0: x` fields of
+/// record literals and patterns.
+fn rename_index_accesses(
+    ctx: &AssistContext,
+    fields: &[hir::Field],
+    names: &[String],
+    edits: &mut FxHashMap<FileId, FileEdits>,
+) {
+    for (field, name) in fields.iter().zip(names) {
+        for (file_id, references) in Definition::Field(*field).usages(&ctx.sema).all() {
+            let edits = edits.entry(file_id).or_default();
+            for FileReference { range, .. } in references {
+                edits.replace(range, name.clone());
+            }
+        }
+    }
+}
+
+fn tuple_usage_to_named(
+    ctx: &AssistContext,
+    strukt: hir::Struct,
+    names: &[String],
+    edits: &FileEdits,
+    usage: &SyntaxNode,
+) -> Option<String> {
+    match_ast! {
+        match usage {
+            ast::CallExpr(call) => {
+                let path = match call.expr()? {
+                    ast::Expr::PathExpr(it) => it.path()?,
+                    _ => return None,
+                };
+                if !is_struct_path(ctx, &path, strukt) {
+                    return None;
+                }
+                let args = call.arg_list()?.args().collect::<Vec<_>>();
+                if args.len() != names.len() {
+                    return None;
+                }
+                let fields = names
+                    .iter()
+                    .zip(args)
+                    .map(|(name, arg)| format!("{}: {}", name, edits.text_of(arg.syntax())));
+                Some(format!("{} {}", edits.text_of(path.syntax()), record_body(fields.collect())))
+            },
+            ast::PathExpr(path_expr) => {
+                let path = path_expr.path()?;
+                if !is_struct_path(ctx, &path, strukt) {
+                    return None;
+                }
+                let body = record_body(names.to_vec());
+                Some(format!("|{}| {} {}", names.join(", "), edits.text_of(path.syntax()), body))
+            },
+            ast::TupleStructPat(pat) => {
+                let path = pat.path()?;
+                if !is_struct_path(ctx, &path, strukt) {
+                    return None;
+                }
+                let pats = pat.fields().collect::<Vec<_>>();
+                let rest = pats.iter().position(|it| matches!(it, ast::Pat::RestPat(_)));
+                let named: Vec<_> = match rest {
+                    Some(rest) => {
+                        let suffix = &pats[rest + 1..];
+                        let suffix_names = &names[names.len().checked_sub(suffix.len())?..];
+                        names.iter().zip(&pats[..rest]).chain(suffix_names.iter().zip(suffix)).collect()
+                    }
+                    None if pats.len() == names.len() => names.iter().zip(&pats).collect(),
+                    None => return None,
+                };
+                let mut fields: Vec<String> = named
+                    .into_iter()
+                    .map(|(name, pat)| format!("{}: {}", name, edits.text_of(pat.syntax())))
+                    .collect();
+                if rest.is_some() {
+                    fields.push("..".to_string());
+                }
+                Some(format!("{} {}", edits.text_of(path.syntax()), record_body(fields)))
+            },
+            _ => None,
+        }
+    }
+}
+
+fn named_usage_to_tuple(
+    ctx: &AssistContext,
+    fields: &[hir::Field],
+    edits: &FileEdits,
+    usage: &SyntaxNode,
+) -> Option<String> {
+    let position = |field: Option<hir::Field>| fields.iter().position(|&it| Some(it) == field);
+    match_ast! {
+        match usage {
+            ast::RecordExpr(record) => {
+                let path = record.path()?;
+                let field_list = record.record_expr_field_list()?;
+                let mut args = vec![None; fields.len()];
+                let mut renamed = Vec::new();
+                for field in field_list.fields() {
+                    let idx = position(ctx.sema.resolve_record_field(&field).map(|(it, _)| it))?;
+                    let value = edits.text_of(field.expr()?.syntax());
+                    renamed.push(format!("{}: {}", idx, value));
+                    args[idx] = Some(value);
+                }
+                let path = edits.text_of(path.syntax());
+                match field_list.spread() {
+                    // Functional update syntax has no positional form.
+                    Some(spread) => {
+                        renamed.push(format!("..{}", edits.text_of(spread.syntax())));
+                        Some(format!("{} {}", path, record_body(renamed)))
+                    }
+                    None => {
+                        let args = args.into_iter().collect::<Option<Vec<_>>>()?;
+                        Some(format!("{}({})", path, args.join(", ")))
+                    }
+                }
+            },
+            ast::RecordPat(pat) => {
+                let path = pat.path()?;
+                let field_list = pat.record_pat_field_list()?;
+                let mut pats = vec![None; fields.len()];
+                for field in field_list.fields() {
+                    let idx = position(ctx.sema.resolve_record_pat_field(&field))?;
+                    pats[idx] = Some(edits.text_of(field.pat()?.syntax()));
+                }
+                let has_rest = field_list.dotdot_token().is_some();
+                if has_rest {
+                    while let Some(None) = pats.last() {
+                        pats.pop();
+                    }
+                }
+                let mut pats: Vec<String> =
+                    pats.into_iter().map(|it| it.unwrap_or_else(|| "_".to_string())).collect();
+                if has_rest {
+                    pats.push("..".to_string());
+                }
+                Some(format!("{}({})", edits.text_of(path.syntax()), pats.join(", ")))
+            },
+            _ => None,
+        }
+    }
+}
+
+/// Returns the attributes and comments in front of `child`, and its
+/// visibility. The comments in front of a tuple field are not part of it, so
+/// they are taken from its siblings.
+fn prefix_before(node: &SyntaxNode, child: &SyntaxNode) -> (Vec<String>, String) {
+    let mut prefix: Vec<String> =
+        iter::successors(node.prev_sibling_or_token(), |it| it.prev_sibling_or_token())
+            .take_while(|it| matches!(it.kind(), WHITESPACE | COMMENT))
+            .filter(|it| it.kind() == COMMENT)
+            .map(|it| it.to_string())
+            .collect();
+    prefix.reverse();
+    let mut vis = String::new();
+    let before_child = node
+        .children_with_tokens()
+        .take_while(|it| it.text_range().start() < child.text_range().start());
+    for it in before_child {
+        match it.kind() {
+            WHITESPACE => (),
+            VISIBILITY => format_to!(vis, "{} ", it),
+            _ => prefix.push(it.to_string()),
+        }
+    }
+    (prefix, vis)
+}
+
+/// Renders the fields of a struct definition with their prefixes. A comment
+/// runs to the end of its line, so if there is one, each attribute, comment
+/// and field gets its own line.
+fn fields_def(strukt: &ast::Struct, fields: Vec<(Vec<String>, String)>, record: bool) -> String {
+    let has_comment = fields.iter().flat_map(|(prefix, _)| prefix).any(|it| it.starts_with('/'));
+    if !has_comment {
+        let fields = fields
+            .into_iter()
+            .map(|(prefix, field)| {
+                let prefix: String = prefix.iter().map(|it| format!("{} ", it)).collect();
+                prefix + &field
+            })
+            .collect::<Vec<_>>();
+        return if record { record_body(fields) } else { format!("({})", fields.join(", ")) };
+    }
+    let indent = IndentLevel::from_node(strukt.syntax());
+    let mut res = String::from(if record { "{" } else { "(" });
+    for (prefix, field) in fields {
+        for it in prefix {
+            format_to!(res, "\n{}{}", indent + 1, it);
+        }
+        format_to!(res, "\n{}{},", indent + 1, field);
+    }
+    format_to!(res, "\n{}{}", indent, if record { "}" } else { ")" });
+    res
+}
+
+fn record_body(fields: Vec<String>) -> String {
+    if fields.is_empty() {
+        "{}".to_string()
+    } else {
+        format!("{{ {} }}", fields.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn tuple_to_named_rewrites_usages() {
+        check_assist(
+            convert_tuple_struct_to_named_struct,
+            r#"
+struct Foo$0<T>(pub T, u32) where T: Clone;
+
+impl<T: Clone> Foo<T> {
+    fn new(value: T) -> Self {
+        Self(value, 0)
+    }
+
+    fn count(&self) -> u32 {
+        let Self(_, count) = self;
+        *count + self.1
+    }
+}
+
+fn f(foo: Foo<i32>) -> i32 {
+    match foo {
+        Foo(0, ..) => Foo(Foo(1, 2).0, foo.1).0,
+        Foo(.., n) => n as i32,
+    }
+}
+"#,
+            r#"
+struct Foo<T> where T: Clone { pub field1: T, field2: u32 }
+
+impl<T: Clone> Foo<T> {
+    fn new(value: T) -> Self {
+        Self { field1: value, field2: 0 }
+    }
+
+    fn count(&self) -> u32 {
+        let Self { field1: _, field2: count } = self;
+        *count + self.field2
+    }
+}
+
+fn f(foo: Foo<i32>) -> i32 {
+    match foo {
+        Foo { field1: 0, .. } => Foo { field1: Foo { field1: 1, field2: 2 }.field1, field2: foo.field2 }.field1,
+        Foo { field2: n, .. } => n as i32,
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn tuple_to_named_in_other_module() {
+        check_assist(
+            convert_tuple_struct_to_named_struct,
+            r#"
+mod a {
+    pub struct $0Wrapper(pub u32);
+}
+
+mod b {
+    use super::a::Wrapper;
+
+    fn unwrap(w: Wrapper) -> u32 {
+        let Wrapper(inner) = w;
+        inner + w.0
+    }
+}
+"#,
+            r#"
+mod a {
+    pub struct Wrapper { pub field1: u32 }
+}
+
+mod b {
+    use super::a::Wrapper;
+
+    fn unwrap(w: Wrapper) -> u32 {
+        let Wrapper { field1: inner } = w;
+        inner + w.field1
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn named_to_tuple_rewrites_usages() {
+        check_assist(
+            convert_named_struct_to_tuple_struct,
+            r#"
+#[derive(Clone)]
+struct Point$0<T> where T: Copy {
+    x: T,
+    #[allow(dead_code)]
+    pub y: T,
+}
+
+impl<T: Copy> Point<T> {
+    fn swap(self) -> Self {
+        let Self { x, y: other } = self;
+        Self { x: other, y: x }
+    }
+}
+
+fn f(p: Point<i32>) -> i32 {
+    let Point { y, .. } = p.clone();
+    let q = Point { x: 1, ..p };
+    match q {
+        Point { x: 0, .. } => y,
+        Point { x, y: _ } => x + p.x,
+    }
+}
+"#,
+            r#"
+#[derive(Clone)]
+struct Point<T>(T, #[allow(dead_code)] pub T) where T: Copy;
+
+impl<T: Copy> Point<T> {
+    fn swap(self) -> Self {
+        let Self(x, other) = self;
+        Self(other, x)
+    }
+}
+
+fn f(p: Point<i32>) -> i32 {
+    let Point(_, y, ..) = p.clone();
+    let q = Point { 0: 1, ..p };
+    match q {
+        Point(0, ..) => y,
+        Point(x, _) => x + p.0,
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn named_to_tuple_shorthand_literal() {
+        check_assist(
+            convert_named_struct_to_tuple_struct,
+            r#"
+struct $0Foo { a: u32, b: u32 }
+
+fn f(a: u32, b: u32) -> u32 {
+    let foo = Foo { b, a };
+    foo.a + foo.b
+}
+"#,
+            r#"
+struct Foo(u32, u32);
+
+fn f(a: u32, b: u32) -> u32 {
+    let foo = Foo(a, b);
+    foo.0 + foo.1
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn tuple_to_named_keeps_doc_comments() {
+        check_assist(
+            convert_tuple_struct_to_named_struct,
+            r#"
+mod m {
+    struct Foo$0(
+        /// The count.
+        #[allow(dead_code)]
+        pub u32,
+        u32,
+    );
+}
+"#,
+            r#"
+mod m {
+    struct Foo {
+        /// The count.
+        #[allow(dead_code)]
+        pub field1: u32,
+        field2: u32,
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn named_to_tuple_keeps_doc_comments() {
+        check_assist(
+            convert_named_struct_to_tuple_struct,
+            r#"
+struct Foo$0 {
+    /// the a
+    a: u32,
+}
+"#,
+            r#"
+struct Foo(
+    /// the a
+    u32,
+);
+"#,
+        );
+    }
+
+    #[test]
+    fn tuple_to_named_rewrites_constructor_used_as_function() {
+        check_assist(
+            convert_tuple_struct_to_named_struct,
+            r#"
+struct Foo$0(u32);
+
+impl Foo {
+    fn constructor() -> fn(u32) -> Self {
+        Self
+    }
+}
+
+fn wrap(values: Vec<u32>) -> Vec<Foo> {
+    values.into_iter().map(Foo).collect()
+}
+"#,
+            r#"
+struct Foo { field1: u32 }
+
+impl Foo {
+    fn constructor() -> fn(u32) -> Self {
+        |field1| Self { field1 }
+    }
+}
+
+fn wrap(values: Vec<u32>) -> Vec<Foo> {
+    values.into_iter().map(|field1| Foo { field1 }).collect()
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_inside_field_list() {
+        check_assist_not_applicable(
+            convert_tuple_struct_to_named_struct,
+            r#"struct Foo(u32$0, u32);"#,
+        );
+        check_assist_not_applicable(
+            convert_named_struct_to_tuple_struct,
+            r#"struct Foo { a: $0u32 }"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_unit_struct() {
+        check_assist_not_applicable(convert_tuple_struct_to_named_struct, r#"struct Foo$0;"#);
+        check_assist_not_applicable(convert_named_struct_to_tuple_struct, r#"struct Foo$0;"#);
+    }
+}
@@ -0,0 +1,448 @@
+use std::collections::BTreeMap;
+
+use hir::{Adt, ModuleDef, ScopeDef};
+use ide_db::{defs::Definition, helpers::mod_path_to_ast, search::FileReference};
+use rustc_hash::{FxHashMap, FxHashSet};
+use syntax::{
+    ast::{self, AstNode, NameOwner},
+    TextRange,
+};
+
+use crate::{AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: destructure_binding
+//
+// Destructures a binding of a struct or tuple type into a pattern, replacing the
+// field accesses on it with the new bindings.
+//
+// ```
+// struct Point { x: f32, y: f32 }
+// fn make_point() -> Point { Point { x: 0.0, y: 0.0 } }
+//
+// fn main() {
+//     let $0p = make_point();
+//     let len = (p.x * p.x + p.y * p.y).sqrt();
+// }
+// ```
+// ->
+// ```
+// struct Point { x: f32, y: f32 }
+// fn make_point() -> Point { Point { x: 0.0, y: 0.0 } }
+//
+// fn main() {
+//     let Point { x, y } = make_point();
+//     let len = (x * x + y * y).sqrt();
+// }
+// ```
+pub(crate) fn destructure_binding(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let ident_pat = ctx.find_node_at_offset::<ast::IdentPat>()?;
+    if ident_pat.at_token().is_some() {
+        return None;
+    }
+    let name = ident_pat.name()?;
+    if ast::RecordPatField::for_field_name(&name).is_some() {
+        return None;
+    }
+    let local = ctx.sema.to_def(&ident_pat)?;
+
+    let mut root = Access::new(ctx, local.ty(ctx.db()), String::new(), String::new());
+    let mut usages = Vec::new();
+    // The new bindings must not shadow anything that is visible at the usages,
+    // except for locals declared after the binding, which shadow them instead.
+    let binding_end = ident_pat.syntax().text_range().end();
+    let mut in_scope = FxHashSet::default();
+    let references = Definition::Local(local).usages(&ctx.sema).all();
+    for FileReference { name, .. } in references.into_iter().flat_map(|(_, refs)| refs) {
+        let segment = name.syntax().parent().and_then(ast::PathSegment::cast)?;
+        let path = segment.parent_path();
+        if path.qualifier().is_some() {
+            return None;
+        }
+        let path_expr = path.syntax().parent().and_then(ast::PathExpr::cast)?;
+        ctx.sema.scope(path_expr.syntax()).process_all_names(&mut |name, def| {
+            if let ScopeDef::Local(local) = def {
+                let declared_at = local
+                    .source(ctx.db())
+                    .value
+                    .either(|it| it.syntax().text_range(), |it| it.syntax().text_range());
+                if declared_at.start() >= binding_end {
+                    return;
+                }
+            }
+            in_scope.insert(name.to_string());
+        });
+        usages.push(root.record(ctx, path_expr.into()));
+    }
+    if root.is_bound() {
+        cov_mark::hit!(destructure_binding_used_whole);
+        return None;
+    }
+
+    let module = ctx.sema.scope(ident_pat.syntax()).module()?;
+    let mut label_counts = FxHashMap::default();
+    for label in root.bound_labels() {
+        *label_counts.entry(label).or_insert(0) += 1;
+    }
+    let naming = Naming {
+        binding: name.to_string(),
+        taken: label_counts
+            .into_iter()
+            .filter(|(label, count)| *count > 1 || in_scope.contains(*label))
+            .map(|(label, _)| label.clone())
+            .collect(),
+        prefix: match (ident_pat.ref_token(), ident_pat.mut_token()) {
+            (Some(_), Some(_)) => "ref mut ",
+            (Some(_), None) => "ref ",
+            (None, Some(_)) => "mut ",
+            (None, None) => "",
+        },
+    };
+
+    let target = ident_pat.syntax().text_range();
+    acc.add(
+        AssistId("destructure_binding", AssistKind::RefactorRewrite),
+        "Destructure binding",
+        target,
+        |builder| {
+            builder.replace(
+                ident_pat.syntax().text_range(),
+                root.render_pattern(ctx, module, &naming),
+            );
+            for usage in usages {
+                if let Some((range, name)) = usage.replacement(&root, &naming) {
+                    builder.replace(range, name);
+                }
+            }
+        },
+    )
+}
+
+enum Shape {
+    Struct(hir::Struct, Vec<String>),
+    Tuple(usize),
+}
+
+/// The parts of a value that are used in the body: either the value as a whole,
+/// or some of its fields.
+struct Access {
+    shape: Option<(Shape, Vec<hir::Type>)>,
+    /// The name of the binding for this value, unless it conflicts with another one.
+    label: String,
+    /// The field names and indices leading to this value, joined with `_`.
+    path: String,
+    used_whole: bool,
+    fields: BTreeMap<usize, Access>,
+}
+
+/// A usage of the original binding, along with the field expressions that are
+/// applied to it.
+struct Usage {
+    fields: Vec<(usize, TextRange)>,
+}
+
+struct Naming {
+    binding: String,
+    /// Labels that can't be used as names, either because they would shadow
+    /// something or because another field has the same label.
+    taken: FxHashSet<String>,
+    prefix: &'static str,
+}
+
+impl Access {
+    fn new(ctx: &AssistContext, ty: hir::Type, label: String, path: String) -> Access {
+        Access { shape: shape(ctx, &ty), label, path, used_whole: false, fields: BTreeMap::new() }
+    }
+
+    /// Follows the field accesses applied to `expr`, and marks the innermost
+    /// accessed value as used as a whole.
+    fn record(&mut self, ctx: &AssistContext, mut expr: ast::Expr) -> Usage {
+        let mut usage = Usage { fields: Vec::new() };
+        let mut access = self;
+        while let Some(field_expr) = expr.syntax().parent().and_then(ast::FieldExpr::cast) {
+            let field_name = match (field_expr.name_ref(), field_expr.index_token()) {
+                (Some(name_ref), _) => name_ref.text().to_string(),
+                (None, Some(index)) => index.text().to_string(),
+                (None, None) => break,
+            };
+            let (shape, types) = match &access.shape {
+                Some(it) => it,
+                None => break,
+            };
+            let idx = match shape {
+                Shape::Struct(_, names) => names.iter().position(|name| *name == field_name),
+                Shape::Tuple(len) => field_name.parse::<usize>().ok().filter(|idx| idx < len),
+            };
+            let idx = match idx {
+                Some(it) => it,
+                None => break,
+            };
+            let (label, path) = match shape {
+                Shape::Struct(_, names) if names[idx].parse::<usize>().is_err() => {
+                    (names[idx].clone(), format!("{}_{}", access.path, names[idx]))
+                }
+                _ => (format!("{}_{}", access.label, idx), format!("{}_{}", access.path, idx)),
+            };
+            let ty = types[idx].clone();
+            let range = ctx.sema.original_range(field_expr.syntax()).range;
+            usage.fields.push((idx, range));
+            access = access.fields.entry(idx).or_insert_with(|| Access::new(ctx, ty, label, path));
+            expr = field_expr.into();
+        }
+        access.used_whole = true;
+        usage
+    }
+
+    /// Whether this value gets a binding of its own, instead of being destructured.
+    fn is_bound(&self) -> bool {
+        self.used_whole || self.fields.is_empty()
+    }
+
+    fn bound_labels(&self) -> Vec<&String> {
+        if self.is_bound() {
+            return vec![&self.label];
+        }
+        self.fields.values().flat_map(|field| field.bound_labels()).collect()
+    }
+
+    fn name(&self, naming: &Naming) -> String {
+        if naming.taken.contains(&self.label) {
+            format!("{}{}", naming.binding, self.path)
+        } else {
+            self.label.clone()
+        }
+    }
+
+    fn render_pattern(&self, ctx: &AssistContext, module: hir::Module, naming: &Naming) -> String {
+        let shape = match &self.shape {
+            Some((shape, _)) if !self.is_bound() => shape,
+            _ => return format!("{}{}", naming.prefix, self.name(naming)),
+        };
+        match shape {
+            Shape::Struct(strukt, names) => {
+                let path = module
+                    .find_use_path_prefixed(
+                        ctx.db(),
+                        ModuleDef::Adt(Adt::Struct(*strukt)),
+                        ctx.config.insert_use.prefix_kind,
+                    )
+                    .map_or_else(
+                        || strukt.name(ctx.db()).to_string(),
+                        |path| mod_path_to_ast(&path).to_string(),
+                    );
+                if names.iter().all(|name| name.parse::<usize>().is_ok()) {
+                    return format!(
+                        "{}({})",
+                        path,
+                        self.positional(ctx, module, naming, names.len())
+                    );
+                }
+                let mut fields = self
+                    .fields
+                    .iter()
+                    .map(|(&idx, field)| {
+                        let pat = field.render_pattern(ctx, module, naming);
+                        if field.is_bound() && field.name(naming) == names[idx] {
+                            pat
+                        } else {
+                            format!("{}: {}", names[idx], pat)
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                if fields.len() < names.len() {
+                    fields.push("..".to_string());
+                }
+                format!("{} {{ {} }}", path, fields.join(", "))
+            }
+            Shape::Tuple(1) => format!("({},)", self.positional(ctx, module, naming, 1)),
+            Shape::Tuple(len) => format!("({})", self.positional(ctx, module, naming, *len)),
+        }
+    }
+
+    fn positional(
+        &self,
+        ctx: &AssistContext,
+        module: hir::Module,
+        naming: &Naming,
+        len: usize,
+    ) -> String {
+        let last = self.fields.keys().last().copied().unwrap_or(0);
+        let mut pats = (0..=last)
+            .map(|idx| match self.fields.get(&idx) {
+                Some(field) => field.render_pattern(ctx, module, naming),
+                None => "_".to_string(),
+            })
+            .collect::<Vec<_>>();
+        if last + 1 < len {
+            pats.push("..".to_string());
+        }
+        pats.join(", ")
+    }
+}
+
+impl Usage {
+    /// Returns the field expression that is replaced with a new binding.
+    fn replacement(&self, root: &Access, naming: &Naming) -> Option<(TextRange, String)> {
+        let mut access = root;
+        for &(idx, range) in &self.fields {
+            access = access.fields.get(&idx)?;
+            if access.is_bound() {
+                return Some((range, access.name(naming)));
+            }
+        }
+        None
+    }
+}
+
+fn shape(ctx: &AssistContext, ty: &hir::Type) -> Option<(Shape, Vec<hir::Type>)> {
+    // Destructuring a reference would change the types of the new bindings.
+    if ty.remove_ref().is_some() {
+        return None;
+    }
+    if let Some(Adt::Struct(strukt)) = ty.as_adt() {
+        let (names, types): (Vec<_>, Vec<_>) = ty
+            .fields(ctx.db())
+            .into_iter()
+            .map(|(field, ty)| (field.name(ctx.db()).to_string(), ty))
+            .unzip();
+        if names.is_empty() {
+            return None;
+        }
+        return Some((Shape::Struct(strukt, names), types));
+    }
+    let types = ty.tuple_fields(ctx.db());
+    if types.is_empty() {
+        return None;
+    }
+    Some((Shape::Tuple(types.len()), types))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn destructure_tuple() {
+        check_assist(
+            destructure_binding,
+            r#"
+fn pair() -> (u32, &'static str, bool) { (1, "", true) }
+fn show(n: u32) {}
+
+fn main() {
+    let $0t = pair();
+    if t.2 {
+        show(t.0);
+    }
+}
+"#,
+            r#"
+fn pair() -> (u32, &'static str, bool) { (1, "", true) }
+fn show(n: u32) {}
+
+fn main() {
+    let (_0, _, _2) = pair();
+    if _2 {
+        show(_0);
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn destructure_partially_used_struct() {
+        check_assist(
+            destructure_binding,
+            r#"
+mod geom {
+    pub struct Point { pub x: i32, pub y: i32 }
+    pub struct Wrapper(pub Point, pub u8);
+}
+
+fn f(w: geom::Wrapper) -> i32 {
+    let mut $0p = w.0;
+    p.y += 1;
+    p.y
+}
+"#,
+            r#"
+mod geom {
+    pub struct Point { pub x: i32, pub y: i32 }
+    pub struct Wrapper(pub Point, pub u8);
+}
+
+fn f(w: geom::Wrapper) -> i32 {
+    let geom::Point { mut y, .. } = w.0;
+    y += 1;
+    y
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn destructure_nested() {
+        check_assist(
+            destructure_binding,
+            r#"
+struct Point { x: i32, y: i32 }
+struct Line { start: Point, end: Point }
+struct Wrapper(Line, u8);
+
+fn f(w: Wrapper) -> i32 {
+    let x = 0;
+    let $0wrapper = w;
+    let end = &wrapper.0.end;
+    wrapper.0.start.x + wrapper.0.start.y + x + end.y
+}
+"#,
+            r#"
+struct Point { x: i32, y: i32 }
+struct Line { start: Point, end: Point }
+struct Wrapper(Line, u8);
+
+fn f(w: Wrapper) -> i32 {
+    let x = 0;
+    let Wrapper(Line { start: Point { x: wrapper_0_start_x, y }, end }, ..) = w;
+    let end = &end;
+    wrapper_0_start_x + y + x + end.y
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_when_used_as_a_whole() {
+        cov_mark::check!(destructure_binding_used_whole);
+        check_assist_not_applicable(
+            destructure_binding,
+            r#"
+struct Point { x: i32, y: i32 }
+fn take(p: Point) {}
+
+fn f(p: Point) {
+    let $0q = p;
+    let x = q.x;
+    take(q);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_reference() {
+        check_assist_not_applicable(
+            destructure_binding,
+            r#"
+struct Point { x: i32, y: i32 }
+
+fn f(p: &Point) -> i32 {
+    let $0q = p;
+    q.x
+}
+"#,
+        );
+    }
+}
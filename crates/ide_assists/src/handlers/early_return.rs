@@ -1,11 +1,15 @@
-use std::{iter::once, ops::RangeInclusive};
+use std::{
+    cmp::Reverse,
+    iter::once,
+    ops::{self, RangeInclusive},
+};
 
 use syntax::{
     algo::replace_children,
     ast::{
         self,
         edit::{AstNodeEdit, IndentLevel},
-        make,
+        make, NameOwner,
     },
     AstNode,
     SyntaxKind::{FN, LOOP_EXPR, L_CURLY, R_CURLY, WHILE_EXPR, WHITESPACE},
@@ -14,7 +18,7 @@ use syntax::{
 
 use crate::{
     assist_context::{AssistContext, Assists},
-    utils::invert_boolean_expression,
+    utils::{extract_trivial_expression, invert_boolean_expression},
     AssistId, AssistKind,
 };
 
@@ -187,6 +191,199 @@ pub(crate) fn convert_to_guarded_return(acc: &mut Assists, ctx: &AssistContext)
     )
 }
 
+// Assist: convert_to_let_else
+//
+// Replaces an `if let` or `match` guard that binds a variable with a `let ... else` statement.
+//
+// ```
+// fn main() {
+//     let x = $0if let Some(x) = foo() {
+//         x
+//     } else {
+//         return;
+//     };
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     let Some(x) = foo() else {
+//         return;
+//     };
+// }
+// ```
+pub(crate) fn convert_to_let_else(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let let_stmt: ast::LetStmt = ctx.find_node_at_offset()?;
+    if let_stmt.ty().is_some() {
+        return None;
+    }
+    let let_pat = let_stmt.pat()?;
+    let (pat, scrutinee, value, else_expr) = match let_stmt.initializer()? {
+        ast::Expr::IfExpr(if_expr) => {
+            let cond = if_expr.condition()?;
+            let else_block = match if_expr.else_branch()? {
+                ast::ElseBranch::Block(it) => it,
+                ast::ElseBranch::IfExpr(_) => return None,
+            };
+            let value = extract_trivial_expression(&if_expr.then_branch()?)?;
+            (cond.pat()?, cond.expr()?, value, ast::Expr::BlockExpr(else_block))
+        }
+        ast::Expr::MatchExpr(match_expr) => {
+            let arms = match_expr.match_arm_list()?.arms().collect::<Vec<_>>();
+            let (happy_arm, sad_arm) = match arms.as_slice() {
+                [first, second] if is_diverging(&second.expr()?) => (first, second),
+                [first, second] if is_diverging(&first.expr()?) => (second, first),
+                _ => return None,
+            };
+            if happy_arm.guard().is_some() || sad_arm.guard().is_some() {
+                return None;
+            }
+            // The `else` block can't use anything bound by the pattern.
+            if !bindings(ctx, &sad_arm.pat()?).is_empty() {
+                return None;
+            }
+            (happy_arm.pat()?, match_expr.expr()?, happy_arm.expr()?, sad_arm.expr()?)
+        }
+        _ => return None,
+    };
+    if !is_diverging(&else_expr) {
+        return None;
+    }
+    let new_pat = rebind_pattern(ctx, &pat, &let_pat, &value)?;
+
+    let target = let_stmt.syntax().text_range();
+    acc.add(
+        AssistId("convert_to_let_else", AssistKind::RefactorRewrite),
+        "Convert to let-else",
+        target,
+        |edit| {
+            let else_block = match else_expr {
+                ast::Expr::BlockExpr(block) => block.to_string(),
+                expr => format!("{{ {} }}", expr),
+            };
+            edit.replace(target, format!("let {} = {} else {};", new_pat, scrutinee, else_block));
+        },
+    )
+}
+
+/// Renders `pat` with the bindings that make up `value` renamed to the
+/// corresponding bindings of `let_pat`, and all other bindings replaced with `_`.
+fn rebind_pattern(
+    ctx: &AssistContext,
+    pat: &ast::Pat,
+    let_pat: &ast::Pat,
+    value: &ast::Expr,
+) -> Option<String> {
+    let pairs = match (let_pat, value) {
+        (ast::Pat::TuplePat(let_pat), ast::Expr::TupleExpr(value)) => {
+            let let_pats = let_pat.fields().collect::<Vec<_>>();
+            let values = value.fields().collect::<Vec<_>>();
+            if let_pats.len() != values.len() {
+                return None;
+            }
+            let_pats.into_iter().zip(values).collect()
+        }
+        _ => vec![(let_pat.clone(), value.clone())],
+    };
+
+    let bindings = bindings(ctx, pat);
+    let mut renames: Vec<(ast::IdentPat, String)> = Vec::new();
+    for (let_pat, value) in pairs {
+        let let_pat = match let_pat {
+            ast::Pat::IdentPat(it) if it.pat().is_none() => it,
+            _ => return None,
+        };
+        let path = match value {
+            ast::Expr::PathExpr(it) => it.path()?,
+            _ => return None,
+        };
+        if path.qualifier().is_some() {
+            return None;
+        }
+        let name = path.segment()?.name_ref()?;
+        let binding = bindings
+            .iter()
+            .find(|binding| binding.name().map_or(false, |it| it.text() == name.text()))?;
+        if renames.iter().any(|(it, _)| it == binding) {
+            return None;
+        }
+        let new_binding = rebind(binding, &let_pat)?;
+        renames.push((binding.clone(), new_binding));
+    }
+    for binding in bindings {
+        if !renames.iter().any(|(it, _)| *it == binding) {
+            renames.push((binding, "_".to_string()));
+        }
+    }
+
+    let pat_start = pat.syntax().text_range().start();
+    let mut text = pat.to_string();
+    renames.sort_by_key(|(binding, _)| Reverse(binding.syntax().text_range().start()));
+    for (binding, new_text) in renames {
+        // Shorthand record fields have to spell out the field name now.
+        let new_text = match binding.name() {
+            Some(name) if ast::RecordPatField::for_field_name(&name).is_some() => {
+                format!("{}: {}", name, new_text)
+            }
+            _ => new_text,
+        };
+        let range = binding.syntax().text_range() - pat_start;
+        text.replace_range(ops::Range::<usize>::from(range), &new_text);
+    }
+    Some(text)
+}
+
+/// Renders `binding` renamed to `let_pat`, which is bound to its value. A
+/// `let` binding can't be both mutable and a reference into the matched
+/// value, or a reference to one.
+fn rebind(binding: &ast::IdentPat, let_pat: &ast::IdentPat) -> Option<String> {
+    let binds_by_ref = binding.ref_token().is_some();
+    let (ref_kw, mut_kw) = match (binds_by_ref, let_pat.ref_token().is_some()) {
+        (true, false) if let_pat.mut_token().is_none() => (true, binding.mut_token().is_some()),
+        (false, by_ref) => (by_ref, let_pat.mut_token().is_some()),
+        _ => return None,
+    };
+    let mut res = String::new();
+    if ref_kw {
+        res.push_str("ref ");
+    }
+    if mut_kw {
+        res.push_str("mut ");
+    }
+    res.push_str(let_pat.name()?.text());
+    Some(res)
+}
+
+fn bindings(ctx: &AssistContext, pat: &ast::Pat) -> Vec<ast::IdentPat> {
+    pat.syntax()
+        .descendants()
+        .filter_map(ast::IdentPat::cast)
+        .filter(|it| ctx.sema.resolve_bind_pat_to_const(it).is_none())
+        .collect()
+}
+
+fn is_diverging(expr: &ast::Expr) -> bool {
+    match expr {
+        ast::Expr::ReturnExpr(_) | ast::Expr::BreakExpr(_) | ast::Expr::ContinueExpr(_) => true,
+        ast::Expr::MacroCall(call) => {
+            let name = call.path().and_then(|it| it.segment()).and_then(|it| it.name_ref());
+            name.map_or(false, |it| {
+                matches!(it.text(), "panic" | "unreachable" | "todo" | "unimplemented")
+            })
+        }
+        ast::Expr::BlockExpr(block) => match block.tail_expr() {
+            Some(tail) => is_diverging(&tail),
+            None => match block.statements().last() {
+                Some(ast::Stmt::ExprStmt(stmt)) => {
+                    stmt.expr().map_or(false, |it| is_diverging(&it))
+                }
+                _ => false,
+            },
+        },
+        _ => false,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::tests::{check_assist, check_assist_not_applicable};
@@ -512,4 +709,153 @@ mod tests {
             "#,
         );
     }
+
+    #[test]
+    fn convert_if_let_to_let_else() {
+        check_assist(
+            convert_to_let_else,
+            r#"
+fn main(opt: Option<(i32, i32)>) {
+    let mut x = i$0f let Some((x, _)) = opt { x } else { return };
+    x += 1;
+}
+"#,
+            r#"
+fn main(opt: Option<(i32, i32)>) {
+    let Some((mut x, _)) = opt else { return };
+    x += 1;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_guarded_match_to_let_else() {
+        check_assist(
+            convert_to_let_else,
+            r#"
+struct Pair { first: u32, second: u32 }
+
+fn main(pair: Option<Pair>) {
+    loop {
+        let ($0a, b) = match pair {
+            Some(Pair { first, second }) => (second, first),
+            _ => continue,
+        };
+    }
+}
+"#,
+            r#"
+struct Pair { first: u32, second: u32 }
+
+fn main(pair: Option<Pair>) {
+    loop {
+        let Some(Pair { first: b, second: a }) = pair else { continue };
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_to_let_else_unused_bindings() {
+        check_assist(
+            convert_to_let_else,
+            r#"
+fn main(res: Result<(u8, u8), ()>) {
+    let $0x = match res {
+        Ok((x, y)) => x,
+        Err(_) => panic!(),
+    };
+}
+"#,
+            r#"
+fn main(res: Result<(u8, u8), ()>) {
+    let Ok((x, _)) = res else { panic!() };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_to_let_else_mut_binding() {
+        check_assist(
+            convert_to_let_else,
+            r#"
+fn main(res: Result<i32, ()>) {
+    let $0mut x = match res {
+        Ok(y) => y,
+        Err(_) => return,
+    };
+}
+"#,
+            r#"
+fn main(res: Result<i32, ()>) {
+    let Ok(mut x) = res else { return };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_to_let_else_ref_binding() {
+        check_assist(
+            convert_to_let_else,
+            r#"
+fn main(res: Result<i32, ()>) {
+    let $0x = match res {
+        Ok(ref mut y) => y,
+        Err(_) => return,
+    };
+}
+"#,
+            r#"
+fn main(res: Result<i32, ()>) {
+    let Ok(ref mut x) = res else { return };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_to_let_else_not_applicable_to_mut_reference() {
+        check_assist_not_applicable(
+            convert_to_let_else,
+            r#"
+fn main(res: Result<i32, ()>) {
+    let $0mut x = match res {
+        Ok(ref y) => y,
+        Err(_) => return,
+    };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_to_let_else_not_applicable_without_divergence() {
+        check_assist_not_applicable(
+            convert_to_let_else,
+            r#"
+fn main(opt: Option<i32>) {
+    let $0x = if let Some(x) = opt { x } else { 0 };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_to_let_else_not_applicable_when_else_uses_binding() {
+        check_assist_not_applicable(
+            convert_to_let_else,
+            r#"
+fn main(res: Result<i32, i32>) {
+    let $0x = match res {
+        Ok(x) => x,
+        Err(e) => return,
+    };
+}
+"#,
+        );
+    }
 }
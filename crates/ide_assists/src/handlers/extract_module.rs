@@ -0,0 +1,416 @@
+use hir::{Adt, AsAssocItem, ModuleDef, PathResolution, ScopeDef};
+use ide_db::{base_db::FileRange, defs::Definition};
+use stdx::format_to;
+use syntax::{
+    ast::{self, edit::IndentLevel},
+    match_ast, AstNode, NodeOrToken,
+    SyntaxKind::*,
+    TextRange,
+};
+
+use crate::{
+    utils::{inner_private_defs, item_needs_visibility, reindent, used_outside, vis_offset},
+    AssistContext, AssistId, AssistKind, Assists,
+};
+
+const MODULE_NAME: &str = "modname";
+
+// Assist: extract_module
+//
+// Extracts the selected items into a new module.
+//
+// ```
+// const LIMIT: u32 = 10;
+//
+// $0fn clamp(x: u32) -> u32 {
+//     x.min(LIMIT)
+// }$0
+//
+// fn main() {
+//     clamp(92);
+// }
+// ```
+// ->
+// ```
+// const LIMIT: u32 = 10;
+//
+// mod modname {
+//     use super::LIMIT;
+//
+//     pub(crate) fn clamp(x: u32) -> u32 {
+//         x.min(LIMIT)
+//     }
+// }
+//
+// use modname::clamp;
+//
+// fn main() {
+//     clamp(92);
+// }
+// ```
+pub(crate) fn extract_module(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let selection = ctx.frange.range;
+    if selection.is_empty() {
+        return None;
+    }
+    let node = match ctx.covering_element() {
+        NodeOrToken::Node(it) => it,
+        NodeOrToken::Token(it) => it.parent(),
+    };
+    let item_list = node.ancestors().find(|it| matches!(it.kind(), SOURCE_FILE | ITEM_LIST))?;
+    let items: Vec<ast::Item> = item_list
+        .children()
+        .filter_map(ast::Item::cast)
+        .filter(|item| {
+            item.syntax().text_range().intersect(selection).map_or(false, |range| !range.is_empty())
+        })
+        .collect();
+    if items.is_empty() {
+        return None;
+    }
+    if items.iter().any(|item| !selection.contains_range(item.syntax().text_range())) {
+        cov_mark::hit!(extract_module_partial_item);
+        return None;
+    }
+    let mut defs = Vec::new();
+    for item in &items {
+        match item_def(ctx, item) {
+            Some(Some(def)) => defs.push(def),
+            Some(None) => (),
+            None => return None,
+        }
+    }
+
+    let db = ctx.db();
+    let first = items.first()?;
+    let last = items.last()?;
+    let source_module = ctx.sema.scope(first.syntax()).module()?;
+    let scope = source_module.scope(db, None);
+    if scope.iter().any(|(name, _)| name.to_string() == MODULE_NAME) {
+        return None;
+    }
+    let moved = FileRange {
+        file_id: ctx.frange.file_id,
+        range: first.syntax().text_range().cover(last.syntax().text_range()),
+    };
+
+    acc.add(
+        AssistId("extract_module", AssistKind::RefactorExtract),
+        "Extract module",
+        moved.range,
+        |builder| {
+            let mut edits = Vec::new();
+            let mut imports = Vec::new();
+
+            // Names the items got from the parent module have to be imported
+            // into the new one.
+            for item in &items {
+                for node in item.syntax().descendants() {
+                    match_ast! {
+                        match node {
+                            ast::Path(path) => {
+                                if path.qualifier().is_some() {
+                                    continue;
+                                }
+                                let name_ref = match path.segment().and_then(|it| it.name_ref()) {
+                                    Some(it) => it,
+                                    None => continue,
+                                };
+                                let def = match ctx.sema.resolve_path(&path) {
+                                    Some(PathResolution::Def(it)) => it,
+                                    _ => continue,
+                                };
+                                if defs.contains(&def) {
+                                    continue;
+                                }
+                                let range = name_ref.syntax().text_range() - moved.range.start();
+                                if name_ref.self_token().is_some() {
+                                    edits.push((range, "super".to_string()));
+                                } else if name_ref.super_token().is_some() {
+                                    edits.push((range, "super::super".to_string()));
+                                } else if scope.iter().any(|(name, scope_def)| {
+                                    name.to_string() == name_ref.text()
+                                        && *scope_def == ScopeDef::ModuleDef(def)
+                                }) {
+                                    imports.push(name_ref.text().to_string());
+                                }
+                            },
+                            ast::MethodCallExpr(call) => {
+                                let trait_ = ctx
+                                    .sema
+                                    .resolve_method_call(&call)
+                                    .and_then(|it| it.as_assoc_item(db))
+                                    .and_then(|it| it.containing_trait(db));
+                                let trait_ = match trait_ {
+                                    Some(it) => ModuleDef::Trait(it),
+                                    None => continue,
+                                };
+                                if defs.contains(&trait_) {
+                                    continue;
+                                }
+                                let name = scope.iter().find_map(|(name, scope_def)| {
+                                    (*scope_def == ScopeDef::ModuleDef(trait_)).then(|| name)
+                                });
+                                if let Some(name) = name {
+                                    imports.push(name.to_string());
+                                }
+                            },
+                            _ => (),
+                        }
+                    }
+                }
+
+                // Whatever is still used from outside has to become visible.
+                if let Some(def) = item_def(ctx, item).flatten() {
+                    if item_needs_visibility(item)
+                        && used_outside(ctx, Definition::ModuleDef(def), moved, None)
+                    {
+                        let offset = vis_offset(item.syntax()) - moved.range.start();
+                        edits.push((TextRange::empty(offset), "pub(crate) ".to_string()));
+                    }
+                }
+                for (node, def) in inner_private_defs(ctx, item) {
+                    if used_outside(ctx, def, moved, None) {
+                        let offset = vis_offset(&node) - moved.range.start();
+                        edits.push((TextRange::empty(offset), "pub(crate) ".to_string()));
+                    }
+                }
+            }
+
+            // Paths leading to the items from the outside gain the new module
+            // as a segment, unqualified uses in the parent get an import.
+            let mut parent_imports = Vec::new();
+            let mut used_outside_parent = false;
+            for def in &defs {
+                let usages = Definition::ModuleDef(*def).usages(&ctx.sema).all();
+                for (file_id, references) in usages {
+                    for reference in references {
+                        if file_id == moved.file_id && moved.range.contains_range(reference.range) {
+                            continue;
+                        }
+                        let name_ref = match reference.name {
+                            ast::NameLike::NameRef(it) => it,
+                            _ => continue,
+                        };
+                        let path = match name_ref.syntax().parent().and_then(ast::PathSegment::cast)
+                        {
+                            Some(segment) => segment.parent_path(),
+                            None => continue,
+                        };
+                        let module = match ctx.sema.scope(path.syntax()).module() {
+                            Some(it) => it,
+                            None => continue,
+                        };
+                        if !module.path_to_root(db).contains(&source_module) {
+                            used_outside_parent = true;
+                        }
+                        let in_use_tree = path.syntax().ancestors().any(|it| it.kind() == USE_TREE);
+                        if path.qualifier().is_some() || in_use_tree {
+                            builder.edit_file(file_id);
+                            builder.insert(
+                                name_ref.syntax().text_range().start(),
+                                format!("{}::", MODULE_NAME),
+                            );
+                        } else if module == source_module {
+                            parent_imports.push(name_ref.text().to_string());
+                        }
+                    }
+                }
+            }
+
+            let list_text = item_list.to_string();
+            let mut text = list_text[moved.range - item_list.text_range().start()].to_string();
+            edits.sort_by_key(|(range, _)| range.start());
+            for (range, replacement) in edits.into_iter().rev() {
+                text.replace_range(std::ops::Range::<usize>::from(range), &replacement);
+            }
+
+            let indent = IndentLevel::from_node(first.syntax());
+            let mut buf = String::new();
+            if used_outside_parent {
+                buf.push_str("pub(crate) ");
+            }
+            format_to!(buf, "mod {} {{\n", MODULE_NAME);
+            if !imports.is_empty() {
+                format_to!(buf, "{}use super::{};\n\n", indent + 1, use_tree_text(imports));
+            }
+            format_to!(buf, "{}{}\n{}}}", indent + 1, reindent(&text, indent, indent + 1), indent);
+            if !parent_imports.is_empty() {
+                format_to!(
+                    buf,
+                    "\n\n{}use {}::{};",
+                    indent,
+                    MODULE_NAME,
+                    use_tree_text(parent_imports)
+                );
+            }
+            builder.edit_file(moved.file_id);
+            builder.replace(moved.range, buf);
+        },
+    )
+}
+
+/// Returns `None` for items that can't be extracted, and `Some(None)` for the
+/// ones that have no definition of their own.
+fn item_def(ctx: &AssistContext, item: &ast::Item) -> Option<Option<ModuleDef>> {
+    let def = match item {
+        ast::Item::Fn(it) => ModuleDef::Function(ctx.sema.to_def(it)?),
+        ast::Item::Struct(it) => ModuleDef::Adt(Adt::Struct(ctx.sema.to_def(it)?)),
+        ast::Item::Enum(it) => ModuleDef::Adt(Adt::Enum(ctx.sema.to_def(it)?)),
+        ast::Item::Union(it) => ModuleDef::Adt(Adt::Union(ctx.sema.to_def(it)?)),
+        ast::Item::Const(it) => ModuleDef::Const(ctx.sema.to_def(it)?),
+        ast::Item::Static(it) => ModuleDef::Static(ctx.sema.to_def(it)?),
+        ast::Item::Trait(it) => ModuleDef::Trait(ctx.sema.to_def(it)?),
+        ast::Item::TypeAlias(it) => ModuleDef::TypeAlias(ctx.sema.to_def(it)?),
+        ast::Item::Impl(_) => return Some(None),
+        _ => return None,
+    };
+    Some(Some(def))
+}
+
+fn use_tree_text(mut names: Vec<String>) -> String {
+    names.sort();
+    names.dedup();
+    if names.len() == 1 {
+        names.pop().unwrap()
+    } else {
+        format!("{{{}}}", names.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn extract_items_with_imports() {
+        check_assist(
+            extract_module,
+            r#"
+mod a {
+    pub trait Greet { fn greet(&self); }
+    pub struct Bar;
+    impl Greet for Bar { fn greet(&self) {} }
+
+    $0pub struct Foo;
+
+    impl Foo {
+        fn new() -> Foo { Foo }
+    }
+
+    fn make() -> Bar {
+        Bar.greet();
+        Bar
+    }$0
+}
+
+fn main() {
+    let _ = a::Foo;
+}
+"#,
+            r#"
+mod a {
+    pub trait Greet { fn greet(&self); }
+    pub struct Bar;
+    impl Greet for Bar { fn greet(&self) {} }
+
+    pub(crate) mod modname {
+        use super::{Bar, Greet};
+
+        pub struct Foo;
+
+        impl Foo {
+            fn new() -> Foo { Foo }
+        }
+
+        fn make() -> Bar {
+            Bar.greet();
+            Bar
+        }
+    }
+}
+
+fn main() {
+    let _ = a::modname::Foo;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn extract_makes_used_fields_visible() {
+        check_assist(
+            extract_module,
+            r#"
+$0struct Point {
+    x: i32,
+    y: i32,
+}$0
+
+fn norm(p: Point) -> i32 {
+    p.x
+}
+"#,
+            r#"
+mod modname {
+    pub(crate) struct Point {
+        pub(crate) x: i32,
+        y: i32,
+    }
+}
+
+use modname::Point;
+
+fn norm(p: Point) -> i32 {
+    p.x
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn extract_adjusts_relative_paths() {
+        check_assist(
+            extract_module,
+            r#"
+mod a {
+    fn helper() {}
+
+    $0fn f() {
+        self::helper();
+        super::g();
+    }$0
+}
+fn g() {}
+"#,
+            r#"
+mod a {
+    fn helper() {}
+
+    mod modname {
+        fn f() {
+            super::helper();
+            super::super::g();
+        }
+    }
+}
+fn g() {}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_part_of_an_item() {
+        cov_mark::check!(extract_module_partial_item);
+        check_assist_not_applicable(
+            extract_module,
+            r#"
+fn foo() {
+    $0let x = 1;$0
+}
+"#,
+        );
+    }
+}
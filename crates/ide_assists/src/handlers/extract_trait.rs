@@ -0,0 +1,451 @@
+use hir::{Module, ModuleDef};
+use ide_db::{
+    defs::Definition,
+    helpers::{
+        insert_use::{insert_use, ImportScope},
+        mod_path_to_ast,
+    },
+};
+use itertools::Itertools;
+use rustc_hash::{FxHashMap, FxHashSet};
+use stdx::format_to;
+use syntax::{
+    algo::SyntaxRewriter,
+    ast::{
+        self,
+        edit::{self, IndentLevel},
+        make, AstNode, GenericParamsOwner, NameOwner, VisibilityOwner,
+    },
+    SyntaxKind::*,
+    TextRange,
+};
+
+use crate::{utils::range_with_trailing_whitespace, AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: extract_trait
+//
+// Extracts the methods of an inherent impl (or the selected ones) into a new trait.
+//
+// ```
+// struct Foo;
+//
+// impl$0 Foo {
+//     pub fn frobnicate(&self) -> u32 { 92 }
+// }
+// ```
+// ->
+// ```
+// struct Foo;
+//
+// pub trait FooExt {
+//     fn frobnicate(&self) -> u32;
+// }
+//
+// impl FooExt for Foo {
+//     fn frobnicate(&self) -> u32 { 92 }
+// }
+// ```
+pub(crate) fn extract_trait(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let impl_def = ctx.find_node_at_offset::<ast::Impl>()?;
+    if impl_def.trait_().is_some() {
+        return None;
+    }
+    let self_name = match impl_def.self_ty()? {
+        ast::Type::PathType(path_ty) => path_ty.path()?.segment()?.name_ref()?,
+        _ => return None,
+    };
+    let assoc_items = impl_def.assoc_item_list()?;
+
+    let selection = ctx.frange.range;
+    let all_fns = assoc_items.assoc_items().filter_map(|item| match item {
+        ast::AssocItem::Fn(func) => Some(func),
+        _ => None,
+    });
+    let selected: Vec<ast::Fn> = if selection.is_empty() {
+        // Without a selection, only offer the assist on the impl header.
+        if assoc_items.syntax().text_range().contains(selection.start()) {
+            return None;
+        }
+        all_fns.collect()
+    } else {
+        if !impl_def.syntax().text_range().contains_range(selection) {
+            return None;
+        }
+        all_fns
+            .filter(|func| {
+                func.syntax()
+                    .text_range()
+                    .intersect(selection)
+                    .map_or(false, |range| !range.is_empty())
+            })
+            .collect()
+    };
+    if selected.is_empty() || selected.iter().any(|func| func.const_token().is_some()) {
+        return None;
+    }
+    // The trait has no generic parameters, so its methods can't use the ones
+    // of the impl.
+    let generic_params = generic_param_names(&impl_def);
+    if selected.iter().any(|func| mentions_any(func, &generic_params)) {
+        cov_mark::hit!(extract_trait_generic_impl);
+        return None;
+    }
+    let self_ty = impl_def.self_ty()?;
+
+    let impl_hir = ctx.sema.to_def(&impl_def)?;
+    let module = impl_hir.module(ctx.db());
+    let trait_name = format!("{}Ext", self_name);
+    let name_is_taken =
+        module.scope(ctx.db(), None).into_iter().any(|(name, _)| name.to_string() == trait_name);
+    if name_is_taken {
+        cov_mark::hit!(extract_trait_name_taken);
+        return None;
+    }
+
+    let moves_everything = assoc_items.assoc_items().count() == selected.len();
+    let target = impl_def.syntax().text_range();
+    acc.add(
+        AssistId("extract_trait", AssistKind::RefactorExtract),
+        "Extract trait",
+        target,
+        |builder| {
+            let indent = IndentLevel::from_node(impl_def.syntax());
+            let vis = selected
+                .iter()
+                .find_map(|func| func.visibility())
+                .map_or(String::new(), |vis| format!("{} ", vis));
+
+            let mut trait_def = format!("{}trait {} {{", vis, trait_name);
+            for func in &selected {
+                let signature =
+                    edit::remove_attrs_and_docs(func).without_visibility().without_body();
+                format_to!(trait_def, "\n{}{}", indent + 1, signature);
+            }
+            format_to!(trait_def, "\n{}}}\n\n{}", indent, indent);
+            builder.insert(impl_def.syntax().text_range().start(), trait_def);
+
+            if moves_everything {
+                builder
+                    .insert(self_ty.syntax().text_range().start(), format!("{} for ", trait_name));
+                for func in &selected {
+                    if let Some(vis) = func.visibility() {
+                        builder.delete(range_with_trailing_whitespace(vis.syntax()));
+                    }
+                }
+            } else {
+                let mut trait_impl = format!("\n\n{}impl", indent);
+                if let Some(generic_params) = impl_def.generic_param_list() {
+                    format_to!(trait_impl, "{}", generic_params);
+                }
+                format_to!(trait_impl, " {} for {}", trait_name, self_ty);
+                if let Some(where_clause) = impl_def.where_clause() {
+                    format_to!(trait_impl, " {}", where_clause);
+                }
+                trait_impl.push_str(" {");
+                for func in &selected {
+                    format_to!(trait_impl, "\n{}{}", indent + 1, func.without_visibility());
+                    builder.delete(range_with_leading_whitespace(func.syntax()));
+                }
+                format_to!(trait_impl, "\n{}}}", indent);
+                builder.insert(impl_def.syntax().text_range().end(), trait_impl);
+            }
+
+            // The new trait has to be in scope wherever one of its methods is used.
+            let mut rewriters = FxHashMap::default();
+            let mut visited_modules = FxHashSet::default();
+            visited_modules.insert(module);
+            for func in &selected {
+                let def = match ctx.sema.to_def(func) {
+                    Some(it) => Definition::ModuleDef(ModuleDef::Function(it)),
+                    None => continue,
+                };
+                for (file_id, references) in def.usages(&ctx.sema).all() {
+                    for reference in references {
+                        let usage_module = match ctx.sema.scope(reference.name.syntax()).module() {
+                            Some(it) => it,
+                            None => continue,
+                        };
+                        if !visited_modules.insert(usage_module) {
+                            continue;
+                        }
+                        let rewriter =
+                            rewriters.entry(file_id).or_insert_with(SyntaxRewriter::default);
+                        insert_trait_import(
+                            ctx,
+                            rewriter,
+                            &reference.name,
+                            usage_module,
+                            module,
+                            &trait_name,
+                        );
+                    }
+                }
+            }
+            for (file_id, rewriter) in rewriters.into_iter().sorted_by_key(|(file_id, _)| *file_id)
+            {
+                builder.edit_file(file_id);
+                builder.rewrite(rewriter);
+            }
+        },
+    )
+}
+
+fn insert_trait_import(
+    ctx: &AssistContext,
+    rewriter: &mut SyntaxRewriter,
+    usage: &ast::NameLike,
+    usage_module: Module,
+    trait_module: Module,
+    trait_name: &str,
+) -> Option<()> {
+    let mod_path = usage_module.find_use_path_prefixed(
+        ctx.db(),
+        ModuleDef::Module(trait_module),
+        ctx.config.insert_use.prefix_kind,
+    )?;
+    let path = make::path_qualified(
+        mod_path_to_ast(&mod_path),
+        make::path_segment(make::name_ref(trait_name)),
+    );
+    let scope = ImportScope::find_insert_use_container(usage.syntax(), &ctx.sema)?;
+    *rewriter += insert_use(&scope, path, ctx.config.insert_use);
+    Some(())
+}
+
+fn generic_param_names(impl_def: &ast::Impl) -> Vec<String> {
+    let generic_params = match impl_def.generic_param_list() {
+        Some(it) => it.generic_params(),
+        None => return Vec::new(),
+    };
+    generic_params
+        .filter_map(|param| match param {
+            ast::GenericParam::TypeParam(it) => it.name().map(|it| it.to_string()),
+            ast::GenericParam::ConstParam(it) => it.name().map(|it| it.to_string()),
+            ast::GenericParam::LifetimeParam(it) => it.lifetime().map(|it| it.to_string()),
+        })
+        .collect()
+}
+
+/// Whether the signature of `func` uses one of `names`.
+fn mentions_any(func: &ast::Fn, names: &[String]) -> bool {
+    func.without_body()
+        .syntax()
+        .descendants_with_tokens()
+        .filter_map(|it| it.into_token())
+        .any(|token| names.iter().any(|name| token.text() == name.as_str()))
+}
+
+fn range_with_leading_whitespace(node: &syntax::SyntaxNode) -> TextRange {
+    match node.prev_sibling_or_token() {
+        Some(ws) if ws.kind() == WHITESPACE => node.text_range().cover(ws.text_range()),
+        _ => node.text_range(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn extract_whole_impl() {
+        check_assist(
+            extract_trait,
+            r#"
+struct Foo;
+
+impl$0 Foo {
+    /// Does the thing.
+    pub fn frobnicate(&self, x: u32) -> u32 { x }
+
+    fn bar(&mut self) {}
+}
+"#,
+            r#"
+struct Foo;
+
+pub trait FooExt {
+    fn frobnicate(&self, x: u32) -> u32;
+    fn bar(&mut self);
+}
+
+impl FooExt for Foo {
+    /// Does the thing.
+    fn frobnicate(&self, x: u32) -> u32 { x }
+
+    fn bar(&mut self) {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn extract_selected_methods() {
+        check_assist(
+            extract_trait,
+            r#"
+struct Foo;
+
+impl Foo {
+    fn new() -> Foo { Foo }
+
+    $0fn bar(&self) {}$0
+}
+"#,
+            r#"
+struct Foo;
+
+trait FooExt {
+    fn bar(&self);
+}
+
+impl Foo {
+    fn new() -> Foo { Foo }
+}
+
+impl FooExt for Foo {
+    fn bar(&self) {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn extract_trait_imports_at_call_sites() {
+        check_assist(
+            extract_trait,
+            r#"
+mod foo {
+    pub struct Foo;
+
+    impl$0 Foo {
+        pub fn bar(&self) {}
+    }
+
+    fn same_module(foo: Foo) {
+        foo.bar();
+    }
+}
+
+mod user {
+    fn f(foo: super::foo::Foo) {
+        foo.bar();
+    }
+}
+"#,
+            r#"
+mod foo {
+    pub struct Foo;
+
+    pub trait FooExt {
+        fn bar(&self);
+    }
+
+    impl FooExt for Foo {
+        fn bar(&self) {}
+    }
+
+    fn same_module(foo: Foo) {
+        foo.bar();
+    }
+}
+
+mod user {
+    use crate::foo::FooExt;
+
+    fn f(foo: super::foo::Foo) {
+        foo.bar();
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_inside_method_without_selection() {
+        check_assist_not_applicable(
+            extract_trait,
+            r#"
+struct Foo;
+impl Foo {
+    fn bar(&self) { $0 }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_trait_impl() {
+        check_assist_not_applicable(
+            extract_trait,
+            r#"
+trait Tr { fn bar(&self); }
+struct Foo;
+impl$0 Tr for Foo {
+    fn bar(&self) {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_generic_impl() {
+        cov_mark::check!(extract_trait_generic_impl);
+        check_assist_not_applicable(
+            extract_trait,
+            r#"
+struct Foo<T>(T);
+impl$0<T> Foo<T> {
+    fn bar(&self) -> &T { &self.0 }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn extract_selected_methods_of_generic_impl() {
+        check_assist(
+            extract_trait,
+            r#"
+struct Foo<T>(T);
+
+impl<T> Foo<T> where T: Clone {
+    fn get(&self) -> T { self.0.clone() }
+
+    $0fn bar(&self) {}$0
+}
+"#,
+            r#"
+struct Foo<T>(T);
+
+trait FooExt {
+    fn bar(&self);
+}
+
+impl<T> Foo<T> where T: Clone {
+    fn get(&self) -> T { self.0.clone() }
+}
+
+impl<T> FooExt for Foo<T> where T: Clone {
+    fn bar(&self) {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_when_name_is_taken() {
+        cov_mark::check!(extract_trait_name_taken);
+        check_assist_not_applicable(
+            extract_trait,
+            r#"
+struct Foo;
+trait FooExt {}
+impl$0 Foo {
+    fn bar(&self) {}
+}
+"#,
+        );
+    }
+}
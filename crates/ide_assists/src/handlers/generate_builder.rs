@@ -0,0 +1,342 @@
+use hir::Adt;
+use ide_db::helpers::FamousDefs;
+use itertools::Itertools;
+use stdx::format_to;
+use syntax::{
+    ast::{self, AstNode, GenericParamsOwner, NameOwner, StructKind, VisibilityOwner},
+    SmolStr,
+};
+
+use crate::{utils::generate_impl_text_for_name, AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: generate_builder
+//
+// Generates a builder for a struct. Fields of `Option` type are optional, the
+// others have to be set before calling `build`.
+//
+// ```
+// # //- /lib.rs crate:core
+// # pub mod option { pub enum Option<T> { None, Some(T) } }
+// # //- /main.rs crate:main deps:core
+// # use core::option::Option;
+// struct Config$0 {
+//     name: String,
+//     port: Option<u16>,
+// }
+// ```
+// ->
+// ```
+// # use core::option::Option;
+// struct Config {
+//     name: String,
+//     port: Option<u16>,
+// }
+//
+// struct ConfigBuilder {
+//     name: Option<String>,
+//     port: Option<u16>,
+// }
+//
+// impl ConfigBuilder {
+//     fn new() -> Self {
+//         Self { name: None, port: None }
+//     }
+//
+//     fn name(mut self, name: String) -> Self {
+//         self.name = Some(name);
+//         self
+//     }
+//
+//     fn port(mut self, port: u16) -> Self {
+//         self.port = Some(port);
+//         self
+//     }
+//
+//     fn $0build(self) -> Result<Config, &'static str> {
+//         Ok(Config {
+//             name: self.name.ok_or("`name` is required")?,
+//             port: self.port,
+//         })
+//     }
+// }
+// ```
+pub(crate) fn generate_builder(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let strukt = ctx.find_node_at_offset::<ast::Struct>()?;
+
+    // We want to only apply this to non-union structs with named fields
+    let field_list = match strukt.kind() {
+        StructKind::Record(named) => named,
+        _ => return None,
+    };
+    let strukt_name = strukt.name()?;
+    let builder_name = format!("{}Builder", strukt_name);
+
+    let module = ctx.sema.to_def(&strukt)?.module(ctx.db());
+    if module.scope(ctx.db(), None).iter().any(|(name, _)| name.to_string() == builder_name) {
+        cov_mark::hit!(builder_exists);
+        return None;
+    }
+    let option = FamousDefs(&ctx.sema, Some(module.krate())).core_option_Option();
+
+    let fields = field_list
+        .fields()
+        .map(|field| {
+            let name = field.name()?;
+            let ty = field.ty()?;
+            let is_option = ctx
+                .sema
+                .to_def(&field)
+                .and_then(|field| field.signature_ty(ctx.db()).as_adt())
+                .map_or(false, |adt| Some(adt) == option.map(Adt::Enum));
+            let inner_ty = if is_option { option_arg(&ty)? } else { ty };
+            Some(BuilderField { name, ty: inner_ty, optional: is_option })
+        })
+        .collect::<Option<Vec<_>>>()?;
+    if fields.is_empty() {
+        return None;
+    }
+
+    let target = strukt.syntax().text_range();
+    acc.add(
+        AssistId("generate_builder", AssistKind::Generate),
+        format!("Generate `{}`", builder_name),
+        target,
+        |builder| {
+            let vis = strukt.visibility().map_or(String::new(), |v| format!("{} ", v));
+
+            let mut buf = String::with_capacity(1024);
+            format_to!(buf, "\n\n{}struct {}", vis, builder_name);
+            if let Some(generic_params) = strukt.generic_param_list() {
+                format_to!(buf, "{}", generic_params);
+            }
+            match strukt.where_clause() {
+                Some(where_clause) => format_to!(buf, "\n{}\n{{\n", where_clause),
+                None => buf.push_str(" {\n"),
+            }
+            for field in &fields {
+                format_to!(buf, "    {}: Option<{}>,\n", field.name, field.ty);
+            }
+            buf.push('}');
+
+            let mut methods = Vec::new();
+            let nones = fields.iter().map(|field| format!("{}: None", field.name)).format(", ");
+            methods.push(format!(
+                "    {}fn new() -> Self {{\n        Self {{ {} }}\n    }}",
+                vis, nones
+            ));
+            for field in &fields {
+                methods.push(format!(
+                    "    {vis}fn {name}(mut self, {name}: {ty}) -> Self {{
+        self.{name} = Some({name});
+        self
+    }}",
+                    vis = vis,
+                    name = field.name,
+                    ty = field.ty,
+                ));
+            }
+            let mut build = String::new();
+            let strukt_ty = format!("{}{}", strukt_name, generic_args(&strukt));
+            format_to!(
+                build,
+                "    {}fn build(self) -> Result<{}, &'static str> {{\n        Ok({} {{\n",
+                vis,
+                strukt_ty,
+                strukt_name
+            );
+            for field in &fields {
+                if field.optional {
+                    format_to!(build, "            {name}: self.{name},\n", name = field.name);
+                } else {
+                    format_to!(
+                        build,
+                        "            {name}: self.{name}.ok_or(\"`{name}` is required\")?,\n",
+                        name = field.name
+                    );
+                }
+            }
+            build.push_str("        })\n    }");
+            methods.push(build);
+
+            let code = methods.join("\n\n");
+            buf.push_str(&generate_impl_text_for_name(
+                &ast::Adt::Struct(strukt.clone()),
+                &builder_name,
+                &code,
+            ));
+
+            let offset = strukt.syntax().text_range().end();
+            match ctx.config.snippet_cap {
+                None => builder.insert(offset, buf),
+                Some(cap) => {
+                    buf = buf.replace("fn build", "fn $0build");
+                    builder.insert_snippet(cap, offset, buf);
+                }
+            }
+        },
+    )
+}
+
+struct BuilderField {
+    name: ast::Name,
+    /// The type the setter takes, ie the field type with `Option` stripped
+    /// for optional fields.
+    ty: ast::Type,
+    optional: bool,
+}
+
+/// Returns `T` for `Option<T>`.
+fn option_arg(ty: &ast::Type) -> Option<ast::Type> {
+    let path = match ty {
+        ast::Type::PathType(it) => it.path()?,
+        _ => return None,
+    };
+    match path.segment()?.generic_arg_list()?.generic_args().exactly_one() {
+        Ok(ast::GenericArg::TypeArg(arg)) => arg.ty(),
+        _ => None,
+    }
+}
+
+fn generic_args(strukt: &ast::Struct) -> String {
+    let generic_params = match strukt.generic_param_list() {
+        Some(it) => it,
+        None => return String::new(),
+    };
+    let lifetime_params = generic_params
+        .lifetime_params()
+        .filter_map(|it| it.lifetime())
+        .map(|it| SmolStr::from(it.text()));
+    let type_params =
+        generic_params.type_params().filter_map(|it| it.name()).map(|it| SmolStr::from(it.text()));
+    format!("<{}>", lifetime_params.chain(type_params).format(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    fn check(before: &str, after: &str) {
+        let before = &format!("//- /main.rs crate:main deps:core{}{}", before, FamousDefs::FIXTURE);
+        check_assist(generate_builder, before, after);
+    }
+
+    #[test]
+    fn required_and_optional_fields() {
+        check(
+            r#"
+pub struct Server$0 {
+    host: String,
+    retries: Option<u8>,
+}
+"#,
+            r#"
+pub struct Server {
+    host: String,
+    retries: Option<u8>,
+}
+
+pub struct ServerBuilder {
+    host: Option<String>,
+    retries: Option<u8>,
+}
+
+impl ServerBuilder {
+    pub fn new() -> Self {
+        Self { host: None, retries: None }
+    }
+
+    pub fn host(mut self, host: String) -> Self {
+        self.host = Some(host);
+        self
+    }
+
+    pub fn retries(mut self, retries: u8) -> Self {
+        self.retries = Some(retries);
+        self
+    }
+
+    pub fn $0build(self) -> Result<Server, &'static str> {
+        Ok(Server {
+            host: self.host.ok_or("`host` is required")?,
+            retries: self.retries,
+        })
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn generic_struct() {
+        check(
+            r#"
+struct Pair<'a, T: Clone>$0 where T: Copy {
+    first: &'a T,
+    second: T,
+}
+"#,
+            r#"
+struct Pair<'a, T: Clone> where T: Copy {
+    first: &'a T,
+    second: T,
+}
+
+struct PairBuilder<'a, T: Clone>
+where T: Copy
+{
+    first: Option<&'a T>,
+    second: Option<T>,
+}
+
+impl<'a, T: Clone> PairBuilder<'a, T>
+where T: Copy
+{
+    fn new() -> Self {
+        Self { first: None, second: None }
+    }
+
+    fn first(mut self, first: &'a T) -> Self {
+        self.first = Some(first);
+        self
+    }
+
+    fn second(mut self, second: T) -> Self {
+        self.second = Some(second);
+        self
+    }
+
+    fn $0build(self) -> Result<Pair<'a, T>, &'static str> {
+        Ok(Pair {
+            first: self.first.ok_or("`first` is required")?,
+            second: self.second.ok_or("`second` is required")?,
+        })
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_tuple_structs() {
+        check_assist_not_applicable(generate_builder, r#"struct Foo$0(u32);"#);
+    }
+
+    #[test]
+    fn not_applicable_to_empty_structs() {
+        check_assist_not_applicable(generate_builder, r#"struct Foo$0 {}"#);
+    }
+
+    #[test]
+    fn not_applicable_if_builder_exists() {
+        cov_mark::check!(builder_exists);
+        check_assist_not_applicable(
+            generate_builder,
+            r#"
+struct Foo$0 { x: u32 }
+struct FooBuilder;
+"#,
+        );
+    }
+}
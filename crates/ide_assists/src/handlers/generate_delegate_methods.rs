@@ -0,0 +1,584 @@
+use hir::{AsAssocItem, HasSource, HasVisibility, ModuleDef};
+use ide_db::helpers::mod_path_to_ast;
+use rustc_hash::FxHashSet;
+use stdx::format_to;
+use syntax::{
+    algo::find_node_at_range,
+    ast::{self, make, AstNode, GenericParamsOwner, NameOwner, VisibilityOwner},
+    TextRange,
+};
+
+use crate::{
+    ast_transform::{self, AstTransform, QualifyPaths, SubstituteTypeParams},
+    utils::{
+        add_method_to_adt, add_trait_assoc_items_to_impl, filter_assoc_items, find_struct_impl,
+        generate_trait_impl_text, DefaultMethods,
+    },
+    AssistContext, AssistId, AssistKind, Assists, GroupLabel,
+};
+
+// Assist: generate_delegate_methods
+//
+// Generate a method forwarding to a method of the field's type.
+//
+// ```
+// struct Age(u8);
+// impl Age {
+//     fn age(&self) -> u8 {
+//         self.0
+//     }
+// }
+//
+// struct Person {
+//     ag$0e: Age,
+// }
+// ```
+// ->
+// ```
+// struct Age(u8);
+// impl Age {
+//     fn age(&self) -> u8 {
+//         self.0
+//     }
+// }
+//
+// struct Person {
+//     age: Age,
+// }
+//
+// impl Person {
+//     fn age(&self) -> u8 {
+//         self.age.age()
+//     }
+// }
+// ```
+pub(crate) fn generate_delegate_methods(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let field = DelegateField::at_cursor(ctx)?;
+    let db = ctx.db();
+    let scope = ctx.sema.scope(field.strukt.syntax());
+    let module = scope.module()?;
+
+    let mut methods = Vec::new();
+    let mut seen = FxHashSet::default();
+    field.hir_ty.iterate_method_candidates(
+        db,
+        module.krate(),
+        &scope.traits_in_scope(),
+        None,
+        |_, func| {
+            let is_inherent =
+                func.as_assoc_item(db).map_or(false, |it| it.containing_trait(db).is_none());
+            if is_inherent
+                && func.self_param(db).is_some()
+                && func.is_visible_from(db, module)
+                && seen.insert(func.name(db).to_string())
+            {
+                methods.push(func);
+            }
+            None::<()>
+        },
+    );
+
+    let adt = ast::Adt::Struct(field.strukt.clone());
+    let vis = field.strukt.visibility().map_or(String::new(), |v| format!("{} ", v));
+    for method in methods {
+        let name = method.name(db).to_string();
+        let impl_def = match find_struct_impl(ctx, &adt, &name) {
+            Some(it) => it,
+            None => continue,
+        };
+        let method = match delegate_method(ctx, &field, method, &vis) {
+            Some(it) => it,
+            None => continue,
+        };
+        acc.add_group(
+            &GroupLabel("Generate delegate methods…".to_owned()),
+            AssistId("generate_delegate_methods", AssistKind::Generate),
+            format!("Generate delegate for `{}.{}()`", field.access, name),
+            field.range,
+            |builder| add_method_to_adt(builder, &adt, impl_def, &method),
+        );
+    }
+    Some(())
+}
+
+// Assist: generate_delegate_trait
+//
+// Implement a trait of the field's type for the struct, forwarding every item
+// to the field.
+//
+// ```
+// trait Shape {
+//     fn area(&self) -> f64;
+// }
+// struct Circle(f64);
+// impl Shape for Circle {
+//     fn area(&self) -> f64 {
+//         3.14 * self.0 * self.0
+//     }
+// }
+//
+// struct Wheel {
+//     r$0im: Circle,
+// }
+// ```
+// ->
+// ```
+// trait Shape {
+//     fn area(&self) -> f64;
+// }
+// struct Circle(f64);
+// impl Shape for Circle {
+//     fn area(&self) -> f64 {
+//         3.14 * self.0 * self.0
+//     }
+// }
+//
+// struct Wheel {
+//     rim: Circle,
+// }
+//
+// impl Shape for Wheel {
+//     fn area(&self) -> f64 {
+//         <Circle as Shape>::area(&self.rim)
+//     }
+// }
+// ```
+pub(crate) fn generate_delegate_trait(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let field = DelegateField::at_cursor(ctx)?;
+    let db = ctx.db();
+    let module = ctx.sema.scope(field.strukt.syntax()).module()?;
+    let strukt_ty = ctx.sema.to_def(&field.strukt)?.ty(db);
+
+    let implemented: FxHashSet<_> =
+        hir::Impl::all_for_type(db, strukt_ty).into_iter().filter_map(|it| it.trait_(db)).collect();
+    let mut seen = FxHashSet::default();
+    let traits = hir::Impl::all_for_type(db, field.hir_ty.clone())
+        .into_iter()
+        .filter(|impl_| !impl_.is_negative(db))
+        .filter_map(|impl_| impl_.trait_(db))
+        .filter(|trait_| {
+            !trait_.is_auto(db)
+                && !implemented.contains(trait_)
+                // Only `Self`, the delegation can't guess the other arguments.
+                && hir::GenericDef::from(*trait_).type_params(db).len() == 1
+        })
+        .filter(|trait_| seen.insert(*trait_));
+
+    let adt = ast::Adt::Struct(field.strukt.clone());
+    for trait_ in traits {
+        let trait_path = match module.find_use_path(db, ModuleDef::Trait(trait_)) {
+            Some(it) => mod_path_to_ast(&it),
+            None => continue,
+        };
+        let items = match delegate_trait_items(ctx, &field, trait_, &trait_path) {
+            Some(it) => it,
+            None => continue,
+        };
+        acc.add_group(
+            &GroupLabel("Generate delegate methods…".to_owned()),
+            AssistId("generate_delegate_trait", AssistKind::Generate),
+            format!("Generate delegate impl `{}` for `{}`", trait_path, field.access),
+            field.range,
+            |builder| {
+                let impl_text = generate_trait_impl_text(&adt, &trait_path.to_string(), &items);
+                builder.insert(field.strukt.syntax().text_range().end(), impl_text);
+            },
+        );
+    }
+    Some(())
+}
+
+/// The field of a struct the generated items forward to.
+struct DelegateField {
+    strukt: ast::Struct,
+    /// The name of the field, or its index for tuple structs.
+    access: String,
+    ty: ast::Type,
+    hir_ty: hir::Type,
+    range: TextRange,
+}
+
+impl DelegateField {
+    fn at_cursor(ctx: &AssistContext) -> Option<DelegateField> {
+        let (node, access, ty, hir_field) =
+            if let Some(field) = ctx.find_node_at_offset::<ast::RecordField>() {
+                let hir_field = ctx.sema.to_def(&field)?;
+                (field.syntax().clone(), field.name()?.to_string(), field.ty()?, hir_field)
+            } else {
+                let field = ctx.find_node_at_offset::<ast::TupleField>()?;
+                let field_list = field.syntax().parent().and_then(ast::TupleFieldList::cast)?;
+                let idx = field_list.fields().position(|it| it == field)?;
+                let hir_field = ctx.sema.to_def(&field)?;
+                (field.syntax().clone(), idx.to_string(), field.ty()?, hir_field)
+            };
+        let strukt = node.ancestors().find_map(ast::Struct::cast)?;
+        let hir_ty = hir_field.signature_ty(ctx.db());
+        Some(DelegateField { strukt, access, ty, hir_ty, range: node.text_range() })
+    }
+}
+
+fn delegate_method(
+    ctx: &AssistContext,
+    field: &DelegateField,
+    method: hir::Function,
+    vis: &str,
+) -> Option<String> {
+    // Re-parse the method through `Semantics`, so that we can query scopes inside of it.
+    let src = method.source(ctx.db())?;
+    let source_file = ctx.sema.parse(src.file_id.original_file(ctx.db()));
+    let source: ast::Fn =
+        find_node_at_range(source_file.syntax(), src.value.syntax().text_range())?;
+    if source.syntax().text() != src.value.syntax().text() {
+        return None;
+    }
+    if source.param_list()?.self_param()?.ty().is_some() {
+        return None;
+    }
+    let impl_def = source.syntax().ancestors().find_map(ast::Impl::cast)?;
+
+    let target_scope = ctx.sema.scope(field.strukt.syntax());
+    let source_scope = ctx.sema.scope(source.syntax());
+    let transform = QualifyPaths::new(&target_scope, &source_scope)
+        .or(SubstituteTypeParams::for_inherent_impl(&source_scope, &impl_def, field.ty.clone()));
+    let func = ast_transform::apply(&*transform, source);
+
+    let call = format!("self.{}.{}({})", field.access, func.name()?, args(&func)?);
+    let mut buf = String::new();
+    format_to!(buf, "    {}", vis);
+    render_fn(&mut buf, &func, &call)?;
+    Some(buf)
+}
+
+fn delegate_trait_items(
+    ctx: &AssistContext,
+    field: &DelegateField,
+    trait_: hir::Trait,
+    trait_path: &ast::Path,
+) -> Option<String> {
+    let db = ctx.db();
+    let mut items = filter_assoc_items(db, &trait_.items(db), DefaultMethods::No);
+    items.extend(filter_assoc_items(db, &trait_.items(db), DefaultMethods::Only));
+    let target_scope = ctx.sema.scope(field.strukt.syntax());
+    let impl_def =
+        make::impl_trait(trait_path.clone(), make::path_from_text(&field.ty.to_string()));
+    let (impl_def, _) =
+        add_trait_assoc_items_to_impl(&ctx.sema, items, trait_, impl_def, target_scope);
+
+    let qualified =
+        |name: &dyn std::fmt::Display| format!("<{} as {}>::{}", field.ty, trait_path, name);
+    let items = impl_def.assoc_item_list()?.assoc_items().map(|item| {
+        let mut buf = String::from("    ");
+        match item {
+            ast::AssocItem::Fn(func) => {
+                let call = delegate_trait_call(field, &func, &qualified(&func.name()?));
+                render_fn(&mut buf, &func, call.as_deref().unwrap_or("todo!()"))?;
+            }
+            ast::AssocItem::TypeAlias(alias) => {
+                let name = alias.name()?;
+                format_to!(buf, "type {} = {};", name, qualified(&name));
+            }
+            ast::AssocItem::Const(const_) => {
+                let name = const_.name()?;
+                format_to!(buf, "const {}: {} = {};", name, const_.ty()?, qualified(&name));
+            }
+            ast::AssocItem::MacroCall(_) => return None,
+        }
+        Some(buf)
+    });
+    let items: Option<Vec<_>> = items.collect();
+    Some(items?.join("\n\n"))
+}
+
+/// Calls the item of the field's trait impl, or returns `None` when the
+/// signature refers to `Self` in a way forwarding can't handle.
+fn delegate_trait_call(field: &DelegateField, func: &ast::Fn, path: &str) -> Option<String> {
+    let param_list = func.param_list()?;
+    let mentions_self = |node: &syntax::SyntaxNode| {
+        node.descendants().filter_map(ast::PathType::cast).any(|it| it.syntax().text() == "Self")
+    };
+    let mut args = Vec::new();
+    if let Some(self_param) = param_list.self_param() {
+        if self_param.ty().is_some() {
+            return None;
+        }
+        let receiver = match (self_param.amp_token(), self_param.mut_token()) {
+            (Some(_), Some(_)) => "&mut self",
+            (Some(_), None) => "&self",
+            (None, _) => "self",
+        };
+        args.push(format!("{}.{}", receiver, field.access));
+    }
+    let other_args = args_of(&param_list)?;
+    if param_list.params().any(|param| mentions_self(param.syntax()))
+        || func.ret_type().map_or(false, |it| mentions_self(it.syntax()))
+    {
+        return None;
+    }
+    args.extend(other_args);
+    Some(format!("{}({})", path, args.join(", ")))
+}
+
+/// Renders `func` without its attributes, docs and visibility, with a body
+/// evaluating to `call`.
+fn render_fn(buf: &mut String, func: &ast::Fn, call: &str) -> Option<()> {
+    if func.const_token().is_some() {
+        buf.push_str("const ");
+    }
+    if func.async_token().is_some() {
+        buf.push_str("async ");
+    }
+    if func.unsafe_token().is_some() {
+        buf.push_str("unsafe ");
+    }
+    format_to!(buf, "fn {}", func.name()?);
+    if let Some(generic_params) = func.generic_param_list() {
+        format_to!(buf, "{}", generic_params);
+    }
+    format_to!(buf, "{}", func.param_list()?);
+    if let Some(ret_type) = func.ret_type() {
+        format_to!(buf, " {}", ret_type);
+    }
+    if let Some(where_clause) = func.where_clause() {
+        format_to!(buf, " {}", where_clause);
+    }
+    let await_ = if func.async_token().is_some() && call != "todo!()" { ".await" } else { "" };
+    format_to!(buf, " {{\n        {}{}\n    }}", call, await_);
+    Some(())
+}
+
+fn args(func: &ast::Fn) -> Option<String> {
+    Some(args_of(&func.param_list()?)?.join(", "))
+}
+
+fn args_of(param_list: &ast::ParamList) -> Option<Vec<String>> {
+    param_list
+        .params()
+        .map(|param| match param.pat()? {
+            ast::Pat::IdentPat(it) => Some(it.name()?.to_string()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_by_label, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn delegate_method_into_existing_impl() {
+        check_assist(
+            generate_delegate_methods,
+            r#"
+struct Age(u8);
+impl Age {
+    fn age(&self) -> u8 {
+        self.0
+    }
+}
+
+pub struct Person {
+    ag$0e: Age,
+}
+
+impl Person {
+    fn new() -> Person {
+        Person { age: Age(0) }
+    }
+}
+"#,
+            r#"
+struct Age(u8);
+impl Age {
+    fn age(&self) -> u8 {
+        self.0
+    }
+}
+
+pub struct Person {
+    age: Age,
+}
+
+impl Person {
+    fn new() -> Person {
+        Person { age: Age(0) }
+    }
+
+    pub fn age(&self) -> u8 {
+        self.age.age()
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn delegate_method_substitutes_type_params() {
+        check_assist_by_label(
+            generate_delegate_methods,
+            r#"
+mod inner {
+    pub struct Vec<T>(T);
+    pub struct Drain;
+    impl<T> Vec<T> {
+        pub fn len(&self) -> usize { 0 }
+        pub fn push(&mut self, value: T) {}
+        pub fn append(&mut self, other: &mut Self) -> Drain { Drain }
+        fn secret(&self) {}
+    }
+}
+
+struct Bytes(inner::V$0ec<u8>);
+"#,
+            r#"
+mod inner {
+    pub struct Vec<T>(T);
+    pub struct Drain;
+    impl<T> Vec<T> {
+        pub fn len(&self) -> usize { 0 }
+        pub fn push(&mut self, value: T) {}
+        pub fn append(&mut self, other: &mut Self) -> Drain { Drain }
+        fn secret(&self) {}
+    }
+}
+
+struct Bytes(inner::Vec<u8>);
+
+impl Bytes {
+    fn append(&mut self, other: &mut inner::Vec<u8>) -> inner::Drain {
+        self.0.append(other)
+    }
+}
+"#,
+            "Generate delegate for `0.append()`",
+        );
+    }
+
+    #[test]
+    fn delegate_method_skips_existing_and_private_methods() {
+        check_assist_not_applicable(
+            generate_delegate_methods,
+            r#"
+mod inner {
+    pub struct Age(u8);
+    impl Age {
+        pub fn age(&self) -> u8 { self.0 }
+        fn secret(&self) {}
+    }
+}
+
+struct Person {
+    ag$0e: inner::Age,
+}
+
+impl Person {
+    fn age(&self) -> u8 { 0 }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn delegate_trait_with_assoc_items() {
+        check_assist(
+            generate_delegate_trait,
+            r#"
+trait Shape {
+    type Unit;
+    const SIDES: usize;
+    fn area(&self) -> Self::Unit;
+    fn scale(&mut self, by: f64);
+    fn name() -> &'static str;
+    fn unit() -> Self;
+    fn describe(&self) -> String { String::new() }
+}
+struct Circle(f64);
+impl Shape for Circle {
+    type Unit = f64;
+    const SIDES: usize = 0;
+    fn area(&self) -> f64 { 3.14 * self.0 * self.0 }
+    fn scale(&mut self, by: f64) { self.0 *= by }
+    fn name() -> &'static str { "circle" }
+    fn unit() -> Self { Circle(1.0) }
+}
+
+struct Wheel {
+    r$0im: Circle,
+}
+"#,
+            r#"
+trait Shape {
+    type Unit;
+    const SIDES: usize;
+    fn area(&self) -> Self::Unit;
+    fn scale(&mut self, by: f64);
+    fn name() -> &'static str;
+    fn unit() -> Self;
+    fn describe(&self) -> String { String::new() }
+}
+struct Circle(f64);
+impl Shape for Circle {
+    type Unit = f64;
+    const SIDES: usize = 0;
+    fn area(&self) -> f64 { 3.14 * self.0 * self.0 }
+    fn scale(&mut self, by: f64) { self.0 *= by }
+    fn name() -> &'static str { "circle" }
+    fn unit() -> Self { Circle(1.0) }
+}
+
+struct Wheel {
+    rim: Circle,
+}
+
+impl Shape for Wheel {
+    type Unit = <Circle as Shape>::Unit;
+
+    const SIDES: usize = <Circle as Shape>::SIDES;
+
+    fn area(&self) -> Self::Unit {
+        <Circle as Shape>::area(&self.rim)
+    }
+
+    fn scale(&mut self, by: f64) {
+        <Circle as Shape>::scale(&mut self.rim, by)
+    }
+
+    fn name() -> &'static str {
+        <Circle as Shape>::name()
+    }
+
+    fn unit() -> Self {
+        todo!()
+    }
+
+    fn describe(&self) -> String {
+        <Circle as Shape>::describe(&self.rim)
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn delegate_trait_not_applicable_when_implemented() {
+        check_assist_not_applicable(
+            generate_delegate_trait,
+            r#"
+trait Shape {
+    fn area(&self) -> f64;
+}
+struct Circle(f64);
+impl Shape for Circle {
+    fn area(&self) -> f64 { 0.0 }
+}
+
+struct Wheel(Cir$0cle);
+impl Shape for Wheel {
+    fn area(&self) -> f64 { 0.0 }
+}
+"#,
+        );
+    }
+}
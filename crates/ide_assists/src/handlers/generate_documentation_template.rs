@@ -0,0 +1,251 @@
+use hir::{AsAssocItem, AssocItemContainer, HasAttrs, ModuleDef};
+use ide_db::helpers::FamousDefs;
+use itertools::Itertools;
+use stdx::{format_to, to_lower_snake_case};
+use syntax::ast::{self, edit::IndentLevel, AstNode, NameOwner, VisibilityOwner};
+
+use crate::{AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: generate_documentation_template
+//
+// Adds a documentation template above a public function without docs.
+//
+// ```
+// # //- /lib.rs crate:mylib
+// pub fn add$0(a: i32, b: i32) -> i32 { a + b }
+// ```
+// ->
+// ```
+// /// Add.
+// ///
+// /// # Examples
+// ///
+// /// ```
+// /// use mylib::add;
+// ///
+// /// assert_eq!(add(a, b), );
+// /// ```
+// pub fn add(a: i32, b: i32) -> i32 { a + b }
+// ```
+pub(crate) fn generate_documentation_template(
+    acc: &mut Assists,
+    ctx: &AssistContext,
+) -> Option<()> {
+    let func = ctx.find_node_at_offset::<ast::Fn>()?;
+    if let Some(body) = func.body() {
+        if body.syntax().text_range().start() <= ctx.offset() {
+            return None;
+        }
+    }
+    if func.visibility()?.syntax().text() != "pub" {
+        return None;
+    }
+    let name = func.name()?;
+    let def = ctx.sema.to_def(&func)?;
+    if def.docs(ctx.db()).is_some() {
+        cov_mark::hit!(already_documented);
+        return None;
+    }
+
+    let mut sections = vec![summary(name.text())];
+    sections.push(examples(ctx, &func, def)?);
+    let krate = def.module(ctx.db()).krate();
+    let result = FamousDefs(&ctx.sema, Some(krate)).core_result_Result();
+    if result.is_some() && def.ret_type(ctx.db()).as_adt() == result.map(hir::Adt::Enum) {
+        sections.push("# Errors\n\nThis function will return an error if .".to_string());
+    }
+    if func.body().map_or(false, |body| may_panic(&body)) {
+        sections.push("# Panics\n\nPanics if .".to_string());
+    }
+
+    let target = func.syntax().text_range();
+    acc.add(
+        AssistId("generate_documentation_template", AssistKind::Generate),
+        "Generate a documentation template",
+        target,
+        |builder| {
+            let indent = IndentLevel::from_node(func.syntax());
+            let mut buf = String::new();
+            for line in sections.join("\n\n").lines() {
+                match line {
+                    "" => format_to!(buf, "///\n{}", indent),
+                    _ => format_to!(buf, "/// {}\n{}", line, indent),
+                }
+            }
+            builder.insert(func.syntax().text_range().start(), buf);
+        },
+    )
+}
+
+/// Turns `parse_config` into `Parse config.`.
+fn summary(name: &str) -> String {
+    let words = name.trim_matches('_').replace('_', " ");
+    let mut chars = words.chars();
+    match chars.next() {
+        Some(first) => format!("{}{}.", first.to_uppercase(), chars.as_str()),
+        None => String::new(),
+    }
+}
+
+/// An `# Examples` section importing the function, or its `Self` type, by its
+/// path in the crate.
+fn examples(ctx: &AssistContext, func: &ast::Fn, def: hir::Function) -> Option<String> {
+    let db = ctx.db();
+    let name = def.name(db);
+    let args = func
+        .param_list()?
+        .params()
+        .map(|param| param.pat().map_or_else(|| "_".to_string(), |pat| pat.to_string()))
+        .join(", ");
+
+    let (item, call) = match def.as_assoc_item(db).map(|it| it.container(db)) {
+        None => (ModuleDef::Function(def), format!("{}({})", name, args)),
+        Some(AssocItemContainer::Impl(impl_)) => {
+            let adt = impl_.target_ty(db).as_adt()?;
+            let call = match func.param_list()?.self_param() {
+                Some(_) => {
+                    let receiver = to_lower_snake_case(&adt.name(db).to_string());
+                    format!("{}.{}({})", receiver, name, args)
+                }
+                None => format!("{}::{}({})", adt.name(db), name, args),
+            };
+            (ModuleDef::Adt(adt), call)
+        }
+        Some(AssocItemContainer::Trait(_)) => return None,
+    };
+
+    let mut buf = String::from("# Examples\n\n```\n");
+    let krate = def.module(db).krate();
+    if let Some(krate_name) = krate.display_name(db) {
+        if let Some(path) = krate.root_module(db).find_use_path(db, item) {
+            format_to!(buf, "use {}::{};\n\n", krate_name, path);
+        }
+    }
+    match func.ret_type() {
+        Some(_) => format_to!(buf, "assert_eq!({}, );\n", call),
+        None => format_to!(buf, "{};\n", call),
+    }
+    buf.push_str("```");
+    Some(buf)
+}
+
+fn may_panic(body: &ast::BlockExpr) -> bool {
+    body.syntax().descendants().any(|node| {
+        if let Some(call) = ast::MethodCallExpr::cast(node.clone()) {
+            return call.name_ref().map_or(false, |it| matches!(it.text(), "unwrap" | "expect"));
+        }
+        if let Some(mac) = ast::MacroCall::cast(node) {
+            return mac.path().map_or(false, |path| {
+                matches!(path.to_string().as_str(), "panic" | "unreachable" | "unimplemented")
+            });
+        }
+        false
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn method_with_errors_and_panics() {
+        let before = r#"
+pub mod config {
+    pub struct Parser;
+
+    impl Parser {
+        pub fn $0parse_line(&self, line: &str) -> core::result::Result<u32, ()> {
+            let n = line.parse().unwrap();
+            Ok(n)
+        }
+    }
+}
+"#;
+        check_assist(
+            generate_documentation_template,
+            &format!("//- /lib.rs crate:conf deps:core{}{}", before, FamousDefs::FIXTURE),
+            r#"
+pub mod config {
+    pub struct Parser;
+
+    impl Parser {
+        /// Parse line.
+        ///
+        /// # Examples
+        ///
+        /// ```
+        /// use conf::config::Parser;
+        ///
+        /// assert_eq!(parser.parse_line(line), );
+        /// ```
+        ///
+        /// # Errors
+        ///
+        /// This function will return an error if .
+        ///
+        /// # Panics
+        ///
+        /// Panics if .
+        pub fn parse_line(&self, line: &str) -> core::result::Result<u32, ()> {
+            let n = line.parse().unwrap();
+            Ok(n)
+        }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn associated_fn_without_return_type() {
+        check_assist(
+            generate_documentation_template,
+            r#"
+//- /lib.rs crate:app
+pub struct Window;
+impl Window {
+    pub fn open_$0all(path: &str, _: u32) { panic!() }
+}
+"#,
+            r#"
+pub struct Window;
+impl Window {
+    /// Open all.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use app::Window;
+    ///
+    /// Window::open_all(path, _);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if .
+    pub fn open_all(path: &str, _: u32) { panic!() }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_documented_fn() {
+        cov_mark::check!(already_documented);
+        check_assist_not_applicable(
+            generate_documentation_template,
+            r#"
+/// Does things.
+pub fn $0f() {}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_private_fn() {
+        check_assist_not_applicable(generate_documentation_template, r#"fn $0f() {}"#);
+        check_assist_not_applicable(generate_documentation_template, r#"pub(crate) fn $0f() {}"#);
+    }
+}
@@ -1,18 +1,19 @@
-use hir::HirDisplay;
+use hir::{Adt, HasSource, HirDisplay};
 use ide_db::{base_db::FileId, helpers::SnippetCap};
 use rustc_hash::{FxHashMap, FxHashSet};
-use stdx::to_lower_snake_case;
+use stdx::{format_to, to_lower_snake_case};
 use syntax::{
+    algo::find_node_at_range,
     ast::{
         self,
         edit::{AstNodeEdit, IndentLevel},
         make, ArgListOwner, AstNode, ModuleItemOwner,
     },
-    SyntaxKind, SyntaxNode, TextSize,
+    match_ast, SyntaxKind, SyntaxNode, TextSize,
 };
 
 use crate::{
-    utils::{render_snippet, Cursor},
+    utils::{find_impl_block_end, find_struct_impl, generate_impl_text, render_snippet, Cursor},
     AssistContext, AssistId, AssistKind, Assists,
 };
 
@@ -78,6 +79,169 @@ pub(crate) fn generate_function(acc: &mut Assists, ctx: &AssistContext) -> Optio
     )
 }
 
+// Assist: generate_method
+//
+// Adds a stub method to the inherent impl of the receiver's type, creating the impl
+// if there is none.
+//
+// ```
+// struct Foo;
+// fn foo(foo: Foo) {
+//     let count: usize = foo.bar$0(1, "x");
+// }
+// ```
+// ->
+// ```
+// struct Foo;
+//
+// impl Foo {
+//     fn bar(&self, arg_1: i32, arg_2: &str) -> usize {
+//         todo!()
+//     }
+// }
+// fn foo(foo: Foo) {
+//     let count: usize = foo.bar(1, "x");
+// }
+// ```
+pub(crate) fn generate_method(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let call: ast::MethodCallExpr = ctx.find_node_at_offset()?;
+    let name_ref = call.name_ref()?;
+    if !name_ref.syntax().text_range().contains_inclusive(ctx.offset()) {
+        return None;
+    }
+    if ctx.sema.resolve_method_call(&call).is_some() {
+        // The method call already resolves, no need to add a method
+        return None;
+    }
+
+    let receiver_ty = ctx.sema.type_of_expr(&call.receiver()?)?;
+    let adt = receiver_ty.remove_ref().unwrap_or(receiver_ty).as_adt()?;
+    let current_module = ctx.sema.scope(call.syntax()).module()?;
+    let target_module = adt.module(ctx.db());
+    if target_module.krate() != current_module.krate() {
+        return None;
+    }
+
+    // Re-parse the ADT through `Semantics`, so that we can look for its impls.
+    let src = match adt {
+        Adt::Struct(it) => it.source(ctx.db())?.map(ast::Adt::Struct),
+        Adt::Enum(it) => it.source(ctx.db())?.map(ast::Adt::Enum),
+        Adt::Union(it) => it.source(ctx.db())?.map(ast::Adt::Union),
+    };
+    let file = src.file_id.original_file(ctx.db());
+    let source_file = ctx.sema.parse(file);
+    let adt_source: ast::Adt =
+        find_node_at_range(source_file.syntax(), src.value.syntax().text_range())?;
+    if adt_source.syntax().text() != src.value.syntax().text() {
+        return None;
+    }
+
+    let fn_name = name_ref.text().to_string();
+    let impl_def = find_struct_impl(ctx, &adt_source, &fn_name)?;
+    let (_, params) = fn_args(ctx, target_module, call.arg_list()?)?;
+    let params = params.params().map(|param| format!(", {}", param)).collect::<String>();
+
+    // See `FunctionBuilder::from_call` for why the snippet is only rendered for
+    // a unit or unknown return type.
+    let ret_ty = expected_type(ctx, &call)
+        .filter(|ty| !ty.is_unknown() && !ty.is_unit())
+        .and_then(|ty| ty.display_source_code(ctx.db(), target_module.into()).ok());
+    let ret_type = match (&ret_ty, ctx.config.snippet_cap) {
+        (Some(ty), _) => format!("-> {} ", ty),
+        (None, Some(_)) => "${0:-> ()} ".to_string(),
+        (None, None) => String::new(),
+    };
+    let vis = if target_module == current_module { "" } else { "pub(crate) " };
+
+    let target = call.syntax().text_range();
+    acc.add(
+        AssistId("generate_method", AssistKind::Generate),
+        format!("Generate `{}` method", fn_name),
+        target,
+        |builder| {
+            let mut buf = String::new();
+            if impl_def.is_some() {
+                buf.push('\n');
+            }
+            format_to!(
+                buf,
+                "    {}fn {}(&self{}) {}{{\n        todo!()\n    }}",
+                vis,
+                fn_name,
+                params,
+                ret_type
+            );
+            let indent = IndentLevel::from_node(
+                impl_def.as_ref().map_or(adt_source.syntax(), |impl_def| impl_def.syntax()),
+            );
+            let start_offset = impl_def
+                .and_then(|impl_def| find_impl_block_end(impl_def, &mut buf))
+                .unwrap_or_else(|| {
+                    buf = generate_impl_text(&adt_source, &buf);
+                    adt_source.syntax().text_range().end()
+                });
+            let buf =
+                buf.split('\n')
+                    .map(|line| {
+                        if line.is_empty() {
+                            line.to_string()
+                        } else {
+                            format!("{}{}", indent, line)
+                        }
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n");
+
+            builder.edit_file(file);
+            match ctx.config.snippet_cap {
+                Some(cap) if ret_ty.is_none() => builder.insert_snippet(cap, start_offset, buf),
+                _ => builder.insert(start_offset, buf),
+            }
+        },
+    )
+}
+
+/// Infers the type that the result of an unresolved method call should have
+/// from the place it is used in, as the call itself is always typed `{unknown}`.
+fn expected_type(ctx: &AssistContext, call: &ast::MethodCallExpr) -> Option<hir::Type> {
+    let ty = ctx.sema.type_of_expr(&ast::Expr::MethodCallExpr(call.clone()));
+    if let Some(ty) = ty.filter(|ty| !ty.is_unknown()) {
+        return Some(ty);
+    }
+    let parent = call.syntax().parent()?;
+    match_ast! {
+        match parent {
+            ast::LetStmt(it) => {
+                it.ty()?;
+                ctx.sema.type_of_pat(&it.pat()?)
+            },
+            ast::ArgList(it) => {
+                let idx = it.args().position(|arg| arg.syntax() == call.syntax())?;
+                let callable = match_ast! {
+                    match (it.syntax().parent()?) {
+                        ast::CallExpr(call) => ctx.sema.type_of_expr(&call.expr()?)?.as_callable(ctx.db()),
+                        ast::MethodCallExpr(call) => ctx.sema.resolve_method_call_as_callable(&call),
+                        _ => None,
+                    }
+                }?;
+                callable.params(ctx.db()).into_iter().nth(idx).map(|(_, ty)| ty)
+            },
+            ast::ReturnExpr(it) => {
+                let func = it.syntax().ancestors().find_map(ast::Fn::cast)?;
+                Some(ctx.sema.to_def(&func)?.ret_type(ctx.db()))
+            },
+            ast::BlockExpr(it) => {
+                if it.tail_expr()?.syntax() != call.syntax() {
+                    return None;
+                }
+                let func = ast::Fn::cast(it.syntax().parent()?)?;
+                Some(ctx.sema.to_def(&func)?.ret_type(ctx.db()))
+            },
+            _ => None,
+        }
+    }
+}
+
 struct FunctionTemplate {
     insert_offset: TextSize,
     leading_ws: String,
@@ -133,7 +297,7 @@ impl FunctionBuilder {
         let needs_pub = target_module.is_some();
         let target_module = target_module.or_else(|| ctx.sema.scope(target.syntax()).module())?;
         let fn_name = fn_name(&path)?;
-        let (type_params, params) = fn_args(ctx, target_module, &call)?;
+        let (type_params, params) = fn_args(ctx, target_module, call.arg_list()?)?;
 
         // should_render_snippet intends to express a rough level of confidence about
         // the correctness of the return type.
@@ -241,11 +405,11 @@ fn fn_name(call: &ast::Path) -> Option<ast::Name> {
 fn fn_args(
     ctx: &AssistContext,
     target_module: hir::Module,
-    call: &ast::CallExpr,
+    arg_list: ast::ArgList,
 ) -> Option<(Option<ast::GenericParamList>, ast::ParamList)> {
     let mut arg_names = Vec::new();
     let mut arg_types = Vec::new();
-    for arg in call.arg_list()?.args() {
+    for arg in arg_list.args() {
         arg_names.push(match fn_arg_name(&arg) {
             Some(name) => name,
             None => String::from("arg"),
@@ -1177,4 +1341,142 @@ impl Foo {
         ",
         )
     }
+
+    #[test]
+    fn add_method_to_existing_impl() {
+        check_assist(
+            generate_method,
+            r"
+struct Foo;
+impl Foo {
+    fn foo(&self) -> u32 {
+        self.bar$0()
+    }
+}
+",
+            r"
+struct Foo;
+impl Foo {
+    fn foo(&self) -> u32 {
+        self.bar()
+    }
+
+    fn bar(&self) -> u32 {
+        todo!()
+    }
+}
+",
+        )
+    }
+
+    #[test]
+    fn add_method_with_unknown_return_type() {
+        check_assist(
+            generate_method,
+            r"
+struct Foo;
+fn foo(foo: &Foo) {
+    foo.bar$0(92);
+}
+",
+            r"
+struct Foo;
+
+impl Foo {
+    fn bar(&self, arg: i32) ${0:-> ()} {
+        todo!()
+    }
+}
+fn foo(foo: &Foo) {
+    foo.bar(92);
+}
+",
+        )
+    }
+
+    #[test]
+    fn add_method_return_type_from_argument() {
+        check_assist(
+            generate_method,
+            r"
+struct Foo;
+struct Name;
+fn take(name: Name) {}
+fn foo(foo: Foo, count: usize) {
+    take(foo.name$0(count));
+}
+",
+            r"
+struct Foo;
+
+impl Foo {
+    fn name(&self, count: usize) -> Name {
+        todo!()
+    }
+}
+struct Name;
+fn take(name: Name) {}
+fn foo(foo: Foo, count: usize) {
+    take(foo.name(count));
+}
+",
+        )
+    }
+
+    #[test]
+    fn add_method_in_other_module() {
+        check_assist(
+            generate_method,
+            r"
+mod m {
+    pub struct Foo;
+}
+fn foo(foo: m::Foo) {
+    let ok: bool = foo.check$0();
+}
+",
+            r"
+mod m {
+    pub struct Foo;
+
+    impl Foo {
+        pub(crate) fn check(&self) -> bool {
+            todo!()
+        }
+    }
+}
+fn foo(foo: m::Foo) {
+    let ok: bool = foo.check();
+}
+",
+        )
+    }
+
+    #[test]
+    fn add_method_not_applicable_if_method_exists() {
+        check_assist_not_applicable(
+            generate_method,
+            r"
+struct Foo;
+impl Foo {
+    fn bar(&self) {}
+}
+fn foo(foo: Foo) {
+    foo.bar$0();
+}
+",
+        )
+    }
+
+    #[test]
+    fn add_method_not_applicable_for_builtin_receiver() {
+        check_assist_not_applicable(
+            generate_method,
+            r"
+fn foo() {
+    1u32.bar$0();
+}
+",
+        )
+    }
 }
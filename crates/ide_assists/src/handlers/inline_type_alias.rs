@@ -0,0 +1,458 @@
+use std::ops;
+
+use hir::{AsAssocItem, HasSource, ModuleDef, PathResolution};
+use ide_db::{defs::Definition, search::FileReference};
+use rustc_hash::FxHashMap;
+use syntax::{
+    algo::find_node_at_range,
+    ast::{self, AstNode, GenericParamsOwner, NameOwner},
+    SyntaxNode, TextRange,
+};
+
+use crate::{
+    ast_transform::{self, QualifyPaths},
+    utils::range_with_trailing_whitespace,
+    AssistContext, AssistId, AssistKind, Assists,
+};
+
+// Assist: inline_type_alias
+//
+// Replaces a usage of a type alias with its definition, substituting the generic arguments.
+//
+// ```
+// type A<T = u32> = Vec<T>;
+//
+// fn main() {
+//     let a: $0A<i64> = Vec::new();
+// }
+// ```
+// ->
+// ```
+// type A<T = u32> = Vec<T>;
+//
+// fn main() {
+//     let a: Vec<i64> = Vec::new();
+// }
+// ```
+pub(crate) fn inline_type_alias(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let name_ref = ctx.find_node_at_offset::<ast::NameRef>()?;
+    let path = path_of_name_ref(&name_ref)?;
+    let alias = match ctx.sema.resolve_path(&path)? {
+        PathResolution::Def(ModuleDef::TypeAlias(it)) => it,
+        _ => return None,
+    };
+    let alias_def = AliasDef::new(ctx, alias)?;
+    let (target, replacement) = alias_def.inline_into(ctx, &path)?;
+    acc.add(
+        AssistId("inline_type_alias", AssistKind::RefactorInline),
+        "Inline type alias",
+        target,
+        |builder| builder.replace(target, replacement),
+    )
+}
+
+// Assist: inline_type_alias_uses
+//
+// Replaces all usages of a type alias with its definition and removes the alias
+// if no usages are left.
+//
+// ```
+// type $0A = Vec<u32>;
+//
+// fn foo(a: A) -> A { a }
+// ```
+// ->
+// ```
+// fn foo(a: Vec<u32>) -> Vec<u32> { a }
+// ```
+pub(crate) fn inline_type_alias_uses(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let name = ctx.find_node_at_offset::<ast::Name>()?;
+    let alias_ast = name.syntax().parent().and_then(ast::TypeAlias::cast)?;
+    let alias = ctx.sema.to_def(&alias_ast)?;
+    let alias_def = AliasDef::new(ctx, alias)?;
+
+    let usages = Definition::ModuleDef(ModuleDef::TypeAlias(alias)).usages(&ctx.sema).all();
+    if usages.is_empty() {
+        return None;
+    }
+    acc.add(
+        AssistId("inline_type_alias_uses", AssistKind::RefactorInline),
+        "Inline type alias into all uses",
+        name.syntax().text_range(),
+        |builder| {
+            let mut inlined_everything = true;
+            for (file_id, references) in usages {
+                builder.edit_file(file_id);
+                for FileReference { range, name, .. } in references {
+                    let inlined = name
+                        .as_name_ref()
+                        // Skip usages coming from macro expansions.
+                        .filter(|name_ref| name_ref.syntax().text_range() == range)
+                        .and_then(path_of_name_ref)
+                        .and_then(|path| alias_def.inline_into(ctx, &path));
+                    match inlined {
+                        Some((range, replacement)) => builder.replace(range, replacement),
+                        None => inlined_everything = false,
+                    }
+                }
+            }
+            if inlined_everything {
+                builder.edit_file(ctx.frange.file_id);
+                builder.delete(range_with_trailing_whitespace(alias_ast.syntax()));
+            } else {
+                cov_mark::hit!(inline_type_alias_keeps_alias);
+            }
+        },
+    )
+}
+
+struct AliasDef {
+    source: ast::TypeAlias,
+    ty: ast::Type,
+}
+
+impl AliasDef {
+    fn new(ctx: &AssistContext, alias: hir::TypeAlias) -> Option<AliasDef> {
+        if alias.as_assoc_item(ctx.db()).is_some() {
+            return None;
+        }
+        // Re-parse the alias through `Semantics`, so that we can query scopes inside of it.
+        let src = alias.source(ctx.db())?;
+        let source_file = ctx.sema.parse(src.file_id.original_file(ctx.db()));
+        let source: ast::TypeAlias =
+            find_node_at_range(source_file.syntax(), src.value.syntax().text_range())?;
+        if source.syntax().text() != src.value.syntax().text() {
+            return None;
+        }
+        let ty = source.ty()?;
+        Some(AliasDef { source, ty })
+    }
+
+    /// Computes the edit replacing `path`, which refers to this alias, with the
+    /// aliased type.
+    fn inline_into(&self, ctx: &AssistContext, path: &ast::Path) -> Option<(TextRange, String)> {
+        let segment = path.segment()?;
+        let substs = self.substitutions(segment.generic_arg_list())?;
+
+        let target_scope = ctx.sema.scope(path.syntax());
+        let source_scope = ctx.sema.scope(self.ty.syntax());
+        let ty = if target_scope.module() == source_scope.module() {
+            self.ty.clone()
+        } else {
+            ast_transform::apply(&QualifyPaths::new(&target_scope, &source_scope), self.ty.clone())
+        };
+        let mut replacement = substitute(ty.syntax(), &substs);
+
+        let parent = path.syntax().parent()?;
+        if ast::Path::can_cast(parent.kind()) {
+            // `Alias::new()` has to become `<Aliased>::new()`.
+            replacement = format!("<{}>", replacement);
+        } else if let Some(path_ty) = ast::PathType::cast(parent) {
+            let needs_parens =
+                matches!(ty, ast::Type::DynTraitType(_) | ast::Type::ImplTraitType(_))
+                    && path_ty.syntax().parent().map_or(false, |it| {
+                        ast::RefType::can_cast(it.kind()) || ast::PtrType::can_cast(it.kind())
+                    });
+            if needs_parens {
+                replacement = format!("({})", replacement);
+            }
+        } else {
+            return None;
+        }
+        Some((path.syntax().text_range(), replacement))
+    }
+
+    /// Maps the names of the alias' generic parameters to the text of the
+    /// arguments they are instantiated with.
+    fn substitutions(
+        &self,
+        args: Option<ast::GenericArgList>,
+    ) -> Option<FxHashMap<String, String>> {
+        let mut substs = FxHashMap::default();
+        let params = match self.source.generic_param_list() {
+            Some(it) => it,
+            None => return Some(substs),
+        };
+
+        let args: Vec<ast::GenericArg> =
+            args.into_iter().flat_map(|it| it.generic_args()).collect();
+        if args.iter().any(|arg| matches!(arg, ast::GenericArg::AssocTypeArg(_))) {
+            return None;
+        }
+        let (lifetime_args, other_args): (Vec<_>, Vec<_>) =
+            args.into_iter().partition(|arg| matches!(arg, ast::GenericArg::LifetimeArg(_)));
+        let mut lifetime_args = lifetime_args.into_iter();
+        let mut other_args = other_args.into_iter();
+
+        for param in params.generic_params() {
+            match param {
+                ast::GenericParam::LifetimeParam(param) => {
+                    let arg = lifetime_args.next().map_or("'_".to_string(), |it| it.to_string());
+                    substs.insert(param.lifetime()?.to_string(), arg);
+                }
+                ast::GenericParam::TypeParam(param) => {
+                    let arg = match other_args.next() {
+                        Some(it) => it.to_string(),
+                        None => param.default_type().map_or("_".to_string(), |it| it.to_string()),
+                    };
+                    substs.insert(param.name()?.to_string(), arg);
+                }
+                ast::GenericParam::ConstParam(param) => {
+                    let arg = match other_args.next() {
+                        Some(it) => it.to_string(),
+                        None => param.default_val()?.to_string(),
+                    };
+                    substs.insert(param.name()?.to_string(), arg);
+                }
+            }
+        }
+        Some(substs)
+    }
+}
+
+fn path_of_name_ref(name_ref: &ast::NameRef) -> Option<ast::Path> {
+    let segment = name_ref.syntax().parent().and_then(ast::PathSegment::cast)?;
+    Some(segment.parent_path())
+}
+
+fn substitute(root: &SyntaxNode, substs: &FxHashMap<String, String>) -> String {
+    let mut replacements: Vec<(TextRange, String)> = Vec::new();
+    for node in root.descendants() {
+        // `descendants` is a preorder traversal, so a nested match can only be
+        // inside of the last replaced node.
+        if replacements.last().map_or(false, |(range, _)| range.contains_range(node.text_range())) {
+            continue;
+        }
+        if let Some(replacement) = substitution_for(&node, substs) {
+            replacements.push((node.text_range(), replacement));
+        }
+    }
+
+    let mut text = root.to_string();
+    let offset = root.text_range().start();
+    for (range, replacement) in replacements.into_iter().rev() {
+        let range: ops::Range<usize> = (range - offset).into();
+        text.replace_range(range, &replacement);
+    }
+    text
+}
+
+fn substitution_for(node: &SyntaxNode, substs: &FxHashMap<String, String>) -> Option<String> {
+    if let Some(lifetime) = ast::Lifetime::cast(node.clone()) {
+        return substs.get(&lifetime.to_string()).cloned();
+    }
+    if let Some(path_ty) = ast::PathType::cast(node.clone()) {
+        return substs.get(&param_name(&path_ty.path()?)?).cloned();
+    }
+    if let Some(path_expr) = ast::PathExpr::cast(node.clone()) {
+        return substs.get(&param_name(&path_expr.path()?)?).cloned();
+    }
+    // `T::Item` becomes `<Arg>::Item`.
+    let path = ast::Path::cast(node.clone())?;
+    if !ast::Path::can_cast(node.parent()?.kind()) {
+        return None;
+    }
+    substs.get(&param_name(&path)?).map(|arg| format!("<{}>", arg))
+}
+
+fn param_name(path: &ast::Path) -> Option<String> {
+    if path.qualifier().is_some() {
+        return None;
+    }
+    let segment = path.segment()?;
+    if segment.generic_arg_list().is_some() || segment.param_list().is_some() {
+        return None;
+    }
+    Some(segment.name_ref()?.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn inline_generic_argument() {
+        check_assist(
+            inline_type_alias,
+            r#"
+struct Bar<T, U>(T, U);
+type Foo<T> = Bar<T, u32>;
+fn f(x: $0Foo<i64>) {}
+"#,
+            r#"
+struct Bar<T, U>(T, U);
+type Foo<T> = Bar<T, u32>;
+fn f(x: Bar<i64, u32>) {}
+"#,
+        );
+    }
+
+    #[test]
+    fn inline_default_argument() {
+        check_assist(
+            inline_type_alias,
+            r#"
+struct Bar<T>(T);
+type Foo<T = u8> = Bar<T>;
+fn f(x: $0Foo) {}
+"#,
+            r#"
+struct Bar<T>(T);
+type Foo<T = u8> = Bar<T>;
+fn f(x: Bar<u8>) {}
+"#,
+        );
+    }
+
+    #[test]
+    fn inline_lifetimes() {
+        check_assist(
+            inline_type_alias,
+            r#"
+type Ref<'a, T> = &'a [T];
+fn f<'b>(x: $0Ref<'b, u8>) {}
+"#,
+            r#"
+type Ref<'a, T> = &'a [T];
+fn f<'b>(x: &'b [u8]) {}
+"#,
+        );
+    }
+
+    #[test]
+    fn inline_elided_lifetime() {
+        check_assist(
+            inline_type_alias,
+            r#"
+type Ref<'a> = &'a str;
+fn f(x: $0Ref) {}
+"#,
+            r#"
+type Ref<'a> = &'a str;
+fn f(x: &'_ str) {}
+"#,
+        );
+    }
+
+    #[test]
+    fn inline_param_used_as_qualifier() {
+        check_assist(
+            inline_type_alias,
+            r#"
+trait Tr { type Item; }
+type Item<T> = Option<T::Item>;
+fn f(x: $0Item<Vec<u8>>) {}
+"#,
+            r#"
+trait Tr { type Item; }
+type Item<T> = Option<T::Item>;
+fn f(x: Option<<Vec<u8>>::Item>) {}
+"#,
+        );
+    }
+
+    #[test]
+    fn inline_in_expression_qualifier() {
+        check_assist(
+            inline_type_alias,
+            r#"
+struct Bar<T>(T);
+impl<T> Bar<T> { fn new(t: T) -> Self { Bar(t) } }
+type Foo = Bar<u32>;
+fn f() { let _ = $0Foo::new(92); }
+"#,
+            r#"
+struct Bar<T>(T);
+impl<T> Bar<T> { fn new(t: T) -> Self { Bar(t) } }
+type Foo = Bar<u32>;
+fn f() { let _ = <Bar<u32>>::new(92); }
+"#,
+        );
+    }
+
+    #[test]
+    fn inline_qualifies_paths() {
+        check_assist(
+            inline_type_alias,
+            r#"
+mod m {
+    pub struct Bar;
+    pub type Foo = Bar;
+}
+fn f(x: m::$0Foo) {}
+"#,
+            r#"
+mod m {
+    pub struct Bar;
+    pub type Foo = Bar;
+}
+fn f(x: m::Bar) {}
+"#,
+        );
+    }
+
+    #[test]
+    fn inline_all_uses_and_remove_alias() {
+        check_assist(
+            inline_type_alias_uses,
+            r#"
+struct Bar<T>(T);
+type $0Foo<T> = Bar<T>;
+
+fn f(x: Foo<u8>) -> Foo<i8> { loop {} }
+"#,
+            r#"
+struct Bar<T>(T);
+fn f(x: Bar<u8>) -> Bar<i8> { loop {} }
+"#,
+        );
+    }
+
+    #[test]
+    fn inline_all_uses_keeps_alias_if_still_used() {
+        cov_mark::check!(inline_type_alias_keeps_alias);
+        check_assist(
+            inline_type_alias_uses,
+            r#"
+mod m {
+    pub struct Bar;
+    pub type $0Foo = Bar;
+}
+use m::Foo;
+fn f() -> Foo { m::Bar }
+"#,
+            r#"
+mod m {
+    pub struct Bar;
+    pub type Foo = Bar;
+}
+use m::Foo;
+fn f() -> m::Bar { m::Bar }
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_associated_types() {
+        check_assist_not_applicable(
+            inline_type_alias_uses,
+            r#"
+trait Tr { type Out; }
+impl Tr for () { type $0Out = u8; }
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_non_alias() {
+        check_assist_not_applicable(
+            inline_type_alias,
+            r#"
+struct Bar;
+fn f(x: $0Bar) {}
+"#,
+        );
+    }
+}
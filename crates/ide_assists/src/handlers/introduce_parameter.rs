@@ -0,0 +1,455 @@
+use hir::{HirDisplay, PathResolution};
+use ide_db::defs::Definition;
+use stdx::to_lower_snake_case;
+use syntax::{
+    ast::{self, ArgListOwner, AstNode, NameOwner},
+    SourceFile,
+    SyntaxKind::{AWAIT_EXPR, BREAK_EXPR, CONTINUE_EXPR, IDENT, RETURN_EXPR, TRY_EXPR},
+    TextRange,
+};
+
+use crate::{
+    ast_transform::{self, QualifyPaths},
+    utils::{range_with_surrounding_whitespace, suggest_name},
+    AssistContext, AssistId, AssistKind, Assists,
+};
+
+use super::remove_unused_param::call_expr_at;
+
+// Assist: promote_local_to_param
+//
+// Turns a local variable into a parameter of the enclosing function. Callers
+// pass the variable's initializer instead.
+//
+// ```
+// fn frobnicate() -> i32 {
+//     let $0threshold = 92;
+//     threshold * 2
+// }
+//
+// fn main() {
+//     frobnicate();
+// }
+// ```
+// ->
+// ```
+// fn frobnicate(threshold: i32) -> i32 {
+//     threshold * 2
+// }
+//
+// fn main() {
+//     frobnicate(92);
+// }
+// ```
+pub(crate) fn promote_local_to_param(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let name = ctx.find_node_at_offset::<ast::Name>()?;
+    let ident_pat = name.syntax().parent().and_then(ast::IdentPat::cast)?;
+    let let_stmt = ident_pat.syntax().parent().and_then(ast::LetStmt::cast)?;
+    let init = let_stmt.initializer()?;
+    let func = let_stmt.syntax().ancestors().find_map(ast::Fn::cast)?;
+
+    let name = name.text().to_string();
+    if binds_name(&func, &name, &ident_pat) {
+        cov_mark::hit!(promote_local_shadowing);
+        return None;
+    }
+    let ty = match let_stmt.ty() {
+        Some(ty) => ty.to_string(),
+        None => {
+            let module = ctx.sema.scope(let_stmt.syntax()).module()?;
+            let ty = ctx.sema.type_of_pat(&ident_pat.clone().into())?;
+            ty.display_source_code(ctx.db(), module.into()).ok()?
+        }
+    };
+
+    let param = NewParam {
+        text: format!("{}: {}", ident_pat, ty),
+        arg: init,
+        body_edit: (range_with_surrounding_whitespace(let_stmt.syntax()), String::new()),
+    };
+    param.add_to(
+        acc,
+        ctx,
+        &func,
+        AssistId("promote_local_to_param", AssistKind::Refactor),
+        "Promote local to parameter",
+        let_stmt.syntax().text_range(),
+    )
+}
+
+// Assist: introduce_parameter
+//
+// Replaces the selected expression with a new parameter of the enclosing
+// function. Callers pass the expression instead.
+//
+// ```
+// fn frobnicate(x: i32) -> i32 {
+//     x * $0default_factor()$0
+// }
+//
+// fn default_factor() -> i32 { 2 }
+//
+// fn main() {
+//     frobnicate(4);
+// }
+// ```
+// ->
+// ```
+// fn frobnicate(x: i32, default_factor: i32) -> i32 {
+//     x * default_factor
+// }
+//
+// fn default_factor() -> i32 { 2 }
+//
+// fn main() {
+//     frobnicate(4, default_factor());
+// }
+// ```
+pub(crate) fn introduce_parameter(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    if ctx.frange.range.is_empty() {
+        return None;
+    }
+    let expr = ctx.covering_element().ancestors().find_map(ast::Expr::cast)?;
+    if expr.syntax().text_range() != ctx.frange.range {
+        return None;
+    }
+    let func = expr.syntax().ancestors().find_map(ast::Fn::cast)?;
+    if !func.body()?.syntax().text_range().contains_range(ctx.frange.range) {
+        return None;
+    }
+
+    let module = ctx.sema.scope(expr.syntax()).module()?;
+    let ty = ctx.sema.type_of_expr(&expr)?.display_source_code(ctx.db(), module.into()).ok()?;
+    let suggested = match &expr {
+        ast::Expr::PathExpr(path_expr) => {
+            path_expr.path().and_then(|path| path.segment()?.name_ref()).map(|name_ref| {
+                let text = name_ref.text();
+                // Constants are already snake case, just in the wrong case.
+                if text.chars().any(|c| c.is_ascii_lowercase()) {
+                    to_lower_snake_case(&text)
+                } else {
+                    text.to_lowercase()
+                }
+            })
+        }
+        _ => None,
+    }
+    .unwrap_or_else(|| suggest_name::variable(&expr, &ctx.sema));
+    let mut name = suggested.clone();
+    let mut counter = 0;
+    while binds_name(&func, &name, &expr) {
+        counter += 1;
+        name = format!("{}{}", suggested, counter);
+    }
+
+    let param = NewParam {
+        text: format!("{}: {}", name, ty),
+        arg: expr.clone(),
+        body_edit: (expr.syntax().text_range(), name),
+    };
+    param.add_to(
+        acc,
+        ctx,
+        &func,
+        AssistId("introduce_parameter", AssistKind::Refactor),
+        "Introduce parameter",
+        expr.syntax().text_range(),
+    )
+}
+
+/// A parameter about to be added to a function, along with what its callers
+/// should pass for it.
+struct NewParam {
+    /// `name: Type`
+    text: String,
+    /// The expression the parameter stands for.
+    arg: ast::Expr,
+    /// The edit to the function body making it use the parameter.
+    body_edit: (TextRange, String),
+}
+
+impl NewParam {
+    fn add_to(
+        self,
+        acc: &mut Assists,
+        ctx: &AssistContext,
+        func: &ast::Fn,
+        id: AssistId,
+        label: &str,
+        target: TextRange,
+    ) -> Option<()> {
+        if is_trait_item(func) {
+            cov_mark::hit!(introduce_param_trait_fn);
+            return None;
+        }
+        let param_list = func.param_list()?;
+        let fn_def = Definition::ModuleDef(ctx.sema.to_def(func)?.into());
+        let usages = fn_def.usages(&ctx.sema).all();
+        let recursive_in_arg = usages
+            .references
+            .get(&ctx.frange.file_id)
+            .into_iter()
+            .flatten()
+            .any(|reference| self.body_edit.0.contains_range(reference.range));
+        if recursive_in_arg {
+            return None;
+        }
+        let evaluable = is_evaluable_elsewhere(ctx, &self.arg);
+
+        acc.add(id, label, target, |builder| {
+            let last_param = param_list
+                .params()
+                .last()
+                .map(|it| it.syntax().clone())
+                .or_else(|| param_list.self_param().map(|it| it.syntax().clone()));
+            match (last_param, param_list.l_paren_token()) {
+                (Some(last), _) => {
+                    builder.insert(last.text_range().end(), format!(", {}", self.text))
+                }
+                (None, Some(l_paren)) => builder.insert(l_paren.text_range().end(), &self.text),
+                (None, None) => (),
+            }
+            let (range, replacement) = &self.body_edit;
+            builder.replace(*range, replacement);
+
+            let source_scope = ctx.sema.scope(self.arg.syntax());
+            let mut placeholder_cap = ctx.config.snippet_cap;
+            for (file_id, references) in usages {
+                let source_file = ctx.sema.parse(file_id);
+                builder.edit_file(file_id);
+                for reference in references {
+                    let arg_list = match call_arg_list(&source_file, reference.range) {
+                        Some(it) => it,
+                        None => continue,
+                    };
+                    let (arg, cap) = if evaluable {
+                        let target_scope = ctx.sema.scope(arg_list.syntax());
+                        if target_scope.module() == source_scope.module() {
+                            (self.arg.to_string(), None)
+                        } else {
+                            let transform = QualifyPaths::new(&target_scope, &source_scope);
+                            (ast_transform::apply(&transform, self.arg.clone()).to_string(), None)
+                        }
+                    } else {
+                        // Only the first placeholder gets the cursor.
+                        match placeholder_cap.take() {
+                            Some(cap) => ("${0:todo!()}".to_string(), Some(cap)),
+                            None => ("todo!()".to_string(), None),
+                        }
+                    };
+                    let (offset, arg) = match (arg_list.args().last(), arg_list.l_paren_token()) {
+                        (Some(last), _) => (last.syntax().text_range().end(), format!(", {}", arg)),
+                        (None, Some(l_paren)) => (l_paren.text_range().end(), arg),
+                        (None, None) => continue,
+                    };
+                    match cap {
+                        Some(cap) => builder.insert_snippet(cap, offset, arg),
+                        None => builder.insert(offset, arg),
+                    }
+                }
+            }
+        })
+    }
+}
+
+/// Finds the argument list of the call, or method call, of the function
+/// reference at `range`.
+fn call_arg_list(source_file: &SourceFile, range: TextRange) -> Option<ast::ArgList> {
+    if let Some(call_expr) = call_expr_at(source_file, range) {
+        return call_expr.arg_list();
+    }
+    let method_call = source_file
+        .syntax()
+        .token_at_offset(range.start())
+        .right_biased()?
+        .ancestors()
+        .find_map(ast::MethodCallExpr::cast)?;
+    if method_call.name_ref()?.syntax().text_range() != range {
+        return None;
+    }
+    method_call.arg_list()
+}
+
+/// Adding a parameter to a trait method would break the trait's other impls.
+pub(super) fn is_trait_item(func: &ast::Fn) -> bool {
+    let container = match func.syntax().parent().and_then(ast::AssocItemList::cast) {
+        Some(it) => it.syntax().parent(),
+        None => return false,
+    };
+    match container.and_then(ast::Item::cast) {
+        Some(ast::Item::Trait(_)) => true,
+        Some(ast::Item::Impl(impl_)) => impl_.trait_().is_some(),
+        _ => false,
+    }
+}
+
+/// Whether `func` binds `name` anywhere but in `except`, so that a parameter
+/// by this name would change what some path refers to.
+fn binds_name(func: &ast::Fn, name: &str, except: &impl AstNode) -> bool {
+    let except = except.syntax().text_range();
+    func.syntax()
+        .descendants()
+        .filter_map(ast::IdentPat::cast)
+        .filter(|pat| !except.contains_range(pat.syntax().text_range()))
+        .any(|pat| pat.name().map_or(false, |it| it.text() == name))
+}
+
+/// Whether `expr` means the same thing when it is moved out of the function
+/// into its callers. Locals, `self`, generic parameters and control flow
+/// don't survive the move.
+fn is_evaluable_elsewhere(ctx: &AssistContext, expr: &ast::Expr) -> bool {
+    expr.syntax().descendants().all(|node| {
+        if matches!(node.kind(), RETURN_EXPR | BREAK_EXPR | CONTINUE_EXPR | TRY_EXPR | AWAIT_EXPR) {
+            return false;
+        }
+        if let Some(macro_call) = ast::MacroCall::cast(node.clone()) {
+            // Macro arguments aren't resolved, so any identifier might be a local.
+            return macro_call.token_tree().map_or(true, |tt| {
+                tt.syntax().descendants_with_tokens().all(|it| it.kind() != IDENT)
+            });
+        }
+        let path = match ast::Path::cast(node) {
+            Some(it) => it,
+            None => return true,
+        };
+        !matches!(
+            ctx.sema.resolve_path(&path),
+            Some(PathResolution::Local(_))
+                | Some(PathResolution::SelfType(_))
+                | Some(PathResolution::TypeParam(_))
+                | Some(PathResolution::ConstParam(_))
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn promote_local_with_inferred_type() {
+        check_assist(
+            promote_local_to_param,
+            r#"
+fn foo(x: i32) -> i32 {
+    let $0step = 2 + 1;
+    x + step
+}
+fn a() { foo(1); }
+fn b() { foo(2,); }
+"#,
+            r#"
+fn foo(x: i32, step: i32) -> i32 {
+    x + step
+}
+fn a() { foo(1, 2 + 1); }
+fn b() { foo(2, 2 + 1,); }
+"#,
+        );
+    }
+
+    #[test]
+    fn promote_local_depending_on_locals_uses_placeholder() {
+        check_assist(
+            promote_local_to_param,
+            r#"
+struct S;
+impl S {
+    fn foo(&self, x: i32) {
+        let mut $0y: i64 = x as i64;
+        y += 1;
+    }
+}
+fn main() { S.foo(1); }
+"#,
+            r#"
+struct S;
+impl S {
+    fn foo(&self, x: i32, mut y: i64) {
+        y += 1;
+    }
+}
+fn main() { S.foo(1, ${0:todo!()}); }
+"#,
+        );
+    }
+
+    #[test]
+    fn promote_local_not_applicable_when_shadowing() {
+        cov_mark::check!(promote_local_shadowing);
+        check_assist_not_applicable(
+            promote_local_to_param,
+            r#"
+fn foo() {
+    let x = 1;
+    let $0x = x + 1;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn introduce_parameter_qualifies_paths_in_other_modules() {
+        check_assist(
+            introduce_parameter,
+            r#"
+mod m {
+    pub const LIMIT: u32 = 10;
+    pub fn clamp(x: u32) -> u32 {
+        if x > $0LIMIT$0 { 0 } else { x }
+    }
+}
+fn main() {
+    m::clamp(3);
+}
+"#,
+            r#"
+mod m {
+    pub const LIMIT: u32 = 10;
+    pub fn clamp(x: u32, limit: u32) -> u32 {
+        if x > limit { 0 } else { x }
+    }
+}
+fn main() {
+    m::clamp(3, m::LIMIT);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn introduce_parameter_without_params() {
+        check_assist(
+            introduce_parameter,
+            r#"
+fn foo() -> bool {
+    $0true$0
+}
+fn main() { foo(); }
+"#,
+            r#"
+fn foo(var_name: bool) -> bool {
+    var_name
+}
+fn main() { foo(true); }
+"#,
+        );
+    }
+
+    #[test]
+    fn introduce_parameter_not_applicable_in_trait_impl() {
+        cov_mark::check!(introduce_param_trait_fn);
+        check_assist_not_applicable(
+            introduce_parameter,
+            r#"
+trait Tr { fn foo(&self) -> u32; }
+struct S;
+impl Tr for S {
+    fn foo(&self) -> u32 { $01 + 1$0 }
+}
+"#,
+        );
+    }
+}
@@ -1,11 +1,12 @@
+use stdx::format_to;
 use syntax::{
     ast::{self, edit::AstNodeEdit, make, AstNode, NameOwner, TypeBoundsOwner},
     match_ast,
     SyntaxKind::*,
-    T,
+    SyntaxNode, TextRange, T,
 };
 
-use crate::{AssistContext, AssistId, AssistKind, Assists};
+use crate::{assist_context::AssistBuilder, AssistContext, AssistId, AssistKind, Assists};
 
 // Assist: move_bounds_to_where_clause
 //
@@ -92,11 +93,224 @@ fn build_predicate(param: ast::TypeParam) -> Option<ast::WherePred> {
     Some(predicate)
 }
 
+// Assist: move_bounds_to_generic_params
+//
+// Moves the bounds of type parameters from a where clause to the parameters.
+//
+// ```
+// fn apply<T, F>(f: F, x: T) -> T where F: FnOnce(T) -> T$0, T: Clone {
+//     f(x)
+// }
+// ```
+// ->
+// ```
+// fn apply<T: Clone, F: FnOnce(T) -> T>(f: F, x: T) -> T {
+//     f(x)
+// }
+// ```
+pub(crate) fn move_bounds_to_generic_params(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let where_clause = ctx.find_node_at_offset::<ast::WhereClause>()?;
+    let param_list =
+        where_clause.syntax().parent()?.children().find_map(ast::GenericParamList::cast)?;
+    let type_params: Vec<ast::TypeParam> = param_list.type_params().collect();
+    let moved: Vec<(ast::WherePred, &ast::TypeParam)> = where_clause
+        .predicates()
+        .filter_map(|pred| {
+            let name = pred_param_name(&pred)?;
+            let param =
+                type_params.iter().find(|it| it.name().map_or(false, |it| it.text() == name))?;
+            Some((pred, param))
+        })
+        .collect();
+    if moved.is_empty() {
+        return None;
+    }
+
+    let target = where_clause.syntax().text_range();
+    acc.add(
+        AssistId("move_bounds_to_generic_params", AssistKind::RefactorRewrite),
+        "Move to generic parameters",
+        target,
+        |builder| {
+            for param in &type_params {
+                let preds: Vec<_> = moved
+                    .iter()
+                    .filter(|(_, it)| *it == param)
+                    .filter_map(|(pred, _)| pred.type_bound_list())
+                    .collect();
+                if preds.is_empty() {
+                    continue;
+                }
+                let bounds = merged_bounds(param.type_bound_list().into_iter().chain(preds));
+                builder.replace(param.syntax().text_range(), param_with_bounds(param, &bounds));
+            }
+            let preds: Vec<_> = moved.iter().map(|(pred, _)| pred.clone()).collect();
+            remove_where_preds(builder, &where_clause, &preds);
+        },
+    )
+}
+
+// Assist: merge_bounds
+//
+// Merges the bounds of a type parameter that are spread over the parameter
+// and the where clause, dropping duplicates.
+//
+// ```
+// fn show<$0T: Clone>(x: T) where T: Debug, T: Clone {}
+// ```
+// ->
+// ```
+// fn show<T: Clone + Debug>(x: T) {}
+// ```
+pub(crate) fn merge_bounds(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let (name, item) = match ctx.find_node_at_offset::<ast::TypeParam>() {
+        Some(param) => (param.name()?.to_string(), param.syntax().parent()?.parent()?),
+        None => {
+            let pred = ctx.find_node_at_offset::<ast::WherePred>()?;
+            (pred_param_name(&pred)?, pred.syntax().parent()?.parent()?)
+        }
+    };
+    let param = item
+        .children()
+        .find_map(ast::GenericParamList::cast)?
+        .type_params()
+        .find(|it| it.name().map_or(false, |it| it.text() == name))?;
+    let where_clause = item.children().find_map(ast::WhereClause::cast);
+    let preds: Vec<ast::WherePred> = where_clause
+        .iter()
+        .flat_map(|it| it.predicates())
+        .filter(|pred| pred_param_name(pred).as_deref() == Some(name.as_str()))
+        .collect();
+
+    let bound_lists: Vec<ast::TypeBoundList> = param
+        .type_bound_list()
+        .into_iter()
+        .chain(preds.iter().filter_map(|pred| pred.type_bound_list()))
+        .collect();
+    let bounds = merged_bounds(bound_lists.iter().cloned());
+    let bound_count: usize = bound_lists.iter().map(|it| it.bounds().count()).sum();
+    if bound_lists.len() < 2 && bound_count == bounds.len() {
+        return None;
+    }
+
+    let target = param.syntax().text_range();
+    acc.add(
+        AssistId("merge_bounds", AssistKind::RefactorRewrite),
+        format!("Merge bounds of `{}`", name),
+        target,
+        |builder| match (param.type_bound_list(), &where_clause) {
+            (None, Some(where_clause)) => {
+                let (first, rest) = preds.split_first().unwrap();
+                let merged = format!("{}: {}", name, bounds.join(" + "));
+                builder.replace(first.syntax().text_range(), merged);
+                remove_where_preds(builder, where_clause, rest);
+            }
+            (_, where_clause) => {
+                builder.replace(param.syntax().text_range(), param_with_bounds(&param, &bounds));
+                if let Some(where_clause) = where_clause {
+                    remove_where_preds(builder, where_clause, &preds);
+                }
+            }
+        },
+    )
+}
+
+/// The name of the type parameter `pred` bounds, if it is a plain `T: Bound`
+/// predicate.
+pub(super) fn pred_param_name(pred: &ast::WherePred) -> Option<String> {
+    if pred.generic_param_list().is_some() || pred.type_bound_list().is_none() {
+        return None;
+    }
+    let path = match pred.ty()? {
+        ast::Type::PathType(it) => it.path()?,
+        _ => return None,
+    };
+    let segment = path.segment()?;
+    if path.qualifier().is_some() || segment.generic_arg_list().is_some() {
+        return None;
+    }
+    Some(segment.name_ref()?.to_string())
+}
+
+/// The distinct bounds in `lists`, in order.
+pub(super) fn merged_bounds(lists: impl Iterator<Item = ast::TypeBoundList>) -> Vec<String> {
+    let mut res = Vec::new();
+    for bound in lists.flat_map(|it| it.bounds()) {
+        let bound = bound.to_string();
+        if !res.contains(&bound) {
+            res.push(bound);
+        }
+    }
+    res
+}
+
+fn param_with_bounds(param: &ast::TypeParam, bounds: &[String]) -> String {
+    let mut buf = param.name().map_or(String::new(), |it| it.to_string());
+    if !bounds.is_empty() {
+        format_to!(buf, ": {}", bounds.join(" + "));
+    }
+    if let Some(default) = param.default_type() {
+        format_to!(buf, " = {}", default);
+    }
+    buf
+}
+
+/// Removes `preds` from `where_clause`, and the whole clause if nothing is
+/// left in it.
+pub(super) fn remove_where_preds(
+    builder: &mut AssistBuilder,
+    where_clause: &ast::WhereClause,
+    preds: &[ast::WherePred],
+) {
+    let all: Vec<SyntaxNode> = where_clause.predicates().map(|it| it.syntax().clone()).collect();
+    let removed: Vec<SyntaxNode> = preds.iter().map(|it| it.syntax().clone()).collect();
+    if !all.iter().all(|it| removed.contains(it)) {
+        remove_list_items(builder, &all, &removed);
+        return;
+    }
+
+    let node = where_clause.syntax();
+    let mut range = node.text_range();
+    if let Some(ws) = node.prev_sibling_or_token().filter(|it| it.kind() == WHITESPACE) {
+        range = range.cover(ws.text_range());
+    }
+    // `where` on its own lines is followed by a newline before the body.
+    match node.next_sibling_or_token() {
+        Some(ws) if ws.kind() == WHITESPACE && ws.to_string().contains('\n') => {
+            builder.replace(range.cover(ws.text_range()), " ");
+        }
+        _ => builder.delete(range),
+    }
+}
+
+/// Removes `removed` from the comma separated `items`, together with the
+/// commas and whitespace separating them from the items that are kept.
+pub(super) fn remove_list_items(
+    builder: &mut AssistBuilder,
+    items: &[SyntaxNode],
+    removed: &[SyntaxNode],
+) {
+    let last_kept = match items.iter().rposition(|it| !removed.contains(it)) {
+        Some(it) => it,
+        None => return,
+    };
+    for (idx, item) in items[..last_kept].iter().enumerate() {
+        if removed.contains(item) {
+            let next = &items[idx + 1];
+            builder.delete(TextRange::new(item.text_range().start(), next.text_range().start()));
+        }
+    }
+    if let Some(last) = items[last_kept + 1..].last() {
+        let start = items[last_kept].text_range().end();
+        builder.delete(TextRange::new(start, last.text_range().end()));
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    use crate::tests::check_assist;
+    use crate::tests::{check_assist, check_assist_not_applicable};
 
     #[test]
     fn move_bounds_to_where_clause_fn() {
@@ -149,4 +363,87 @@ mod tests {
             "#,
         );
     }
+
+    #[test]
+    fn move_bounds_to_generic_params_partially() {
+        check_assist(
+            move_bounds_to_generic_params,
+            r#"
+fn foo<T: Copy, U>(t: T, u: U)
+where
+    T: $0Clone,
+    Vec<U>: Debug,
+    U: Default,
+{
+}
+"#,
+            r#"
+fn foo<T: Copy + Clone, U: Default>(t: T, u: U)
+where
+    Vec<U>: Debug,
+{
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn move_bounds_to_generic_params_multiline() {
+        check_assist(
+            move_bounds_to_generic_params,
+            r#"
+struct S<T>
+where
+    $0T: Clone,
+{
+    t: T,
+}
+"#,
+            r#"
+struct S<T: Clone> {
+    t: T,
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn move_bounds_to_generic_params_keeps_defaults() {
+        check_assist(
+            move_bounds_to_generic_params,
+            r#"struct S<T = u32>(T) where T: Copy$0;"#,
+            r#"struct S<T: Copy = u32>(T);"#,
+        );
+    }
+
+    #[test]
+    fn move_bounds_to_generic_params_not_applicable_to_complex_preds() {
+        check_assist_not_applicable(
+            move_bounds_to_generic_params,
+            r#"fn foo<T>() where for<'a> T: Fn(&'a u8)$0, Vec<T>: Clone {}"#,
+        );
+    }
+
+    #[test]
+    fn merge_bounds_into_where_clause() {
+        check_assist(
+            merge_bounds,
+            r#"fn foo<T, U>() where T: Clone, U: Copy, $0T: Debug + Clone {}"#,
+            r#"fn foo<T, U>() where T: Clone + Debug, U: Copy {}"#,
+        );
+    }
+
+    #[test]
+    fn merge_duplicate_inline_bounds() {
+        check_assist(
+            merge_bounds,
+            r#"fn foo<$0T: Clone + Clone>() {}"#,
+            r#"fn foo<T: Clone>() {}"#,
+        );
+    }
+
+    #[test]
+    fn merge_bounds_not_applicable_when_merged() {
+        check_assist_not_applicable(merge_bounds, r#"fn foo<$0T: Clone>() where U: Copy {}"#);
+    }
 }
@@ -0,0 +1,740 @@
+use std::ops::Range;
+
+use hir::{
+    Adt, AssocItem, HasSource, HasVisibility, Module, ModuleDef, ModuleSource, PathResolution,
+    ScopeDef,
+};
+use ide_db::{
+    base_db::{FileId, FileRange},
+    defs::Definition,
+    helpers::mod_path_to_ast,
+};
+use rustc_hash::FxHashSet;
+use syntax::{
+    ast::{self, edit::IndentLevel, make, ModuleItemOwner, VisibilityOwner},
+    match_ast, AstNode, Direction,
+    SyntaxKind::*,
+    TextRange, TextSize, T,
+};
+
+use crate::{
+    assist_context::{AssistBuilder, AssistContext, Assists},
+    utils::{
+        inner_private_defs, item_needs_visibility, range_with_surrounding_whitespace, reindent,
+        used_outside, vis_offset,
+    },
+    AssistId, AssistKind, GroupLabel,
+};
+
+use super::fix_visibility::target_data_for_def;
+
+// Assist: move_item_to_module
+//
+// Moves an item into a neighbouring module: the parent module, a sibling module
+// or a module declared next to the item. The paths that refer to it are updated.
+//
+// ```
+// fn $0frobnicate() {}
+//
+// mod util {}
+//
+// fn main() {
+//     frobnicate();
+// }
+// ```
+// ->
+// ```
+// mod util {
+//     pub(crate) fn frobnicate() {}
+// }
+//
+// fn main() {
+//     util::frobnicate();
+// }
+// ```
+pub(crate) fn move_item_to_module(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let item = ctx.find_node_at_offset::<ast::Item>()?;
+    if !matches!(item.syntax().parent()?.kind(), SOURCE_FILE | ITEM_LIST) {
+        return None;
+    }
+    let (def, body) = match &item {
+        ast::Item::Fn(it) => (
+            Some(ModuleDef::Function(ctx.sema.to_def(it)?)),
+            it.body().map(|it| it.syntax().clone()),
+        ),
+        ast::Item::Struct(it) => (
+            Some(ModuleDef::Adt(Adt::Struct(ctx.sema.to_def(it)?))),
+            it.field_list().map(|it| it.syntax().clone()),
+        ),
+        ast::Item::Enum(it) => (
+            Some(ModuleDef::Adt(Adt::Enum(ctx.sema.to_def(it)?))),
+            it.variant_list().map(|it| it.syntax().clone()),
+        ),
+        ast::Item::Union(it) => (
+            Some(ModuleDef::Adt(Adt::Union(ctx.sema.to_def(it)?))),
+            it.record_field_list().map(|it| it.syntax().clone()),
+        ),
+        ast::Item::Const(it) => {
+            (Some(ModuleDef::Const(ctx.sema.to_def(it)?)), it.body().map(|it| it.syntax().clone()))
+        }
+        ast::Item::Static(it) => {
+            (Some(ModuleDef::Static(ctx.sema.to_def(it)?)), it.body().map(|it| it.syntax().clone()))
+        }
+        ast::Item::Trait(it) => (
+            Some(ModuleDef::Trait(ctx.sema.to_def(it)?)),
+            it.assoc_item_list().map(|it| it.syntax().clone()),
+        ),
+        ast::Item::TypeAlias(it) => (
+            Some(ModuleDef::TypeAlias(ctx.sema.to_def(it)?)),
+            it.ty().map(|it| it.syntax().clone()),
+        ),
+        ast::Item::Impl(it) => (None, it.assoc_item_list().map(|it| it.syntax().clone())),
+        _ => return None,
+    };
+    if body.map_or(false, |body| body.text_range().contains(ctx.offset())) {
+        cov_mark::hit!(move_item_to_module_in_body);
+        return None;
+    }
+
+    let db = ctx.db();
+    let source_module = ctx.sema.scope(item.syntax()).module()?;
+    let name = def.and_then(|def| def.name(db));
+
+    // Only the neighbouring modules are offered, to keep the list of assists short.
+    let parent = source_module.parent(db);
+    let siblings = parent.into_iter().flat_map(|it| it.children(db));
+    let neighbours = parent.into_iter().chain(siblings).chain(source_module.children(db));
+    let mut targets = Vec::new();
+    for module in neighbours {
+        if module == source_module {
+            continue;
+        }
+        if let (Some(def), Some(name)) = (def, &name) {
+            let name_is_taken = module
+                .scope(db, None)
+                .into_iter()
+                .any(|(it, scope_def)| it == *name && scope_def != ScopeDef::ModuleDef(def));
+            if name_is_taken {
+                cov_mark::hit!(move_item_to_module_name_taken);
+                continue;
+            }
+        }
+        match module.definition_source(db).value {
+            ModuleSource::SourceFile(_) => (),
+            ModuleSource::Module(it) if it.item_list().is_some() => (),
+            _ => continue,
+        }
+        targets.push((module_path(ctx, module), module));
+    }
+    if targets.is_empty() {
+        return None;
+    }
+    targets.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    let group = GroupLabel("Move item to module".to_owned());
+    let target = item.syntax().text_range();
+    for (path, module) in targets {
+        acc.add_group(
+            &group,
+            AssistId("move_item_to_module", AssistKind::RefactorRewrite),
+            format!("Move to `{}`", path),
+            target,
+            |builder| {
+                move_item(ctx, builder, &item, def, module);
+            },
+        );
+    }
+    Some(())
+}
+
+fn move_item(
+    ctx: &AssistContext,
+    builder: &mut AssistBuilder,
+    item: &ast::Item,
+    def: Option<ModuleDef>,
+    target_module: Module,
+) -> Option<()> {
+    let db = ctx.db();
+    let moved = FileRange { file_id: ctx.frange.file_id, range: item.syntax().text_range() };
+    let mut bumped = FxHashSet::default();
+    let mut item_edits = Vec::new();
+
+    // Paths inside the item are resolved from the module it is moved to, and
+    // everything it refers to has to stay accessible from there.
+    for node in item.syntax().descendants() {
+        match_ast! {
+            match node {
+                ast::Path(path) => {
+                    let referenced = match ctx.sema.resolve_path(&path) {
+                        Some(PathResolution::Def(it)) => it,
+                        Some(PathResolution::AssocItem(AssocItem::Function(it))) => {
+                            ModuleDef::Function(it)
+                        }
+                        Some(PathResolution::AssocItem(AssocItem::Const(it))) => {
+                            ModuleDef::Const(it)
+                        }
+                        Some(PathResolution::AssocItem(AssocItem::TypeAlias(it))) => {
+                            ModuleDef::TypeAlias(it)
+                        }
+                        _ => continue,
+                    };
+                    if Some(referenced) == def {
+                        continue;
+                    }
+                    bump_visibility(ctx, builder, &mut bumped, moved, referenced, target_module);
+                    if path.qualifier().is_some() {
+                        continue;
+                    }
+                    let name_ref = match path.segment().and_then(|it| it.name_ref()) {
+                        Some(it) => it,
+                        None => continue,
+                    };
+                    let new_path = match path_from(ctx, target_module, referenced) {
+                        Some(it) => it.to_string(),
+                        None => continue,
+                    };
+                    if new_path != name_ref.text() {
+                        let range = name_ref.syntax().text_range() - moved.range.start();
+                        item_edits.push((range, new_path));
+                    }
+                },
+                ast::MethodCallExpr(call) => {
+                    if let Some(func) = ctx.sema.resolve_method_call(&call) {
+                        let func = ModuleDef::Function(func);
+                        bump_visibility(ctx, builder, &mut bumped, moved, func, target_module);
+                    }
+                },
+                ast::FieldExpr(field) => {
+                    if let Some(field) = ctx.sema.resolve_field(&field) {
+                        let bumped = &mut bumped;
+                        bump_field_visibility(ctx, builder, bumped, moved, field, target_module);
+                    }
+                },
+                ast::RecordExprField(field) => {
+                    if let Some((field, _)) = ctx.sema.resolve_record_field(&field) {
+                        let bumped = &mut bumped;
+                        bump_field_visibility(ctx, builder, bumped, moved, field, target_module);
+                    }
+                },
+                _ => (),
+            }
+        }
+    }
+
+    // Private parts of the item have to become visible to the code left behind.
+    if let Some(def) = def {
+        if item_needs_visibility(item)
+            && used_outside(ctx, Definition::ModuleDef(def), moved, Some(target_module))
+        {
+            item_edits.push((
+                TextRange::empty(vis_offset(item.syntax()) - moved.range.start()),
+                "pub(crate) ".to_string(),
+            ));
+        }
+    }
+    for (node, def) in inner_private_defs(ctx, item) {
+        if used_outside(ctx, def, moved, Some(target_module)) {
+            let offset = vis_offset(&node) - moved.range.start();
+            item_edits.push((TextRange::empty(offset), "pub(crate) ".to_string()));
+        }
+    }
+
+    if let (Some(def), Some(name)) = (def, def.and_then(|def| def.name(db))) {
+        update_usages(ctx, builder, &mut bumped, moved, def, &name.to_string(), target_module);
+    }
+
+    let mut text = item.syntax().to_string();
+    item_edits.sort_by_key(|(range, _)| range.start());
+    for (range, replacement) in item_edits.into_iter().rev() {
+        text.replace_range(Range::<usize>::from(range), &replacement);
+    }
+    let from_indent = IndentLevel::from_node(item.syntax());
+
+    builder.edit_file(moved.file_id);
+    builder.delete(range_with_surrounding_whitespace(item.syntax()));
+
+    let target_source = target_module.definition_source(db);
+    builder.edit_file(target_source.file_id.original_file(db));
+    match target_source.value {
+        ModuleSource::SourceFile(file) => match file.items().last() {
+            Some(last) => builder.insert(
+                last.syntax().text_range().end(),
+                format!("\n\n{}", reindent(&text, from_indent, IndentLevel(0))),
+            ),
+            None => builder.insert(
+                file.syntax().text_range().end(),
+                format!("{}\n", reindent(&text, from_indent, IndentLevel(0))),
+            ),
+        },
+        ModuleSource::Module(module) => {
+            let item_list = module.item_list()?;
+            match item_list.items().last() {
+                Some(last) => {
+                    let indent = IndentLevel::from_node(last.syntax());
+                    builder.insert(
+                        last.syntax().text_range().end(),
+                        format!("\n\n{}{}", indent, reindent(&text, from_indent, indent)),
+                    )
+                }
+                None => {
+                    let indent = IndentLevel::from_node(module.syntax());
+                    let range = TextRange::new(
+                        item_list.l_curly_token()?.text_range().end(),
+                        item_list.r_curly_token()?.text_range().start(),
+                    );
+                    builder.replace(
+                        range,
+                        format!(
+                            "\n{}{}\n{}",
+                            indent + 1,
+                            reindent(&text, from_indent, indent + 1),
+                            indent
+                        ),
+                    )
+                }
+            }
+        }
+        ModuleSource::BlockExpr(_) => return None,
+    }
+    Some(())
+}
+
+/// Rewrites the paths and imports referring to the moved item.
+fn update_usages(
+    ctx: &AssistContext,
+    builder: &mut AssistBuilder,
+    bumped: &mut FxHashSet<(FileId, TextSize)>,
+    moved: FileRange,
+    def: ModuleDef,
+    name: &str,
+    target_module: Module,
+) {
+    let usages = Definition::ModuleDef(def).usages(&ctx.sema).all();
+    let mut references = Vec::new();
+    for (file_id, file_references) in usages {
+        for reference in file_references {
+            if file_id == moved.file_id && moved.range.contains_range(reference.range) {
+                continue;
+            }
+            let name_ref = match reference.name {
+                ast::NameLike::NameRef(it) => it,
+                _ => continue,
+            };
+            let path = match name_ref.syntax().parent().and_then(ast::PathSegment::cast) {
+                Some(segment) => segment.parent_path(),
+                None => continue,
+            };
+            let module = match ctx.sema.scope(path.syntax()).module() {
+                Some(it) => it,
+                None => continue,
+            };
+            let use_tree = path
+                .syntax()
+                .ancestors()
+                .take_while(|it| it.kind() == PATH)
+                .last()
+                .and_then(|top| top.parent())
+                .and_then(ast::UseTree::cast);
+            references.push((file_id, path, module, use_tree));
+        }
+    }
+
+    // Modules importing the item keep referring to it by its name.
+    let importing_modules: FxHashSet<Module> = references
+        .iter()
+        .filter(|(_, _, _, use_tree)| use_tree.is_some())
+        .map(|(_, _, module, _)| *module)
+        .collect();
+
+    for (file_id, path, module, use_tree) in references {
+        builder.edit_file(file_id);
+        if module != target_module {
+            bump_visibility(ctx, builder, bumped, moved, ModuleDef::Module(target_module), module);
+            builder.edit_file(file_id);
+        }
+        let target_path = match path_from(ctx, module, ModuleDef::Module(target_module)) {
+            Some(it) => it,
+            None => continue,
+        };
+        match use_tree {
+            Some(use_tree) => {
+                let imports_item = use_tree.path().map_or(false, |it| it.syntax() == path.syntax());
+                if module == target_module && imports_item && use_tree.rename().is_none() {
+                    cov_mark::hit!(move_item_to_module_remove_import);
+                    remove_use_tree(builder, &use_tree);
+                } else if use_tree.syntax().parent().and_then(ast::Use::cast).is_some() {
+                    builder
+                        .replace(path.syntax().text_range(), format!("{}::{}", target_path, name));
+                } else if let Some(use_item) =
+                    use_tree.syntax().ancestors().find_map(ast::Use::cast)
+                {
+                    // The rest of the group is relative to a prefix that no
+                    // longer leads to the item, so import it separately.
+                    let tree_range = use_tree.syntax().text_range();
+                    let rest = &use_tree.syntax().to_string()
+                        [usize::from(path.syntax().text_range().end() - tree_range.start())..];
+                    remove_use_tree(builder, &use_tree);
+                    if module != target_module || !imports_item {
+                        builder.insert(
+                            use_item.syntax().text_range().end(),
+                            format!(
+                                "\n{}use {}::{}{};",
+                                IndentLevel::from_node(use_item.syntax()),
+                                target_path,
+                                name,
+                                rest
+                            ),
+                        );
+                    }
+                }
+            }
+            None => match path.qualifier() {
+                Some(qualifier) => {
+                    let segment_start = match path.segment() {
+                        Some(it) => it.syntax().text_range().start(),
+                        None => continue,
+                    };
+                    if module == target_module {
+                        builder.delete(TextRange::new(
+                            qualifier.syntax().text_range().start(),
+                            segment_start,
+                        ));
+                    } else {
+                        builder.replace(qualifier.syntax().text_range(), target_path.to_string());
+                    }
+                }
+                None => {
+                    if module != target_module && !importing_modules.contains(&module) {
+                        builder.insert(
+                            path.syntax().text_range().start(),
+                            format!("{}::", target_path),
+                        );
+                    }
+                }
+            },
+        }
+    }
+}
+
+/// Renders the path under which `def` can be reached from `from`, even when
+/// it is not visible from there yet.
+fn path_from(ctx: &AssistContext, from: Module, def: ModuleDef) -> Option<ast::Path> {
+    let db = ctx.db();
+    let mod_path = match from.find_use_path(db, def) {
+        Some(it) => it,
+        None => {
+            let parent = path_from(ctx, from, ModuleDef::Module(def.module(db)?))?;
+            return Some(make::path_qualified(
+                parent,
+                make::path_segment(make::name_ref(&def.name(db)?.to_string())),
+            ));
+        }
+    };
+    Some(mod_path_to_ast(&mod_path))
+}
+
+fn module_path(ctx: &AssistContext, module: Module) -> String {
+    let mut segments = vec!["crate".to_string()];
+    segments.extend(
+        module
+            .path_to_root(ctx.db())
+            .into_iter()
+            .rev()
+            .filter_map(|it| it.name(ctx.db()))
+            .map(|it| it.to_string()),
+    );
+    segments.join("::")
+}
+
+fn bump_visibility(
+    ctx: &AssistContext,
+    builder: &mut AssistBuilder,
+    bumped: &mut FxHashSet<(FileId, TextSize)>,
+    moved: FileRange,
+    def: ModuleDef,
+    from: Module,
+) -> Option<()> {
+    let db = ctx.db();
+    let module = def.module(db)?;
+    if module.krate() != from.krate()
+        || module.visibility_of(db, &def)?.is_visible_from(db, from.into())
+    {
+        return None;
+    }
+    let (offset, current_visibility, _, file_id, _) = target_data_for_def(db, def)?;
+    insert_pub_crate(builder, bumped, moved, file_id, offset, current_visibility)
+}
+
+fn bump_field_visibility(
+    ctx: &AssistContext,
+    builder: &mut AssistBuilder,
+    bumped: &mut FxHashSet<(FileId, TextSize)>,
+    moved: FileRange,
+    field: hir::Field,
+    from: Module,
+) -> Option<()> {
+    let db = ctx.db();
+    if field.parent_def(db).module(db).krate() != from.krate()
+        || field.visibility(db).is_visible_from(db, from.into())
+    {
+        return None;
+    }
+    let source = field.source(db)?;
+    let (offset, current_visibility) = match &source.value {
+        hir::FieldSource::Named(it) => (vis_offset(it.syntax()), it.visibility()),
+        hir::FieldSource::Pos(it) => (vis_offset(it.syntax()), it.visibility()),
+    };
+    let file_id = source.file_id.original_file(db);
+    insert_pub_crate(builder, bumped, moved, file_id, offset, current_visibility)
+}
+
+fn insert_pub_crate(
+    builder: &mut AssistBuilder,
+    bumped: &mut FxHashSet<(FileId, TextSize)>,
+    moved: FileRange,
+    file_id: FileId,
+    offset: TextSize,
+    current_visibility: Option<ast::Visibility>,
+) -> Option<()> {
+    if file_id == moved.file_id && moved.range.contains(offset) {
+        return None;
+    }
+    if !bumped.insert((file_id, offset)) {
+        return None;
+    }
+    builder.edit_file(file_id);
+    match current_visibility {
+        Some(vis) => builder.replace(vis.syntax().text_range(), "pub(crate)"),
+        None => builder.insert(offset, "pub(crate) "),
+    }
+    Some(())
+}
+
+fn remove_use_tree(builder: &mut AssistBuilder, use_tree: &ast::UseTree) {
+    let list = match use_tree.syntax().parent().and_then(ast::UseTreeList::cast) {
+        Some(it) => it,
+        None => {
+            if let Some(use_item) = use_tree.syntax().parent().and_then(ast::Use::cast) {
+                builder.delete(range_with_surrounding_whitespace(use_item.syntax()));
+            }
+            return;
+        }
+    };
+    if list.use_trees().count() == 1 {
+        remove_use_tree(builder, &list.parent_use_tree());
+        return;
+    }
+    let node = use_tree.syntax();
+    let next_comma = node
+        .siblings_with_tokens(Direction::Next)
+        .skip(1)
+        .find(|it| it.kind() != WHITESPACE)
+        .filter(|it| it.kind() == T![,]);
+    let range = match next_comma {
+        Some(comma) => {
+            let end = comma
+                .next_sibling_or_token()
+                .filter(|it| it.kind() == WHITESPACE)
+                .unwrap_or(comma)
+                .text_range()
+                .end();
+            TextRange::new(node.text_range().start(), end)
+        }
+        None => {
+            let start = node
+                .siblings_with_tokens(Direction::Prev)
+                .skip(1)
+                .find(|it| it.kind() != WHITESPACE)
+                .filter(|it| it.kind() == T![,])
+                .map_or(node.text_range().start(), |it| it.text_range().start());
+            TextRange::new(start, node.text_range().end())
+        }
+    };
+    builder.delete(range);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_by_label, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn qualifies_usages_in_source_module() {
+        check_assist(
+            move_item_to_module,
+            r#"
+fn $0frobnicate() {}
+
+mod util {
+    fn f() {}
+}
+
+fn main() {
+    frobnicate();
+}
+"#,
+            r#"
+mod util {
+    fn f() {}
+
+    pub(crate) fn frobnicate() {}
+}
+
+fn main() {
+    util::frobnicate();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn removes_import_in_target_module() {
+        cov_mark::check!(move_item_to_module_remove_import);
+        check_assist_by_label(
+            move_item_to_module,
+            r#"
+mod a {
+    pub struct $0Foo;
+}
+mod b {
+    use super::a::Foo;
+    fn f(_: Foo) {}
+}
+fn main() {
+    let _ = a::Foo;
+}
+"#,
+            r#"
+mod a {
+}
+mod b {
+    fn f(_: Foo) {}
+
+    pub struct Foo;
+}
+fn main() {
+    let _ = b::Foo;
+}
+"#,
+            "Move to `crate::b`",
+        );
+    }
+
+    #[test]
+    fn rewrites_grouped_import() {
+        check_assist_by_label(
+            move_item_to_module,
+            r#"
+mod a {
+    pub struct $0Foo;
+    pub struct Bar;
+}
+mod b {}
+mod c {
+    use super::a::{Bar, Foo};
+}
+"#,
+            r#"
+mod a {
+    pub struct Bar;
+}
+mod b {
+    pub struct Foo;
+}
+mod c {
+    use super::a::{Bar};
+    use crate::b::Foo;
+}
+"#,
+            "Move to `crate::b`",
+        );
+    }
+
+    #[test]
+    fn makes_referenced_items_visible() {
+        check_assist_by_label(
+            move_item_to_module,
+            r#"
+mod a {
+    fn helper() -> u32 { 92 }
+
+    pub fn $0compute() -> u32 {
+        helper()
+    }
+}
+mod b {}
+"#,
+            r#"
+mod a {
+    pub(crate) fn helper() -> u32 { 92 }
+}
+mod b {
+    pub fn compute() -> u32 {
+        crate::a::helper()
+    }
+}
+"#,
+            "Move to `crate::b`",
+        );
+    }
+
+    #[test]
+    fn moves_impl_block() {
+        check_assist(
+            move_item_to_module,
+            r#"
+struct Foo;
+
+impl$0 Foo {
+    fn new() -> Foo { Foo }
+}
+
+mod util {}
+
+fn main() {
+    let _ = Foo::new();
+}
+"#,
+            r#"
+struct Foo;
+
+mod util {
+    impl crate::Foo {
+        pub(crate) fn new() -> crate::Foo { crate::Foo }
+    }
+}
+
+fn main() {
+    let _ = Foo::new();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_in_body() {
+        cov_mark::check!(move_item_to_module_in_body);
+        check_assist_not_applicable(
+            move_item_to_module,
+            r#"
+fn foo() { $0 }
+mod util {}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_when_name_is_taken() {
+        cov_mark::check!(move_item_to_module_name_taken);
+        check_assist_not_applicable(
+            move_item_to_module,
+            r#"
+mod a {
+    fn $0foo() {}
+}
+fn foo() {}
+"#,
+        );
+    }
+}
@@ -86,13 +86,19 @@ fn process_usage(
     FileReference { range, .. }: FileReference,
     arg_to_remove: usize,
 ) -> Option<TextRange> {
+    let call_expr = call_expr_at(source_file, range)?;
+    let arg = call_expr.arg_list()?.args().nth(arg_to_remove)?;
+    Some(range_to_remove(arg.syntax()))
+}
+
+/// Finds the call whose callee is the function reference at `range`.
+pub(super) fn call_expr_at(source_file: &SourceFile, range: TextRange) -> Option<ast::CallExpr> {
     let call_expr: ast::CallExpr = find_node_at_range(source_file.syntax(), range)?;
     let call_expr_range = call_expr.expr()?.syntax().text_range();
     if !call_expr_range.contains_range(range) {
         return None;
     }
-    let arg = call_expr.arg_list()?.args().nth(arg_to_remove)?;
-    Some(range_to_remove(arg.syntax()))
+    Some(call_expr)
 }
 
 fn range_to_remove(node: &SyntaxNode) -> TextRange {
@@ -3,7 +3,11 @@ use ide_db::helpers::mod_path_to_ast;
 use ide_db::items_locator;
 use itertools::Itertools;
 use syntax::{
-    ast::{self, make, AstNode, NameOwner},
+    ast::{
+        self,
+        edit::{AstNodeEdit, IndentLevel},
+        make, AstNode, NameOwner,
+    },
     SyntaxKind::{IDENT, WHITESPACE},
     TextSize,
 };
@@ -11,29 +15,30 @@ use syntax::{
 use crate::{
     assist_context::{AssistBuilder, AssistContext, Assists},
     utils::{
-        add_trait_assoc_items_to_impl, filter_assoc_items, generate_trait_impl_text,
-        render_snippet, Cursor, DefaultMethods,
+        add_trait_assoc_items_to_impl, filter_assoc_items, gen_trait_fn_body::gen_trait_fn_body,
+        generate_trait_impl_text, render_snippet, Cursor, DefaultMethods,
     },
     AssistId, AssistKind,
 };
 
 // Assist: replace_derive_with_manual_impl
 //
-// Converts a `derive` impl into a manual one.
+// Converts a `derive` impl into a manual one. For the derivable std traits the
+// body the derive would have generated is filled in.
 //
 // ```
-// # trait Debug { fn fmt(&self, f: &mut Formatter) -> Result<()>; }
-// #[derive(Deb$0ug, Display)]
+// # trait Serialize { fn serialize(&self) -> String; }
+// #[derive(Serial$0ize, Debug)]
 // struct S;
 // ```
 // ->
 // ```
-// # trait Debug { fn fmt(&self, f: &mut Formatter) -> Result<()>; }
-// #[derive(Display)]
+// # trait Serialize { fn serialize(&self) -> String; }
+// #[derive(Debug)]
 // struct S;
 //
-// impl Debug for S {
-//     fn fmt(&self, f: &mut Formatter) -> Result<()> {
+// impl Serialize for S {
+//     fn serialize(&self) -> String {
 //         ${0:todo!()}
 //     }
 // }
@@ -111,7 +116,7 @@ fn add_assist(
         target,
         |builder| {
             let impl_def_with_items =
-                impl_def_from_trait(&ctx.sema, annotated_name, trait_, trait_path);
+                impl_def_from_trait(&ctx.sema, adt, annotated_name, trait_, trait_path);
             update_attribute(builder, &input, &trait_name, &attr);
             let trait_path = format!("{}", trait_path);
             match (ctx.config.snippet_cap, impl_def_with_items) {
@@ -149,6 +154,7 @@ fn add_assist(
 
 fn impl_def_from_trait(
     sema: &hir::Semantics<ide_db::RootDatabase>,
+    adt: &ast::Adt,
     annotated_name: &ast::Name,
     trait_: Option<hir::Trait>,
     trait_path: &ast::Path,
@@ -165,9 +171,30 @@ fn impl_def_from_trait(
     );
     let (impl_def, first_assoc_item) =
         add_trait_assoc_items_to_impl(sema, trait_items, trait_, impl_def, target_scope);
+
+    // Generate the body the derive would have expanded to.
+    if let ast::AssocItem::Fn(func) = &first_assoc_item {
+        let body =
+            hir_adt(sema, adt).and_then(|adt| gen_trait_fn_body(sema.db, func, trait_path, adt));
+        if let Some(body) = body {
+            let impl_def = impl_def
+                .replace_descendant(func.clone(), func.with_body(body.indent(IndentLevel(1))));
+            let first_assoc_item = impl_def.assoc_item_list()?.assoc_items().next()?;
+            return Some((impl_def, first_assoc_item));
+        }
+    }
     Some((impl_def, first_assoc_item))
 }
 
+fn hir_adt(sema: &hir::Semantics<ide_db::RootDatabase>, adt: &ast::Adt) -> Option<hir::Adt> {
+    let adt = match adt {
+        ast::Adt::Struct(it) => hir::Adt::Struct(sema.to_def(it)?),
+        ast::Adt::Enum(it) => hir::Adt::Enum(sema.to_def(it)?),
+        ast::Adt::Union(it) => hir::Adt::Union(sema.to_def(it)?),
+    };
+    Some(adt)
+}
+
 fn update_attribute(
     builder: &mut AssistBuilder,
     input: &ast::TokenTree,
@@ -211,7 +238,7 @@ mod tests {
     fn add_custom_impl_debug() {
         check_assist(
             replace_derive_with_manual_impl,
-            "
+            r#"
 mod fmt {
     pub struct Error;
     pub type Result = Result<(), Error>;
@@ -225,8 +252,8 @@ mod fmt {
 struct Foo {
     bar: String,
 }
-",
-            "
+"#,
+            r#"
 mod fmt {
     pub struct Error;
     pub type Result = Result<(), Error>;
@@ -241,11 +268,193 @@ struct Foo {
 }
 
 impl fmt::Debug for Foo {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        ${0:todo!()}
+    $0fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Foo").field("bar", &self.bar).finish()
     }
 }
-",
+"#,
+        )
+    }
+
+    #[test]
+    fn add_custom_impl_debug_enum() {
+        check_assist(
+            replace_derive_with_manual_impl,
+            r#"
+mod fmt {
+    pub struct Formatter;
+    pub trait Debug {
+        fn fmt(&self, f: &mut Formatter) -> Result<(), ()>;
+    }
+}
+
+#[derive(Debu$0g)]
+enum Foo {
+    Bar(usize),
+    Baz { x: i32 },
+    Qux,
+}
+"#,
+            r#"
+mod fmt {
+    pub struct Formatter;
+    pub trait Debug {
+        fn fmt(&self, f: &mut Formatter) -> Result<(), ()>;
+    }
+}
+
+enum Foo {
+    Bar(usize),
+    Baz { x: i32 },
+    Qux,
+}
+
+impl fmt::Debug for Foo {
+    $0fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), ()> {
+        match self {
+            Self::Bar(arg0) => f.debug_tuple("Bar").field(arg0).finish(),
+            Self::Baz { x } => f.debug_struct("Baz").field("x", x).finish(),
+            Self::Qux => f.write_str("Qux"),
+        }
+    }
+}
+"#,
+        )
+    }
+
+    #[test]
+    fn add_custom_impl_clone_tuple_struct() {
+        check_assist(
+            replace_derive_with_manual_impl,
+            r#"
+trait Clone {
+    fn clone(&self) -> Self;
+}
+
+#[derive(Clo$0ne)]
+struct Foo(usize, String);
+"#,
+            r#"
+trait Clone {
+    fn clone(&self) -> Self;
+}
+
+struct Foo(usize, String);
+
+impl Clone for Foo {
+    $0fn clone(&self) -> Self {
+        Self(self.0.clone(), self.1.clone())
+    }
+}
+"#,
+        )
+    }
+
+    #[test]
+    fn add_custom_impl_hash_struct() {
+        check_assist(
+            replace_derive_with_manual_impl,
+            r#"
+trait Hasher {}
+trait Hash {
+    fn hash<H: Hasher>(&self, state: &mut H);
+}
+
+#[derive(Has$0h)]
+struct Foo {
+    bin: usize,
+    bar: usize,
+}
+"#,
+            r#"
+trait Hasher {}
+trait Hash {
+    fn hash<H: Hasher>(&self, state: &mut H);
+}
+
+struct Foo {
+    bin: usize,
+    bar: usize,
+}
+
+impl Hash for Foo {
+    $0fn hash<H: Hasher>(&self, state: &mut H) {
+        self.bin.hash(state);
+        self.bar.hash(state);
+    }
+}
+"#,
+        )
+    }
+
+    #[test]
+    fn add_custom_impl_partial_eq_struct() {
+        check_assist(
+            replace_derive_with_manual_impl,
+            r#"
+trait PartialEq {
+    fn eq(&self, other: &Self) -> bool;
+}
+
+#[derive(Partial$0Eq)]
+struct Foo {
+    bin: usize,
+    bar: usize,
+}
+"#,
+            r#"
+trait PartialEq {
+    fn eq(&self, other: &Self) -> bool;
+}
+
+struct Foo {
+    bin: usize,
+    bar: usize,
+}
+
+impl PartialEq for Foo {
+    $0fn eq(&self, other: &Self) -> bool {
+        self.bin == other.bin && self.bar == other.bar
+    }
+}
+"#,
+        )
+    }
+
+    #[test]
+    fn add_custom_impl_partial_eq_enum() {
+        check_assist(
+            replace_derive_with_manual_impl,
+            r#"
+trait PartialEq {
+    fn eq(&self, other: &Self) -> bool;
+}
+
+#[derive(Partial$0Eq)]
+enum Foo {
+    Bar(usize),
+    Baz,
+}
+"#,
+            r#"
+trait PartialEq {
+    fn eq(&self, other: &Self) -> bool;
+}
+
+enum Foo {
+    Bar(usize),
+    Baz,
+}
+
+impl PartialEq for Foo {
+    $0fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Bar(l_0), Self::Bar(r_0)) => l_0 == r_0,
+            _ => core::mem::discriminant(self) == core::mem::discriminant(other),
+        }
+    }
+}
+"#,
         )
     }
     #[test]
@@ -92,7 +92,8 @@ pub(crate) fn replace_if_let_with_match(acc: &mut Assists, ctx: &AssistContext)
 
 // Assist: replace_match_with_if_let
 //
-// Replaces a binary `match` with a wildcard pattern and no guards with an `if let` expression.
+// Replaces a `match` with a wildcard pattern and no guards with an `if let` expression,
+// chaining `else if let` for every arm but the last one.
 //
 // ```
 // enum Action { Move { distance: u32 }, Stop }
@@ -118,20 +119,31 @@ pub(crate) fn replace_if_let_with_match(acc: &mut Assists, ctx: &AssistContext)
 // ```
 pub(crate) fn replace_match_with_if_let(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
     let match_expr: ast::MatchExpr = ctx.find_node_at_offset()?;
-    let mut arms = match_expr.match_arm_list()?.arms();
-    let first_arm = arms.next()?;
-    let second_arm = arms.next()?;
-    if arms.next().is_some() || first_arm.guard().is_some() || second_arm.guard().is_some() {
+    let arms = match_expr.match_arm_list()?.arms().collect::<Vec<_>>();
+    if arms.len() < 2 || arms.iter().any(|arm| arm.guard().is_some()) {
         return None;
     }
     let condition_expr = match_expr.expr()?;
-    let (if_let_pat, then_expr, else_expr) = if is_pat_wildcard_or_sad(&ctx.sema, &first_arm.pat()?)
-    {
-        (second_arm.pat()?, second_arm.expr()?, first_arm.expr()?)
-    } else if is_pat_wildcard_or_sad(&ctx.sema, &second_arm.pat()?) {
-        (first_arm.pat()?, first_arm.expr()?, second_arm.expr()?)
-    } else {
-        return None;
+    let (if_let_arms, else_expr) = match arms.as_slice() {
+        [first_arm, second_arm] if is_pat_wildcard_or_sad(&ctx.sema, &first_arm.pat()?) => {
+            (vec![(second_arm.pat()?, second_arm.expr()?)], first_arm.expr()?)
+        }
+        [rest @ .., last_arm] if is_pat_wildcard_or_sad(&ctx.sema, &last_arm.pat()?) => {
+            let rest = rest
+                .iter()
+                .map(|arm| Some((arm.pat()?, arm.expr()?)))
+                .collect::<Option<Vec<_>>>()?;
+            if rest.iter().any(|(pat, _)| matches!(pat, ast::Pat::WildcardPat(_))) {
+                return None;
+            }
+            // Every `if let` in the chain evaluates the scrutinee again.
+            if rest.len() > 1 && !matches!(condition_expr, ast::Expr::PathExpr(_)) {
+                cov_mark::hit!(replace_match_with_if_let_chain_complex_scrutinee);
+                return None;
+            }
+            (rest, last_arm.expr()?)
+        }
+        _ => return None,
     };
 
     let target = match_expr.syntax().text_range();
@@ -140,11 +152,6 @@ pub(crate) fn replace_match_with_if_let(acc: &mut Assists, ctx: &AssistContext)
         "Replace with if let",
         target,
         move |edit| {
-            let condition = make::condition(condition_expr, Some(if_let_pat));
-            let then_block = match then_expr.reset_indent() {
-                ast::Expr::BlockExpr(block) => block,
-                expr => make::block_expr(iter::empty(), Some(expr)),
-            };
             let else_expr = match else_expr {
                 ast::Expr::BlockExpr(block)
                     if block.statements().count() == 0 && block.tail_expr().is_none() =>
@@ -154,14 +161,25 @@ pub(crate) fn replace_match_with_if_let(acc: &mut Assists, ctx: &AssistContext)
                 ast::Expr::TupleExpr(tuple) if tuple.fields().count() == 0 => None,
                 expr => Some(expr),
             };
-            let if_let_expr = make::expr_if(
-                condition,
-                then_block,
-                else_expr.map(|else_expr| {
-                    ast::ElseBranch::Block(make::block_expr(iter::empty(), Some(else_expr)))
-                }),
-            )
-            .indent(IndentLevel::from_node(match_expr.syntax()));
+            let mut else_branch = else_expr.map(|else_expr| match else_expr {
+                ast::Expr::BlockExpr(block) => ast::ElseBranch::Block(block.reset_indent()),
+                expr => ast::ElseBranch::Block(make::block_expr(iter::empty(), Some(expr))),
+            });
+            let mut if_let_expr = None;
+            for (pat, expr) in if_let_arms.into_iter().rev() {
+                let condition = make::condition(condition_expr.clone(), Some(pat));
+                let then_block = match expr.reset_indent() {
+                    ast::Expr::BlockExpr(block) => block,
+                    expr => make::block_expr(iter::empty(), Some(expr)),
+                };
+                let expr = make::expr_if(condition, then_block, else_branch.take());
+                else_branch = ast::IfExpr::cast(expr.syntax().clone()).map(ast::ElseBranch::IfExpr);
+                if_let_expr = Some(expr);
+            }
+            let if_let_expr = match if_let_expr {
+                Some(it) => it.indent(IndentLevel::from_node(match_expr.syntax())),
+                None => return,
+            };
 
             edit.replace_ast::<ast::Expr>(match_expr.into(), if_let_expr);
         },
@@ -179,7 +197,7 @@ fn is_pat_wildcard_or_sad(sema: &hir::Semantics<RootDatabase>, pat: &ast::Pat) -
 mod tests {
     use super::*;
 
-    use crate::tests::{check_assist, check_assist_target};
+    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target};
 
     #[test]
     fn test_replace_if_let_with_match_unwraps_simple_expressions() {
@@ -593,6 +611,56 @@ fn main() {
         println!("{}", rel_path)
     }
 }
+"#,
+        )
+    }
+
+    #[test]
+    fn replace_match_with_if_let_chain() {
+        check_assist(
+            replace_match_with_if_let,
+            r#"
+enum Shape { Circle(f32), Square(f32), Point }
+
+fn area(shape: Shape) -> f32 {
+    $0match shape {
+        Shape::Circle(r) => 3.14 * r * r,
+        Shape::Square(side) => {
+            side * side
+        }
+        _ => 0.0,
+    }
+}
+"#,
+            r#"
+enum Shape { Circle(f32), Square(f32), Point }
+
+fn area(shape: Shape) -> f32 {
+    if let Shape::Circle(r) = shape {
+        3.14 * r * r
+    } else if let Shape::Square(side) = shape {
+        side * side
+    } else {
+        0.0
+    }
+}
+"#,
+        )
+    }
+
+    #[test]
+    fn replace_match_with_if_let_chain_not_applicable_to_complex_scrutinee() {
+        cov_mark::check!(replace_match_with_if_let_chain_complex_scrutinee);
+        check_assist_not_applicable(
+            replace_match_with_if_let,
+            r#"
+fn main() {
+    $0match next() {
+        Some(1) => (),
+        Some(2) => (),
+        _ => (),
+    }
+}
 "#,
         )
     }
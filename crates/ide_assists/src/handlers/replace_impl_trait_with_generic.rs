@@ -1,7 +1,14 @@
-use syntax::ast::{self, edit::AstNodeEdit, make, AstNode, GenericParamsOwner};
+use hir::PathResolution;
+use ide_db::defs::Definition;
+use syntax::{
+    ast::{self, edit::AstNodeEdit, make, AstNode, GenericParamsOwner, NameOwner, TypeBoundsOwner},
+    SyntaxNode,
+};
 
 use crate::{AssistContext, AssistId, AssistKind, Assists};
 
+use super::move_bounds::{merged_bounds, pred_param_name, remove_list_items, remove_where_preds};
+
 // Assist: replace_impl_trait_with_generic
 //
 // Replaces `impl Trait` function argument with the named generic.
@@ -45,11 +52,126 @@ pub(crate) fn replace_impl_trait_with_generic(
     )
 }
 
+// Assist: replace_generic_with_impl_trait
+//
+// Replaces a generic type parameter that is only used as the type of one
+// argument with `impl Trait`.
+//
+// ```
+// fn foo<$0B: Bar>(bar: B) {}
+// ```
+// ->
+// ```
+// fn foo(bar: impl Bar) {}
+// ```
+pub(crate) fn replace_generic_with_impl_trait(
+    acc: &mut Assists,
+    ctx: &AssistContext,
+) -> Option<()> {
+    let type_param = ctx.find_node_at_offset::<ast::TypeParam>()?;
+    let param_list = type_param.syntax().parent().and_then(ast::GenericParamList::cast)?;
+    let type_fn = param_list.syntax().parent().and_then(ast::Fn::cast)?;
+    let name = type_param.name()?.to_string();
+
+    let where_clause = type_fn.where_clause();
+    let preds: Vec<ast::WherePred> = where_clause
+        .iter()
+        .flat_map(|it| it.predicates())
+        .filter(|pred| pred_param_name(pred).as_deref() == Some(name.as_str()))
+        .collect();
+    let bounds = merged_bounds(
+        type_param
+            .type_bound_list()
+            .into_iter()
+            .chain(preds.iter().filter_map(|pred| pred.type_bound_list())),
+    );
+    if bounds.is_empty() {
+        return None;
+    }
+
+    // The parameter must only be used once, in the type of an argument.
+    let mut uses = type_fn.syntax().descendants().filter_map(ast::NameRef::cast).filter(|it| {
+        it.text() == name
+            && !preds
+                .iter()
+                .filter_map(|pred| pred.ty())
+                .any(|ty| ty.syntax().text_range().contains_range(it.syntax().text_range()))
+    });
+    let use_ = uses.next()?;
+    if uses.next().is_some() {
+        cov_mark::hit!(replace_generic_used_twice);
+        return None;
+    }
+    let path_type = use_.syntax().ancestors().find_map(ast::PathType::cast)?;
+    let path = path_type.path()?;
+    if path.qualifier().is_some()
+        || !matches!(ctx.sema.resolve_path(&path)?, PathResolution::TypeParam(_))
+        || !type_fn.param_list()?.syntax().text_range().contains_range(path.syntax().text_range())
+    {
+        return None;
+    }
+
+    // `impl Trait` arguments can't be given explicitly, so callers must not
+    // use a turbofish.
+    let def = ctx.sema.to_def(&type_fn)?;
+    let usages = Definition::ModuleDef(def.into()).usages(&ctx.sema).all();
+    let turbofish = usages.references.values().flatten().any(|reference| match &reference.name {
+        ast::NameLike::NameRef(name_ref) => has_generic_args(name_ref),
+        _ => false,
+    });
+    if turbofish {
+        cov_mark::hit!(replace_generic_turbofish);
+        return None;
+    }
+
+    let target = type_param.syntax().text_range();
+    acc.add(
+        AssistId("replace_generic_with_impl_trait", AssistKind::RefactorRewrite),
+        "Replace generic with impl trait",
+        target,
+        |builder| {
+            let impl_trait = format!("impl {}", bounds.join(" + "));
+            let is_arg_type =
+                path_type.syntax().parent().map_or(false, |it| ast::Param::can_cast(it.kind()));
+            match bounds.len() > 1 && !is_arg_type {
+                true => {
+                    builder.replace(path_type.syntax().text_range(), format!("({})", impl_trait))
+                }
+                false => builder.replace(path_type.syntax().text_range(), impl_trait),
+            }
+
+            let params: Vec<SyntaxNode> =
+                param_list.generic_params().map(|it| it.syntax().clone()).collect();
+            match params.as_slice() {
+                [_] => builder.delete(param_list.syntax().text_range()),
+                _ => remove_list_items(builder, &params, &[type_param.syntax().clone()]),
+            }
+            if let Some(where_clause) = &where_clause {
+                remove_where_preds(builder, where_clause, &preds);
+            }
+        },
+    )
+}
+
+fn has_generic_args(name_ref: &ast::NameRef) -> bool {
+    let parent = match name_ref.syntax().parent() {
+        Some(it) => it,
+        None => return false,
+    };
+    if let Some(segment) = ast::PathSegment::cast(parent.clone()) {
+        return segment.generic_arg_list().is_some();
+    }
+    if let Some(call) = ast::MethodCallExpr::cast(parent) {
+        return call.generic_arg_list().is_some();
+    }
+    false
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    use crate::tests::check_assist;
+    use crate::tests::{check_assist, check_assist_not_applicable};
 
     #[test]
     fn replace_impl_trait_with_generic_params() {
@@ -165,4 +287,53 @@ mod tests {
             "#,
         );
     }
+
+    #[test]
+    fn replace_generic_with_impl_trait_inline_bounds() {
+        check_assist(
+            replace_generic_with_impl_trait,
+            r#"
+            fn foo<G, $0B: Bar>(g: G, bar: B) {}
+            "#,
+            r#"
+            fn foo<G>(g: G, bar: impl Bar) {}
+            "#,
+        );
+    }
+
+    #[test]
+    fn replace_generic_with_impl_trait_where_bounds() {
+        check_assist(
+            replace_generic_with_impl_trait,
+            r#"
+            fn foo<$0B, G>(bar: &B, g: G) where B: Foo, G: Copy, B: Bar {}
+            "#,
+            r#"
+            fn foo<G>(bar: &(impl Foo + Bar), g: G) where G: Copy {}
+            "#,
+        );
+    }
+
+    #[test]
+    fn replace_generic_with_impl_trait_not_applicable_when_used_twice() {
+        cov_mark::check!(replace_generic_used_twice);
+        check_assist_not_applicable(
+            replace_generic_with_impl_trait,
+            r#"
+            fn foo<$0B: Bar>(bar: B) -> B { bar }
+            "#,
+        );
+    }
+
+    #[test]
+    fn replace_generic_with_impl_trait_not_applicable_with_turbofish() {
+        cov_mark::check!(replace_generic_turbofish);
+        check_assist_not_applicable(
+            replace_generic_with_impl_trait,
+            r#"
+            fn foo<$0B: Bar>(bar: B) {}
+            fn main() { foo::<u32>(0); }
+            "#,
+        );
+    }
 }
@@ -0,0 +1,511 @@
+use itertools::Itertools;
+use syntax::{
+    ast::{self, AstNode, AttrsOwner, NameOwner},
+    match_ast, Direction, SyntaxNode, SyntaxToken, TextRange, T,
+};
+
+use crate::{AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: sort_items
+//
+// Sorts struct fields, enum variants, trait and impl items, or match arms
+// with path patterns alphabetically. With a selection, only the selected
+// items are sorted among themselves.
+//
+// ```
+// struct Point {
+//     $0y: u32,
+//     /// The horizontal part.
+//     x: u32,
+// }
+// ```
+// ->
+// ```
+// struct Point {
+//     /// The horizontal part.
+//     x: u32,
+//     y: u32,
+// }
+// ```
+pub(crate) fn sort_items(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let (label, parts) = sortable_list(ctx)?;
+    let selection = ctx.frange.range;
+    let mut moves = Vec::new();
+    for part in parts {
+        let items: Vec<SortItem> = part
+            .into_iter()
+            .filter(|item| selection.is_empty() || selection.contains_range(item.node.text_range()))
+            .collect();
+        let sorted: Vec<SortItem> =
+            items.iter().cloned().sorted_by_key(|item| item.key.to_lowercase()).collect();
+        moves.extend(items.into_iter().zip(sorted));
+    }
+    if moves.len() < 2 {
+        return None;
+    }
+    if moves.iter().all(|(slot, item)| slot.node == item.node) {
+        cov_mark::hit!(sort_items_already_sorted);
+        return None;
+    }
+
+    let target = moves.iter().map(|(slot, _)| slot.node.text_range()).fold1(|a, b| a.cover(b))?;
+    acc.add(AssistId("sort_items", AssistKind::RefactorRewrite), label, target, |builder| {
+        for (slot, item) in moves.iter().filter(|(slot, item)| slot.node != item.node) {
+            builder.replace(slot.range(), item.text_for(slot));
+        }
+    })
+}
+
+#[derive(Clone)]
+struct SortItem {
+    node: SyntaxNode,
+    key: String,
+    /// Match arms need their commas fixed up when they are moved.
+    arm: Option<MatchArmInfo>,
+}
+
+#[derive(Clone)]
+struct MatchArmInfo {
+    comma: Option<SyntaxToken>,
+    is_last: bool,
+    is_block: bool,
+}
+
+impl SortItem {
+    /// The range replaced by the item moved here, including the comma after a
+    /// match arm.
+    fn range(&self) -> TextRange {
+        let range = self.node.text_range();
+        match self.arm.as_ref().and_then(|arm| arm.comma.as_ref()) {
+            Some(comma) => range.cover(comma.text_range()),
+            None => range,
+        }
+    }
+
+    /// The text of this item when it's moved to where `slot` was.
+    fn text_for(&self, slot: &SortItem) -> String {
+        let text = self.node.to_string();
+        match (&self.arm, &slot.arm) {
+            (Some(this), Some(slot))
+                if !this.is_block && (!slot.is_last || slot.comma.is_some()) =>
+            {
+                format!("{},", text)
+            }
+            _ => text,
+        }
+    }
+}
+
+const FIELDS: &str = "Sort fields alphabetically";
+const VARIANTS: &str = "Sort variants alphabetically";
+const ITEMS: &str = "Sort items alphabetically";
+const ARMS: &str = "Sort match arms alphabetically";
+
+/// Finds the closest list of sortable items around the cursor, split into
+/// parts which are sorted separately, and a label for the assist.
+fn sortable_list(ctx: &AssistContext) -> Option<(&'static str, Vec<Vec<SortItem>>)> {
+    for node in ctx.covering_element().ancestors() {
+        let found = match_ast! {
+            match node {
+                ast::RecordFieldList(it) => Some((FIELDS, vec![fields(&it)?])),
+                ast::Struct(it) => match it.field_list()? {
+                    ast::FieldList::RecordFieldList(it) => Some((FIELDS, vec![fields(&it)?])),
+                    ast::FieldList::TupleFieldList(_) => None,
+                },
+                ast::VariantList(it) => Some((VARIANTS, vec![variants(&it)?])),
+                ast::Enum(it) => Some((VARIANTS, vec![variants(&it.variant_list()?)?])),
+                ast::AssocItemList(it) => Some((ITEMS, assoc_items(&it))),
+                ast::Impl(it) => Some((ITEMS, assoc_items(&it.assoc_item_list()?))),
+                ast::Trait(it) => Some((ITEMS, assoc_items(&it.assoc_item_list()?))),
+                ast::MatchArmList(it) => Some((ARMS, vec![match_arms(ctx, &it)?])),
+                ast::MatchExpr(it) => Some((ARMS, vec![match_arms(ctx, &it.match_arm_list()?)?])),
+                // Don't sort the items around a function body the cursor is in.
+                ast::BlockExpr(_it) => return None,
+                _ => None,
+            }
+        };
+        if found.is_some() {
+            return found;
+        }
+    }
+    None
+}
+
+fn fields(list: &ast::RecordFieldList) -> Option<Vec<SortItem>> {
+    let adt = list.syntax().ancestors().find_map(ast::Adt::cast)?;
+    if order_is_significant(&adt) {
+        return None;
+    }
+    let fields = list
+        .fields()
+        .filter_map(|field| {
+            Some(SortItem {
+                key: field.name()?.to_string(),
+                node: field.syntax().clone(),
+                arm: None,
+            })
+        })
+        .collect();
+    Some(fields)
+}
+
+/// Moving a variant without an explicit discriminant changes its value, if
+/// other variants have one.
+fn variants(list: &ast::VariantList) -> Option<Vec<SortItem>> {
+    let adt = list.syntax().ancestors().find_map(ast::Adt::cast)?;
+    if order_is_significant(&adt) {
+        return None;
+    }
+    let (explicit, implicit): (Vec<_>, Vec<_>) =
+        list.variants().partition(|variant| variant.expr().is_some());
+    if !explicit.is_empty() && !implicit.is_empty() {
+        cov_mark::hit!(sort_items_implicit_discriminant);
+        return None;
+    }
+    let variants = list
+        .variants()
+        .filter_map(|variant| {
+            let key = variant.name()?.to_string();
+            Some(SortItem { key, node: variant.syntax().clone(), arm: None })
+        })
+        .collect();
+    Some(variants)
+}
+
+/// The declaration order matters for the layout of `#[repr]` types, and for
+/// derived comparisons and hashes.
+fn order_is_significant(adt: &ast::Adt) -> bool {
+    let significant = adt.attrs().filter_map(|attr| attr.as_simple_call()).any(|(name, args)| {
+        match name.as_str() {
+            "repr" => true,
+            "derive" => args
+                .syntax()
+                .descendants_with_tokens()
+                .filter_map(|it| it.into_token())
+                .any(|token| matches!(token.text(), "PartialOrd" | "Ord" | "Hash")),
+            _ => false,
+        }
+    });
+    if significant {
+        cov_mark::hit!(sort_items_significant_order);
+    }
+    significant
+}
+
+/// Items without a name, like macro calls, keep their place and split the
+/// list into parts that are sorted separately.
+fn assoc_items(list: &ast::AssocItemList) -> Vec<Vec<SortItem>> {
+    let mut parts = vec![Vec::new()];
+    for item in list.assoc_items() {
+        let name = match &item {
+            ast::AssocItem::Fn(it) => it.name(),
+            ast::AssocItem::Const(it) => it.name(),
+            ast::AssocItem::TypeAlias(it) => it.name(),
+            ast::AssocItem::MacroCall(_) => None,
+        };
+        match name {
+            Some(name) => parts.last_mut().unwrap().push(SortItem {
+                key: name.to_string(),
+                node: item.syntax().clone(),
+                arm: None,
+            }),
+            None => parts.push(Vec::new()),
+        }
+    }
+    parts
+}
+
+/// Only lists of arms matching paths, like enum variants or constants, can
+/// be sorted without changing which arm is taken. A trailing `_` arm stays
+/// where it is.
+fn match_arms(ctx: &AssistContext, list: &ast::MatchArmList) -> Option<Vec<SortItem>> {
+    let mut arms: Vec<_> = list.arms().collect();
+    if let Some(ast::Pat::WildcardPat(_)) = arms.last().and_then(|arm| arm.pat()) {
+        arms.pop();
+    }
+    let last = list.arms().last();
+    arms.into_iter()
+        .map(|arm| {
+            let key = match arm.pat()? {
+                ast::Pat::PathPat(it) => it.path()?.to_string(),
+                ast::Pat::IdentPat(it) if ctx.sema.resolve_bind_pat_to_const(&it).is_some() => {
+                    it.name()?.to_string()
+                }
+                _ => {
+                    cov_mark::hit!(sort_items_complex_match_arm);
+                    return None;
+                }
+            };
+            if arm.guard().is_some() {
+                return None;
+            }
+            let info = MatchArmInfo {
+                comma: arm
+                    .syntax()
+                    .siblings_with_tokens(Direction::Next)
+                    .skip(1)
+                    .find(|it| !it.kind().is_trivia())
+                    .and_then(|it| it.into_token())
+                    .filter(|it| it.kind() == T![,]),
+                is_last: Some(&arm) == last.as_ref(),
+                is_block: matches!(arm.expr()?, ast::Expr::BlockExpr(_)),
+            };
+            Some(SortItem { key, node: arm.syntax().clone(), arm: Some(info) })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn sorts_fields_keeping_attrs_and_docs() {
+        check_assist(
+            sort_items,
+            r#"
+pub struct $0Config {
+    #[serde(default)]
+    pub verbose: bool,
+    /// Where to look.
+    /// Defaults to the cwd.
+    pub dir: String,
+    pub Cache: u32,
+}
+"#,
+            r#"
+pub struct Config {
+    pub Cache: u32,
+    /// Where to look.
+    /// Defaults to the cwd.
+    pub dir: String,
+    #[serde(default)]
+    pub verbose: bool,
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn sorts_variants() {
+        check_assist(
+            sort_items,
+            r#"
+enum Kind {
+    Struct,
+    Enum$0 { variants: u32 },
+    Const(u32),
+}
+"#,
+            r#"
+enum Kind {
+    Const(u32),
+    Enum { variants: u32 },
+    Struct,
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn sorts_impl_items() {
+        check_assist(
+            sort_items,
+            r#"
+struct S;
+impl$0 S {
+    fn new() -> S { S }
+
+    const MAX: u32 = 92;
+
+    fn get(&self) -> u32 { 0 }
+}
+"#,
+            r#"
+struct S;
+impl S {
+    fn get(&self) -> u32 { 0 }
+
+    const MAX: u32 = 92;
+
+    fn new() -> S { S }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn sorts_selected_trait_items() {
+        check_assist(
+            sort_items,
+            r#"
+trait T {
+    fn z();
+    $0fn c();
+    fn b();
+    fn a();$0
+}
+"#,
+            r#"
+trait T {
+    fn z();
+    fn a();
+    fn b();
+    fn c();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn sorts_every_part_around_macro_calls() {
+        check_assist(
+            sort_items,
+            r#"
+struct S;
+impl$0 S {
+    fn d() {}
+    fn c() {}
+    m!();
+    fn b() {}
+    fn a() {}
+}
+"#,
+            r#"
+struct S;
+impl S {
+    fn c() {}
+    fn d() {}
+    m!();
+    fn a() {}
+    fn b() {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn sorts_match_arms_fixing_commas() {
+        check_assist(
+            sort_items,
+            r#"
+enum E { A, B, C, D }
+fn f(e: E) -> u32 {
+    match$0 e {
+        E::D => 4,
+        E::C => {
+            3
+        }
+        E::B => 2,
+        E::A => 1,
+        _ => 0
+    }
+}
+"#,
+            r#"
+enum E { A, B, C, D }
+fn f(e: E) -> u32 {
+    match e {
+        E::A => 1,
+        E::B => 2,
+        E::C => {
+            3
+        }
+        E::D => 4,
+        _ => 0
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn sorts_last_match_arm() {
+        check_assist(
+            sort_items,
+            r#"
+enum Dir { Up, Down }
+use Dir::*;
+fn f(d: Dir) {
+    match d {
+        $0Up => {}
+        Down => ()
+    }
+}
+"#,
+            r#"
+enum Dir { Up, Down }
+use Dir::*;
+fn f(d: Dir) {
+    match d {
+        Down => (),
+        Up => {}
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_match_with_bindings() {
+        cov_mark::check!(sort_items_complex_match_arm);
+        check_assist_not_applicable(
+            sort_items,
+            r#"
+fn f(e: Option<u32>) {
+    match e {
+        $0Some(x) => {}
+        None => {}
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_when_sorted() {
+        cov_mark::check!(sort_items_already_sorted);
+        check_assist_not_applicable(sort_items, r#"struct S { $0a: u8, b: u8 }"#);
+    }
+
+    #[test]
+    fn not_applicable_in_fn_body() {
+        check_assist_not_applicable(
+            sort_items,
+            r#"
+struct S;
+impl S {
+    fn b() { $0 }
+    fn a() {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_repr_struct() {
+        cov_mark::check!(sort_items_significant_order);
+        check_assist_not_applicable(sort_items, r#"#[repr(C)] struct S { $0b: u8, a: u16 }"#);
+    }
+
+    #[test]
+    fn not_applicable_with_derived_ord() {
+        check_assist_not_applicable(
+            sort_items,
+            r#"#[derive(Clone, PartialOrd)] struct S { $0b: u8, a: u8 }"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_with_derived_hash() {
+        check_assist_not_applicable(sort_items, r#"#[derive(std::hash::Hash)] enum E { $0B, A }"#);
+    }
+
+    #[test]
+    fn not_applicable_with_implicit_discriminants() {
+        cov_mark::check!(sort_items_implicit_discriminant);
+        check_assist_not_applicable(sort_items, r#"enum E { $0C = 2, B, A }"#);
+    }
+}
@@ -0,0 +1,349 @@
+use ide_db::{defs::Definition, search::FileReference};
+use rustc_hash::FxHashSet;
+use syntax::{
+    ast::{self, edit::IndentLevel, AstNode},
+    match_ast, SyntaxNode, TextRange,
+};
+
+use crate::{assist_context::AssistBuilder, AssistContext, AssistId, AssistKind, Assists};
+
+use super::introduce_parameter::is_trait_item;
+
+// Assist: make_fn_async
+//
+// Makes a function `async`. Calls from async code get awaited, the other ones
+// are flagged with a comment.
+//
+// ```
+// fn $0fetch() -> u32 { 92 }
+//
+// async fn run() {
+//     let x = fetch();
+// }
+//
+// fn main() {
+//     fetch();
+// }
+// ```
+// ->
+// ```
+// async fn fetch() -> u32 { 92 }
+//
+// async fn run() {
+//     let x = fetch().await;
+// }
+//
+// fn main() {
+//     // FIXME: `fetch` is async now, this call needs to be awaited
+//     fetch();
+// }
+// ```
+pub(crate) fn make_fn_async(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let func = fn_at_header(ctx)?;
+    if func.async_token().is_some() || func.const_token().is_some() {
+        return None;
+    }
+    if is_trait_item(&func) {
+        cov_mark::hit!(make_fn_async_trait_fn);
+        return None;
+    }
+    let def = ctx.sema.to_def(&func)?;
+    let name = def.name(ctx.db());
+    let anchor = func
+        .unsafe_token()
+        .or_else(|| func.abi()?.syntax().first_token())
+        .or_else(|| func.fn_token())?;
+
+    acc.add(
+        AssistId("make_fn_async", AssistKind::RefactorRewrite),
+        "Make function async",
+        func.syntax().text_range(),
+        |builder| {
+            builder.insert(anchor.text_range().start(), "async ");
+            let comment = format!("FIXME: `{}` is async now, this call needs to be awaited", name);
+            for_each_call(ctx, builder, def, &comment, |builder, call| {
+                if !in_async_context(ctx, call.syntax()) {
+                    return false;
+                }
+                builder.insert(call.syntax().text_range().end(), ".await");
+                true
+            });
+        },
+    )
+}
+
+// Assist: make_fn_sync
+//
+// Makes an `async` function that doesn't await anything synchronous. Awaited
+// calls lose their `.await`, the other ones are flagged with a comment.
+//
+// ```
+// async fn $0fetch() -> u32 { 92 }
+//
+// async fn run() {
+//     let x = fetch().await;
+// }
+// ```
+// ->
+// ```
+// fn fetch() -> u32 { 92 }
+//
+// async fn run() {
+//     let x = fetch();
+// }
+// ```
+pub(crate) fn make_fn_sync(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let func = fn_at_header(ctx)?;
+    let async_token = func.async_token()?;
+    if is_trait_item(&func) {
+        return None;
+    }
+    let awaits =
+        func.body()?.syntax().descendants().filter_map(ast::AwaitExpr::cast).any(|it| {
+            enclosing_async_body(it.syntax()).map_or(false, |body| &body == func.syntax())
+        });
+    if awaits {
+        cov_mark::hit!(make_fn_sync_awaits);
+        return None;
+    }
+    let def = ctx.sema.to_def(&func)?;
+    let name = def.name(ctx.db());
+
+    acc.add(
+        AssistId("make_fn_sync", AssistKind::RefactorRewrite),
+        "Make function non-async",
+        func.syntax().text_range(),
+        |builder| {
+            let async_end = async_token
+                .next_token()
+                .filter(|it| it.kind().is_trivia())
+                .map_or(async_token.text_range(), |ws| ws.text_range());
+            builder.delete(async_token.text_range().cover(async_end));
+            let comment =
+                format!("FIXME: `{}` is not async anymore, this call is not a future", name);
+            for_each_call(ctx, builder, def, &comment, |builder, call| {
+                let await_expr = match call.syntax().parent().and_then(ast::AwaitExpr::cast) {
+                    Some(it) => it,
+                    None => return false,
+                };
+                let call_end = call.syntax().text_range().end();
+                builder.delete(TextRange::new(call_end, await_expr.syntax().text_range().end()));
+                true
+            });
+        },
+    )
+}
+
+/// The function whose signature the cursor is in.
+fn fn_at_header(ctx: &AssistContext) -> Option<ast::Fn> {
+    let func = ctx.find_node_at_offset::<ast::Fn>()?;
+    match func.body() {
+        Some(body) if body.syntax().text_range().start() <= ctx.offset() => None,
+        _ => Some(func),
+    }
+}
+
+/// Calls `fix` on each call to `def`. The calls it can't fix get `comment`
+/// put above their statement.
+fn for_each_call(
+    ctx: &AssistContext,
+    builder: &mut AssistBuilder,
+    def: hir::Function,
+    comment: &str,
+    mut fix: impl FnMut(&mut AssistBuilder, &ast::Expr) -> bool,
+) {
+    let usages = Definition::ModuleDef(def.into()).usages(&ctx.sema).all();
+    for (file_id, references) in usages {
+        builder.edit_file(file_id);
+        let mut flagged = FxHashSet::default();
+        for reference in references {
+            let call = match call_of(&reference) {
+                Some(it) => it,
+                None => continue,
+            };
+            if fix(builder, &call) {
+                continue;
+            }
+            let stmt = call.syntax().ancestors().find(|it| {
+                it.parent().map_or(false, |parent| ast::BlockExpr::can_cast(parent.kind()))
+            });
+            if let Some(stmt) = stmt {
+                let offset = stmt.text_range().start();
+                if flagged.insert(offset) {
+                    let indent = IndentLevel::from_node(&stmt);
+                    builder.insert(offset, format!("// {}\n{}", comment, indent));
+                }
+            }
+        }
+    }
+}
+
+/// The call expression calling the function referenced by `reference`.
+fn call_of(reference: &FileReference) -> Option<ast::Expr> {
+    let name_ref = match &reference.name {
+        ast::NameLike::NameRef(it) => it,
+        _ => return None,
+    };
+    let parent = name_ref.syntax().parent()?;
+    if let Some(method_call) = ast::MethodCallExpr::cast(parent) {
+        return Some(method_call.into());
+    }
+    let path = name_ref.syntax().ancestors().find_map(ast::PathExpr::cast)?;
+    let call = path.syntax().parent().and_then(ast::CallExpr::cast)?;
+    if call.expr()?.syntax() != path.syntax() {
+        return None;
+    }
+    Some(call.into())
+}
+
+fn in_async_context(ctx: &AssistContext, node: &SyntaxNode) -> bool {
+    match enclosing_async_body(node).and_then(ast::Fn::cast) {
+        Some(func) => ctx.sema.to_def(&func).map_or(false, |func| func.is_async(ctx.db())),
+        None => enclosing_async_body(node).is_some(),
+    }
+}
+
+/// The innermost function, closure or block `node` is evaluated in that is,
+/// or could be, async. Blocks that aren't async are transparent.
+fn enclosing_async_body(node: &SyntaxNode) -> Option<SyntaxNode> {
+    for node in node.ancestors().skip(1) {
+        match_ast! {
+            match node {
+                ast::Fn(_it) => return Some(node),
+                ast::ClosureExpr(it) => return it.async_token().map(|_| node),
+                ast::EffectExpr(it) => if it.async_token().is_some() {
+                    return Some(node);
+                },
+                ast::Const(_it) => return None,
+                ast::Static(_it) => return None,
+                _ => (),
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn awaits_calls_in_async_contexts() {
+        check_assist(
+            make_fn_async,
+            r#"
+struct S;
+impl S {
+    fn $0get(&self) -> u32 { 0 }
+}
+async fn a(s: S) -> u32 {
+    s.get() + 1
+}
+fn b(s: S) {
+    let f = async move { s.get() };
+    let g = || {
+        let x = S.get();
+    };
+}
+"#,
+            r#"
+struct S;
+impl S {
+    async fn get(&self) -> u32 { 0 }
+}
+async fn a(s: S) -> u32 {
+    s.get().await + 1
+}
+fn b(s: S) {
+    let f = async move { s.get().await };
+    let g = || {
+        // FIXME: `get` is async now, this call needs to be awaited
+        let x = S.get();
+    };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn flags_each_sync_statement_once() {
+        check_assist(
+            make_fn_async,
+            r#"
+pub unsafe fn $0f() -> u32 { 0 }
+fn main() {
+    let x = unsafe {
+        f() + f()
+    };
+}
+"#,
+            r#"
+pub async unsafe fn f() -> u32 { 0 }
+fn main() {
+    let x = unsafe {
+        // FIXME: `f` is async now, this call needs to be awaited
+        f() + f()
+    };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn make_async_not_applicable_in_trait_impl() {
+        cov_mark::check!(make_fn_async_trait_fn);
+        check_assist_not_applicable(
+            make_fn_async,
+            r#"
+trait Tr { fn f(&self); }
+impl Tr for () {
+    fn $0f(&self) {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn make_async_not_applicable_in_body() {
+        check_assist_not_applicable(make_fn_async, r#"fn f() { $0 }"#);
+    }
+
+    #[test]
+    fn removes_awaits_and_flags_futures() {
+        check_assist(
+            make_fn_sync,
+            r#"
+async fn $0f() -> u32 { 0 }
+async fn g() {
+    let x = f() . await;
+    let fut = f();
+}
+"#,
+            r#"
+fn f() -> u32 { 0 }
+async fn g() {
+    let x = f();
+    // FIXME: `f` is not async anymore, this call is not a future
+    let fut = f();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn make_sync_not_applicable_when_awaiting() {
+        cov_mark::check!(make_fn_sync_awaits);
+        check_assist_not_applicable(
+            make_fn_sync,
+            r#"
+async fn g() {}
+async fn $0f() {
+    let nested = async { g().await };
+    g().await;
+}
+"#,
+        );
+    }
+}
@@ -95,11 +95,33 @@ impl Assist {
         let sema = Semantics::new(db);
         let ctx = AssistContext::new(sema, config, range);
         let mut acc = Assists::new(&ctx, resolve);
-        handlers::all().iter().for_each(|handler| {
+        handlers::all().iter().for_each(|(_, handler)| {
             handler(&mut acc, &ctx);
         });
         acc.finish()
     }
+
+    /// Return the assists with the given id applicable at the given position,
+    /// running only the handler which produces them.
+    pub fn get_by_id(
+        db: &RootDatabase,
+        config: &AssistConfig,
+        resolve: bool,
+        range: FileRange,
+        assist_id: &str,
+    ) -> Vec<Assist> {
+        let handler = match handlers::by_id(assist_id) {
+            Some(it) => it,
+            None => return Vec::new(),
+        };
+        let sema = Semantics::new(db);
+        let ctx = AssistContext::new(sema, config, range);
+        let mut acc = Assists::new(&ctx, resolve);
+        handler(&mut acc, &ctx);
+        let mut res = acc.finish();
+        res.retain(|assist| assist.id.0 == assist_id);
+        res
+    }
 }
 
 mod handlers {
@@ -115,22 +137,31 @@ mod handlers {
     mod auto_import;
     mod change_visibility;
     mod convert_integer_literal;
+    mod convert_closure_to_fn;
     mod convert_comment_block;
+    mod convert_for_loop_to_iter_chain;
     mod convert_iter_for_each_to_for;
+    mod convert_struct_kind;
+    mod destructure_binding;
     mod early_return;
     mod expand_glob_import;
     mod extract_function;
+    mod extract_module;
     mod extract_struct_from_enum_variant;
+    mod extract_trait;
     mod extract_variable;
     mod fill_match_arms;
     mod fix_visibility;
     mod flip_binexpr;
     mod flip_comma;
     mod flip_trait_bound;
+    mod generate_builder;
     mod generate_default_from_enum_variant;
     mod generate_default_from_new;
     mod generate_is_empty_from_len;
+    mod generate_delegate_methods;
     mod generate_derive;
+    mod generate_documentation_template;
     mod generate_enum_is_method;
     mod generate_enum_projection_method;
     mod generate_from_impl_for_enum;
@@ -143,12 +174,15 @@ mod handlers {
     mod infer_function_return_type;
     mod inline_function;
     mod inline_local_variable;
+    mod inline_type_alias;
     mod introduce_named_lifetime;
+    mod introduce_parameter;
     mod invert_if;
     mod merge_imports;
     mod merge_match_arms;
     mod move_bounds;
     mod move_guard;
+    mod move_item_to_module;
     mod move_module_to_file;
     mod pull_assignment_up;
     mod qualify_path;
@@ -166,14 +200,36 @@ mod handlers {
     mod replace_qualified_name_with_use;
     mod replace_string_with_char;
     mod replace_unwrap_with_match;
+    mod sort_items;
     mod split_import;
+    mod toggle_async;
     mod toggle_ignore;
     mod unmerge_use;
     mod unwrap_block;
     mod wrap_return_type_in_result;
 
-    pub(crate) fn all() -> &'static [Handler] {
-        &[
+    /// Lists the handlers along with their names.
+    macro_rules! handlers {
+        ($($module:ident::$handler:ident,)*) => {
+            &[$((stringify!($handler), $module::$handler),)*]
+        };
+    }
+
+    /// The handler producing the assist with the given id. Most assists are
+    /// named after their handler.
+    pub(crate) fn by_id(assist_id: &str) -> Option<Handler> {
+        let name = match assist_id {
+            "add_type_ascription" => "add_turbo_fish",
+            "add_impl_missing_members" => "add_missing_impl_members",
+            "add_impl_default_members" => "add_missing_default_members",
+            "block_to_line" | "line_to_block" => "convert_comment_block",
+            _ => assist_id,
+        };
+        all().iter().find(|(it, _)| *it == name).map(|(_, handler)| *handler)
+    }
+
+    pub(crate) fn all() -> &'static [(&'static str, Handler)] {
+        handlers![
             // These are alphabetic for the foolish consistency
             add_explicit_type::add_explicit_type,
             add_lifetime_to_type::add_lifetime_to_type,
@@ -182,25 +238,39 @@ mod handlers {
             auto_import::auto_import,
             change_visibility::change_visibility,
             convert_integer_literal::convert_integer_literal,
+            convert_closure_to_fn::convert_closure_to_fn,
+            convert_closure_to_fn::convert_fn_to_closure,
             convert_comment_block::convert_comment_block,
+            convert_for_loop_to_iter_chain::convert_for_loop_to_iter_chain,
+            convert_for_loop_to_iter_chain::convert_iter_chain_to_for_loop,
             convert_iter_for_each_to_for::convert_iter_for_each_to_for,
+            convert_struct_kind::convert_named_struct_to_tuple_struct,
+            convert_struct_kind::convert_tuple_struct_to_named_struct,
+            destructure_binding::destructure_binding,
             early_return::convert_to_guarded_return,
+            early_return::convert_to_let_else,
             expand_glob_import::expand_glob_import,
             extract_struct_from_enum_variant::extract_struct_from_enum_variant,
+            extract_trait::extract_trait,
             fill_match_arms::fill_match_arms,
             fix_visibility::fix_visibility,
             flip_binexpr::flip_binexpr,
             flip_comma::flip_comma,
             flip_trait_bound::flip_trait_bound,
+            generate_builder::generate_builder,
             generate_default_from_enum_variant::generate_default_from_enum_variant,
             generate_default_from_new::generate_default_from_new,
             generate_is_empty_from_len::generate_is_empty_from_len,
+            generate_delegate_methods::generate_delegate_methods,
+            generate_delegate_methods::generate_delegate_trait,
             generate_derive::generate_derive,
+            generate_documentation_template::generate_documentation_template,
             generate_enum_is_method::generate_enum_is_method,
             generate_enum_projection_method::generate_enum_as_method,
             generate_enum_projection_method::generate_enum_try_into_method,
             generate_from_impl_for_enum::generate_from_impl_for_enum,
             generate_function::generate_function,
+            generate_function::generate_method,
             generate_getter_mut::generate_getter_mut,
             generate_getter::generate_getter,
             generate_impl::generate_impl,
@@ -209,13 +279,20 @@ mod handlers {
             infer_function_return_type::infer_function_return_type,
             inline_function::inline_function,
             inline_local_variable::inline_local_variable,
+            inline_type_alias::inline_type_alias,
+            inline_type_alias::inline_type_alias_uses,
             introduce_named_lifetime::introduce_named_lifetime,
+            introduce_parameter::introduce_parameter,
+            introduce_parameter::promote_local_to_param,
             invert_if::invert_if,
             merge_imports::merge_imports,
             merge_match_arms::merge_match_arms,
+            move_bounds::merge_bounds,
+            move_bounds::move_bounds_to_generic_params,
             move_bounds::move_bounds_to_where_clause,
             move_guard::move_arm_cond_to_match_guard,
             move_guard::move_guard_to_arm_body,
+            move_item_to_module::move_item_to_module,
             move_module_to_file::move_module_to_file,
             pull_assignment_up::pull_assignment_up,
             qualify_path::qualify_path,
@@ -231,11 +308,15 @@ mod handlers {
             replace_for_loop_with_for_each::replace_for_loop_with_for_each,
             replace_if_let_with_match::replace_if_let_with_match,
             replace_if_let_with_match::replace_match_with_if_let,
+            replace_impl_trait_with_generic::replace_generic_with_impl_trait,
             replace_impl_trait_with_generic::replace_impl_trait_with_generic,
             replace_let_with_if_let::replace_let_with_if_let,
             replace_qualified_name_with_use::replace_qualified_name_with_use,
             replace_unwrap_with_match::replace_unwrap_with_match,
+            sort_items::sort_items,
             split_import::split_import,
+            toggle_async::make_fn_async,
+            toggle_async::make_fn_sync,
             toggle_ignore::toggle_ignore,
             unmerge_use::unmerge_use,
             unwrap_block::unwrap_block,
@@ -252,6 +333,7 @@ mod handlers {
             //
             extract_variable::extract_variable,
             extract_function::extract_function,
+            extract_module::extract_module,
             // Are you sure you want to add new assist here, and not to the
             // sorted list above?
         ]
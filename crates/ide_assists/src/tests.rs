@@ -68,9 +68,9 @@ fn check_doc_test(assist_id: &str, before: &str, after: &str) {
     let before = db.file_text(file_id).to_string();
     let frange = FileRange { file_id, range: selection.into() };
 
-    let assist = Assist::get(&db, &TEST_CONFIG, true, frange)
+    let assist = Assist::get_by_id(&db, &TEST_CONFIG, true, frange, assist_id)
         .into_iter()
-        .find(|assist| assist.id.0 == assist_id)
+        .next()
         .unwrap_or_else(|| {
             panic!(
                 "\n\nAssist is not applicable: {}\nAvailable assists: {}",
@@ -194,6 +194,7 @@ fn assist_order_field_struct() {
     assert_eq!(assists.next().expect("expected assist").label, "Generate a mut getter method");
     assert_eq!(assists.next().expect("expected assist").label, "Generate a getter method");
     assert_eq!(assists.next().expect("expected assist").label, "Generate a setter method");
+    assert_eq!(assists.next().expect("expected assist").label, "Generate `FooBuilder`");
     assert_eq!(assists.next().expect("expected assist").label, "Add `#[derive]`");
 }
 
@@ -216,6 +217,7 @@ pub fn test_some_range(a: int) -> bool {
 
     expect![[r#"
         Convert integer base
+        Introduce parameter
         Extract into variable
         Extract into function
         Replace with match
@@ -223,6 +225,31 @@ pub fn test_some_range(a: int) -> bool {
     .assert_eq(&expected);
 }
 
+#[test]
+fn assist_by_id_works() {
+    let (db, frange) = RootDatabase::with_range(
+        r#"
+pub fn test_some_range(a: int) -> bool {
+    if let 2..6 = $05$0 {
+        true
+    } else {
+        false
+    }
+}
+"#,
+    );
+
+    let assists = Assist::get_by_id(&db, &TEST_CONFIG, false, frange, "extract_variable");
+    let expected = labels(&assists);
+    expect![[r#"
+        Extract into variable
+    "#]]
+    .assert_eq(&expected);
+
+    let assists = Assist::get_by_id(&db, &TEST_CONFIG, false, frange, "no_such_assist");
+    assert!(assists.is_empty());
+}
+
 #[test]
 fn assist_filter_works() {
     let (db, frange) = RootDatabase::with_range(
@@ -245,6 +272,7 @@ pub fn test_some_range(a: int) -> bool {
 
         expect![[r#"
             Convert integer base
+            Introduce parameter
             Extract into variable
             Extract into function
             Replace with match
@@ -192,6 +192,82 @@ pub(crate) fn frobnicate() {}
     )
 }
 
+#[test]
+fn doctest_convert_closure_to_fn() {
+    check_doc_test(
+        "convert_closure_to_fn",
+        r#####"
+#[lang = "copy"] pub trait Copy {}
+impl Copy for u32 {}
+fn main() {
+    let step = 2;
+    let add = |$0x: u32| x + step;
+}
+"#####,
+        r#####"
+#[lang = "copy"] pub trait Copy {}
+impl Copy for u32 {}
+fn main() {
+    let step = 2;
+    fn $0fun_name(x: u32, step: u32) -> u32 {
+        x + step
+    }
+
+    let add = |x| fun_name(x, step);
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_convert_fn_to_closure() {
+    check_doc_test(
+        "convert_fn_to_closure",
+        r#####"
+fn main() {
+    fn $0double(x: u32) -> u32 {
+        x * 2
+    }
+    let v: Vec<u32> = vec![1, 2].into_iter().map(double).collect();
+}
+"#####,
+        r#####"
+fn main() {
+    let v: Vec<u32> = vec![1, 2].into_iter().map(|x: u32| -> u32 {
+        x * 2
+    }).collect();
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_convert_for_loop_to_iter_chain() {
+    check_doc_test(
+        "convert_for_loop_to_iter_chain",
+        r#####"
+//- /lib.rs crate:core
+pub mod iter { pub mod traits { pub mod iterator { pub trait Iterator {} } } }
+pub struct SomeIter;
+impl self::iter::traits::iterator::Iterator for SomeIter {}
+//- /lib.rs crate:main deps:core
+use core::SomeIter;
+fn main() {
+    let mut doubled = Vec::new();
+    for$0 x in SomeIter {
+        doubled.push(x * 2);
+    }
+}
+"#####,
+        r#####"
+use core::SomeIter;
+fn main() {
+    let doubled: Vec<_> = SomeIter.map(|x| x * 2).collect();
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_convert_integer_literal() {
     check_doc_test(
@@ -205,6 +281,38 @@ const _: i32 = 0b1010;
     )
 }
 
+#[test]
+fn doctest_convert_iter_chain_to_for_loop() {
+    check_doc_test(
+        "convert_iter_chain_to_for_loop",
+        r#####"
+//- /lib.rs crate:core
+pub mod iter { pub mod traits { pub mod iterator { pub trait Iterator {} } } }
+pub struct SomeIter;
+impl self::iter::traits::iterator::Iterator for SomeIter {}
+//- /alloc.rs crate:alloc
+pub mod vec { pub struct Vec<T>(T); }
+//- /lib.rs crate:main deps:core,alloc
+use alloc::vec::Vec;
+use core::SomeIter;
+fn main() {
+    let doubled: Vec<i32> = SomeIter.map(|x| x * 2).coll$0ect();
+}
+"#####,
+        r#####"
+use alloc::vec::Vec;
+use core::SomeIter;
+fn main() {
+    let mut doubled: Vec<i32> = Vec::new();
+    for x in SomeIter {
+        let x = x * 2;
+        doubled.push(x);
+    }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_convert_iter_for_each_to_for() {
     check_doc_test(
@@ -235,6 +343,29 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_convert_named_struct_to_tuple_struct() {
+    check_doc_test(
+        "convert_named_struct_to_tuple_struct",
+        r#####"
+struct Point$0 { x: f32, y: f32 }
+
+fn origin() -> Point {
+    let p = Point { x: 0.0, y: 0.0 };
+    Point { y: p.y, x: p.x }
+}
+"#####,
+        r#####"
+struct Point(f32, f32);
+
+fn origin() -> Point {
+    let p = Point(0.0, 0.0);
+    Point(p.0, p.1)
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_convert_to_guarded_return() {
     check_doc_test(
@@ -259,6 +390,77 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_convert_to_let_else() {
+    check_doc_test(
+        "convert_to_let_else",
+        r#####"
+fn main() {
+    let x = $0if let Some(x) = foo() {
+        x
+    } else {
+        return;
+    };
+}
+"#####,
+        r#####"
+fn main() {
+    let Some(x) = foo() else {
+        return;
+    };
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_convert_tuple_struct_to_named_struct() {
+    check_doc_test(
+        "convert_tuple_struct_to_named_struct",
+        r#####"
+struct Point$0(f32, f32);
+
+fn origin() -> Point {
+    let p = Point(0.0, 0.0);
+    Point(p.0, p.1)
+}
+"#####,
+        r#####"
+struct Point { field1: f32, field2: f32 }
+
+fn origin() -> Point {
+    let p = Point { field1: 0.0, field2: 0.0 };
+    Point { field1: p.field1, field2: p.field2 }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_destructure_binding() {
+    check_doc_test(
+        "destructure_binding",
+        r#####"
+struct Point { x: f32, y: f32 }
+fn make_point() -> Point { Point { x: 0.0, y: 0.0 } }
+
+fn main() {
+    let $0p = make_point();
+    let len = (p.x * p.x + p.y * p.y).sqrt();
+}
+"#####,
+        r#####"
+struct Point { x: f32, y: f32 }
+fn make_point() -> Point { Point { x: 0.0, y: 0.0 } }
+
+fn main() {
+    let Point { x, y } = make_point();
+    let len = (x * x + y * y).sqrt();
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_expand_glob_import() {
     check_doc_test(
@@ -313,6 +515,41 @@ fn $0fun_name(n: i32) {
     )
 }
 
+#[test]
+fn doctest_extract_module() {
+    check_doc_test(
+        "extract_module",
+        r#####"
+const LIMIT: u32 = 10;
+
+$0fn clamp(x: u32) -> u32 {
+    x.min(LIMIT)
+}$0
+
+fn main() {
+    clamp(92);
+}
+"#####,
+        r#####"
+const LIMIT: u32 = 10;
+
+mod modname {
+    use super::LIMIT;
+
+    pub(crate) fn clamp(x: u32) -> u32 {
+        x.min(LIMIT)
+    }
+}
+
+use modname::clamp;
+
+fn main() {
+    clamp(92);
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_extract_struct_from_enum_variant() {
     check_doc_test(
@@ -328,6 +565,31 @@ enum A { One(One) }
     )
 }
 
+#[test]
+fn doctest_extract_trait() {
+    check_doc_test(
+        "extract_trait",
+        r#####"
+struct Foo;
+
+impl$0 Foo {
+    pub fn frobnicate(&self) -> u32 { 92 }
+}
+"#####,
+        r#####"
+struct Foo;
+
+pub trait FooExt {
+    fn frobnicate(&self) -> u32;
+}
+
+impl FooExt for Foo {
+    fn frobnicate(&self) -> u32 { 92 }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_extract_variable() {
     check_doc_test(
@@ -442,6 +704,58 @@ fn foo<T: Copy + Clone>() { }
     )
 }
 
+#[test]
+fn doctest_generate_builder() {
+    check_doc_test(
+        "generate_builder",
+        r#####"
+//- /lib.rs crate:core
+pub mod option { pub enum Option<T> { None, Some(T) } }
+//- /main.rs crate:main deps:core
+use core::option::Option;
+struct Config$0 {
+    name: String,
+    port: Option<u16>,
+}
+"#####,
+        r#####"
+use core::option::Option;
+struct Config {
+    name: String,
+    port: Option<u16>,
+}
+
+struct ConfigBuilder {
+    name: Option<String>,
+    port: Option<u16>,
+}
+
+impl ConfigBuilder {
+    fn new() -> Self {
+        Self { name: None, port: None }
+    }
+
+    fn name(mut self, name: String) -> Self {
+        self.name = Some(name);
+        self
+    }
+
+    fn port(mut self, port: u16) -> Self {
+        self.port = Some(port);
+        self
+    }
+
+    fn $0build(self) -> Result<Config, &'static str> {
+        Ok(Config {
+            name: self.name.ok_or("`name` is required")?,
+            port: self.port,
+        })
+    }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_generate_default_from_enum_variant() {
     check_doc_test(
@@ -500,6 +814,86 @@ impl Default for Example {
     )
 }
 
+#[test]
+fn doctest_generate_delegate_methods() {
+    check_doc_test(
+        "generate_delegate_methods",
+        r#####"
+struct Age(u8);
+impl Age {
+    fn age(&self) -> u8 {
+        self.0
+    }
+}
+
+struct Person {
+    ag$0e: Age,
+}
+"#####,
+        r#####"
+struct Age(u8);
+impl Age {
+    fn age(&self) -> u8 {
+        self.0
+    }
+}
+
+struct Person {
+    age: Age,
+}
+
+impl Person {
+    fn age(&self) -> u8 {
+        self.age.age()
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_generate_delegate_trait() {
+    check_doc_test(
+        "generate_delegate_trait",
+        r#####"
+trait Shape {
+    fn area(&self) -> f64;
+}
+struct Circle(f64);
+impl Shape for Circle {
+    fn area(&self) -> f64 {
+        3.14 * self.0 * self.0
+    }
+}
+
+struct Wheel {
+    r$0im: Circle,
+}
+"#####,
+        r#####"
+trait Shape {
+    fn area(&self) -> f64;
+}
+struct Circle(f64);
+impl Shape for Circle {
+    fn area(&self) -> f64 {
+        3.14 * self.0 * self.0
+    }
+}
+
+struct Wheel {
+    rim: Circle,
+}
+
+impl Shape for Wheel {
+    fn area(&self) -> f64 {
+        <Circle as Shape>::area(&self.rim)
+    }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_generate_derive() {
     check_doc_test(
@@ -520,6 +914,29 @@ struct Point {
     )
 }
 
+#[test]
+fn doctest_generate_documentation_template() {
+    check_doc_test(
+        "generate_documentation_template",
+        r#####"
+//- /lib.rs crate:mylib
+pub fn add$0(a: i32, b: i32) -> i32 { a + b }
+"#####,
+        r#####"
+/// Add.
+///
+/// # Examples
+///
+/// ```
+/// use mylib::add;
+///
+/// assert_eq!(add(a, b), );
+/// ```
+pub fn add(a: i32, b: i32) -> i32 { a + b }
+"#####,
+    )
+}
+
 #[test]
 fn doctest_generate_enum_as_method() {
     check_doc_test(
@@ -750,6 +1167,31 @@ impl MyStruct {
     )
 }
 
+#[test]
+fn doctest_generate_method() {
+    check_doc_test(
+        "generate_method",
+        r#####"
+struct Foo;
+fn foo(foo: Foo) {
+    let count: usize = foo.bar$0(1, "x");
+}
+"#####,
+        r#####"
+struct Foo;
+
+impl Foo {
+    fn bar(&self, arg_1: i32, arg_2: &str) -> usize {
+        todo!()
+    }
+}
+fn foo(foo: Foo) {
+    let count: usize = foo.bar(1, "x");
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_generate_new() {
     check_doc_test(
@@ -849,6 +1291,42 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_inline_type_alias() {
+    check_doc_test(
+        "inline_type_alias",
+        r#####"
+type A<T = u32> = Vec<T>;
+
+fn main() {
+    let a: $0A<i64> = Vec::new();
+}
+"#####,
+        r#####"
+type A<T = u32> = Vec<T>;
+
+fn main() {
+    let a: Vec<i64> = Vec::new();
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_inline_type_alias_uses() {
+    check_doc_test(
+        "inline_type_alias_uses",
+        r#####"
+type $0A = Vec<u32>;
+
+fn foo(a: A) -> A { a }
+"#####,
+        r#####"
+fn foo(a: Vec<u32>) -> Vec<u32> { a }
+"#####,
+    )
+}
+
 #[test]
 fn doctest_introduce_named_lifetime() {
     check_doc_test(
@@ -874,6 +1352,35 @@ impl<'a> Cursor<'a> {
     )
 }
 
+#[test]
+fn doctest_introduce_parameter() {
+    check_doc_test(
+        "introduce_parameter",
+        r#####"
+fn frobnicate(x: i32) -> i32 {
+    x * $0default_factor()$0
+}
+
+fn default_factor() -> i32 { 2 }
+
+fn main() {
+    frobnicate(4);
+}
+"#####,
+        r#####"
+fn frobnicate(x: i32, default_factor: i32) -> i32 {
+    x * default_factor
+}
+
+fn default_factor() -> i32 { 2 }
+
+fn main() {
+    frobnicate(4, default_factor());
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_invert_if() {
     check_doc_test(
@@ -891,6 +1398,57 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_make_fn_async() {
+    check_doc_test(
+        "make_fn_async",
+        r#####"
+fn $0fetch() -> u32 { 92 }
+
+async fn run() {
+    let x = fetch();
+}
+
+fn main() {
+    fetch();
+}
+"#####,
+        r#####"
+async fn fetch() -> u32 { 92 }
+
+async fn run() {
+    let x = fetch().await;
+}
+
+fn main() {
+    // FIXME: `fetch` is async now, this call needs to be awaited
+    fetch();
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_make_fn_sync() {
+    check_doc_test(
+        "make_fn_sync",
+        r#####"
+async fn $0fetch() -> u32 { 92 }
+
+async fn run() {
+    let x = fetch().await;
+}
+"#####,
+        r#####"
+fn fetch() -> u32 { 92 }
+
+async fn run() {
+    let x = fetch();
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_make_raw_string() {
     check_doc_test(
@@ -925,6 +1483,19 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_merge_bounds() {
+    check_doc_test(
+        "merge_bounds",
+        r#####"
+fn show<$0T: Clone>(x: T) where T: Debug, T: Clone {}
+"#####,
+        r#####"
+fn show<T: Clone + Debug>(x: T) {}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_merge_imports() {
     check_doc_test(
@@ -992,6 +1563,23 @@ fn handle(action: Action) {
     )
 }
 
+#[test]
+fn doctest_move_bounds_to_generic_params() {
+    check_doc_test(
+        "move_bounds_to_generic_params",
+        r#####"
+fn apply<T, F>(f: F, x: T) -> T where F: FnOnce(T) -> T$0, T: Clone {
+    f(x)
+}
+"#####,
+        r#####"
+fn apply<T: Clone, F: FnOnce(T) -> T>(f: F, x: T) -> T {
+    f(x)
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_move_bounds_to_where_clause() {
     check_doc_test(
@@ -1038,6 +1626,31 @@ fn handle(action: Action) {
     )
 }
 
+#[test]
+fn doctest_move_item_to_module() {
+    check_doc_test(
+        "move_item_to_module",
+        r#####"
+fn $0frobnicate() {}
+
+mod util {}
+
+fn main() {
+    frobnicate();
+}
+"#####,
+        r#####"
+mod util {
+    pub(crate) fn frobnicate() {}
+}
+
+fn main() {
+    util::frobnicate();
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_move_module_to_file() {
     check_doc_test(
@@ -1053,6 +1666,32 @@ mod foo;
     )
 }
 
+#[test]
+fn doctest_promote_local_to_param() {
+    check_doc_test(
+        "promote_local_to_param",
+        r#####"
+fn frobnicate() -> i32 {
+    let $0threshold = 92;
+    threshold * 2
+}
+
+fn main() {
+    frobnicate();
+}
+"#####,
+        r#####"
+fn frobnicate(threshold: i32) -> i32 {
+    threshold * 2
+}
+
+fn main() {
+    frobnicate(92);
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_pull_assignment_up() {
     check_doc_test(
@@ -1228,17 +1867,17 @@ fn doctest_replace_derive_with_manual_impl() {
     check_doc_test(
         "replace_derive_with_manual_impl",
         r#####"
-trait Debug { fn fmt(&self, f: &mut Formatter) -> Result<()>; }
-#[derive(Deb$0ug, Display)]
+trait Serialize { fn serialize(&self) -> String; }
+#[derive(Serial$0ize, Debug)]
 struct S;
 "#####,
         r#####"
-trait Debug { fn fmt(&self, f: &mut Formatter) -> Result<()>; }
-#[derive(Display)]
+trait Serialize { fn serialize(&self) -> String; }
+#[derive(Debug)]
 struct S;
 
-impl Debug for S {
-    fn fmt(&self, f: &mut Formatter) -> Result<()> {
+impl Serialize for S {
+    fn serialize(&self) -> String {
         ${0:todo!()}
     }
 }
@@ -1269,6 +1908,19 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_replace_generic_with_impl_trait() {
+    check_doc_test(
+        "replace_generic_with_impl_trait",
+        r#####"
+fn foo<$0B: Bar>(bar: B) {}
+"#####,
+        r#####"
+fn foo(bar: impl Bar) {}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_replace_if_let_with_match() {
     check_doc_test(
@@ -1420,6 +2072,27 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_sort_items() {
+    check_doc_test(
+        "sort_items",
+        r#####"
+struct Point {
+    $0y: u32,
+    /// The horizontal part.
+    x: u32,
+}
+"#####,
+        r#####"
+struct Point {
+    /// The horizontal part.
+    x: u32,
+    y: u32,
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_split_import() {
     check_doc_test(
@@ -1,25 +1,28 @@
 //! Assorted functions shared by several assists.
 
+pub(crate) mod gen_trait_fn_body;
 pub(crate) mod suggest_name;
 
 use std::ops;
 
 use ast::TypeBoundsOwner;
-use hir::{Adt, HasSource, Semantics};
+use hir::{known, Access, Adt, HasSource, Module, ModuleDef, Semantics};
 use ide_db::{
+    base_db::FileRange,
+    defs::Definition,
     helpers::{FamousDefs, SnippetCap},
     RootDatabase,
 };
 use itertools::Itertools;
 use stdx::format_to;
 use syntax::{
-    ast::edit::AstNodeEdit,
+    ast::edit::{AstNodeEdit, IndentLevel},
     ast::AttrsOwner,
     ast::NameOwner,
-    ast::{self, edit, make, ArgListOwner, GenericParamsOwner},
+    ast::{self, edit, make, ArgListOwner, GenericParamsOwner, VisibilityOwner},
     AstNode, Direction, SmolStr,
     SyntaxKind::*,
-    SyntaxNode, TextSize, T,
+    SyntaxNode, TextRange, TextSize, T,
 };
 
 use crate::{
@@ -402,16 +405,27 @@ pub(crate) fn find_impl_block_end(impl_def: ast::Impl, buf: &mut String) -> Opti
 // Generates the surrounding `impl Type { <code> }` including type and lifetime
 // parameters
 pub(crate) fn generate_impl_text(adt: &ast::Adt, code: &str) -> String {
-    generate_impl_text_inner(adt, None, code)
+    generate_impl_text_inner(adt, None, None, code)
+}
+
+// Generates the surrounding `impl <name> { <code> }` for a type that shares
+// the type and lifetime parameters of `adt`
+pub(crate) fn generate_impl_text_for_name(adt: &ast::Adt, name: &str, code: &str) -> String {
+    generate_impl_text_inner(adt, Some(name), None, code)
 }
 
 // Generates the surrounding `impl <trait> for Type { <code> }` including type
 // and lifetime parameters
 pub(crate) fn generate_trait_impl_text(adt: &ast::Adt, trait_text: &str, code: &str) -> String {
-    generate_impl_text_inner(adt, Some(trait_text), code)
+    generate_impl_text_inner(adt, None, Some(trait_text), code)
 }
 
-fn generate_impl_text_inner(adt: &ast::Adt, trait_text: Option<&str>, code: &str) -> String {
+fn generate_impl_text_inner(
+    adt: &ast::Adt,
+    name: Option<&str>,
+    trait_text: Option<&str>,
+    code: &str,
+) -> String {
     let generic_params = adt.generic_param_list();
     let mut buf = String::with_capacity(code.len());
     buf.push_str("\n\n");
@@ -442,7 +456,10 @@ fn generate_impl_text_inner(adt: &ast::Adt, trait_text: Option<&str>, code: &str
         buf.push_str(trait_text);
         buf.push_str(" for ");
     }
-    buf.push_str(adt.name().unwrap().text());
+    match name {
+        Some(name) => buf.push_str(name),
+        None => buf.push_str(adt.name().unwrap().text()),
+    }
     if let Some(generic_params) = generic_params {
         let lifetime_params = generic_params
             .lifetime_params()
@@ -488,3 +505,218 @@ pub(crate) fn add_method_to_adt(
 
     builder.insert(start_offset, buf);
 }
+
+/// The range of `node` with the whitespace following it.
+pub(crate) fn range_with_trailing_whitespace(node: &SyntaxNode) -> TextRange {
+    match node.next_sibling_or_token() {
+        Some(ws) if ws.kind() == WHITESPACE => node.text_range().cover(ws.text_range()),
+        _ => node.text_range(),
+    }
+}
+
+/// The range of `node` with the whitespace separating it from its next
+/// sibling, or from the previous one if it is the last item.
+pub(crate) fn range_with_surrounding_whitespace(node: &SyntaxNode) -> TextRange {
+    let has_next_item = node
+        .siblings_with_tokens(Direction::Next)
+        .skip(1)
+        .any(|it| !matches!(it.kind(), WHITESPACE | T!['}']));
+    match (node.next_sibling_or_token(), node.prev_sibling_or_token()) {
+        (Some(ws), _) if ws.kind() == WHITESPACE && has_next_item => {
+            node.text_range().cover(ws.text_range())
+        }
+        (_, Some(ws)) if ws.kind() == WHITESPACE => node.text_range().cover(ws.text_range()),
+        _ => node.text_range(),
+    }
+}
+
+/// Moves the lines of `text` after the first one from the indentation `from`
+/// to `to`.
+pub(crate) fn reindent(text: &str, from: IndentLevel, to: IndentLevel) -> String {
+    let from = from.to_string();
+    let to = to.to_string();
+    let mut lines = text.lines();
+    let mut res = lines.next().unwrap_or_default().to_string();
+    for line in lines {
+        res.push('\n');
+        if !line.trim().is_empty() {
+            res.push_str(&to);
+            res.push_str(line.strip_prefix(from.as_str()).unwrap_or(line));
+        }
+    }
+    res
+}
+
+/// How the expression, a use of a local, accesses it.
+pub(crate) fn usage_access(ctx: &AssistContext, expr: &ast::Expr) -> Access {
+    let parent = match expr.syntax().parent() {
+        Some(it) => it,
+        None => return Access::Owned,
+    };
+    if let Some(bin_expr) = ast::BinExpr::cast(parent.clone()) {
+        let is_assignment = bin_expr.op_kind().map_or(false, |it| it.is_assignment());
+        let is_lhs = bin_expr.lhs().map_or(false, |lhs| lhs.syntax() == expr.syntax());
+        return if is_assignment && is_lhs { Access::Exclusive } else { Access::Shared };
+    }
+    if let Some(ref_expr) = ast::RefExpr::cast(parent.clone()) {
+        return if ref_expr.mut_token().is_some() { Access::Exclusive } else { Access::Shared };
+    }
+    if let Some(method_call) = ast::MethodCallExpr::cast(parent.clone()) {
+        return ctx
+            .sema
+            .resolve_method_call(&method_call)
+            .and_then(|func| func.self_param(ctx.db()))
+            .map_or(Access::Shared, |self_param| self_param.access(ctx.db()));
+    }
+    if let Some(field) = ast::FieldExpr::cast(parent) {
+        return usage_access(ctx, &field.into());
+    }
+    Access::Owned
+}
+
+/// Whether `item` is private, and would need a visibility to be used from
+/// another module.
+pub(crate) fn item_needs_visibility(item: &ast::Item) -> bool {
+    let vis = match item {
+        ast::Item::Fn(it) => it.visibility(),
+        ast::Item::Struct(it) => it.visibility(),
+        ast::Item::Enum(it) => it.visibility(),
+        ast::Item::Union(it) => it.visibility(),
+        ast::Item::Const(it) => it.visibility(),
+        ast::Item::Static(it) => it.visibility(),
+        ast::Item::Trait(it) => it.visibility(),
+        ast::Item::TypeAlias(it) => it.visibility(),
+        _ => return false,
+    };
+    vis.is_none()
+}
+
+/// Fields and inherent methods of `item` that have no visibility.
+pub(crate) fn inner_private_defs(
+    ctx: &AssistContext,
+    item: &ast::Item,
+) -> Vec<(SyntaxNode, Definition)> {
+    let mut res = Vec::new();
+    let field_list = match item {
+        ast::Item::Struct(it) => it.field_list(),
+        ast::Item::Union(it) => it.record_field_list().map(ast::FieldList::RecordFieldList),
+        ast::Item::Impl(it) if it.trait_().is_none() => {
+            let assoc_items = it.assoc_item_list().into_iter().flat_map(|it| it.assoc_items());
+            for assoc_item in assoc_items {
+                let def = match &assoc_item {
+                    ast::AssocItem::Fn(it) if it.visibility().is_none() => {
+                        ctx.sema.to_def(it).map(ModuleDef::Function)
+                    }
+                    ast::AssocItem::Const(it) if it.visibility().is_none() => {
+                        ctx.sema.to_def(it).map(ModuleDef::Const)
+                    }
+                    _ => None,
+                };
+                if let Some(def) = def {
+                    res.push((assoc_item.syntax().clone(), Definition::ModuleDef(def)));
+                }
+            }
+            None
+        }
+        _ => None,
+    };
+    match field_list {
+        Some(ast::FieldList::RecordFieldList(fields)) => {
+            for field in fields.fields().filter(|it| it.visibility().is_none()) {
+                if let Some(def) = ctx.sema.to_def(&field) {
+                    res.push((field.syntax().clone(), Definition::Field(def)));
+                }
+            }
+        }
+        Some(ast::FieldList::TupleFieldList(fields)) => {
+            for field in fields.fields().filter(|it| it.visibility().is_none()) {
+                if let Some(def) = ctx.sema.to_def(&field) {
+                    res.push((field.syntax().clone(), Definition::Field(def)));
+                }
+            }
+        }
+        None => (),
+    }
+    res
+}
+
+/// Whether `def` is used from outside of `target_module` and its descendants,
+/// not counting the uses inside the moved range itself. Without a target
+/// module, every use outside of the moved range counts.
+pub(crate) fn used_outside(
+    ctx: &AssistContext,
+    def: Definition,
+    moved: FileRange,
+    target_module: Option<Module>,
+) -> bool {
+    def.usages(&ctx.sema).all().into_iter().any(|(file_id, references)| {
+        references.into_iter().any(|reference| {
+            if file_id == moved.file_id && moved.range.contains_range(reference.range) {
+                return false;
+            }
+            let target_module = match target_module {
+                Some(it) => it,
+                None => return true,
+            };
+            match ctx.sema.scope(reference.name.syntax()).module() {
+                Some(module) => !module.path_to_root(ctx.db()).contains(&target_module),
+                None => false,
+            }
+        })
+    })
+}
+
+/// If iterable is a reference where the expression behind the reference implements a method
+/// returning an Iterator called iter or iter_mut (depending on the type of reference) then return
+/// the expression behind the reference and the method name
+pub(crate) fn is_ref_and_impls_iter_method(
+    sema: &hir::Semantics<ide_db::RootDatabase>,
+    iterable: &ast::Expr,
+) -> Option<(ast::Expr, hir::Name)> {
+    let ref_expr = match iterable {
+        ast::Expr::RefExpr(r) => r,
+        _ => return None,
+    };
+    let wanted_method = if ref_expr.mut_token().is_some() { known::iter_mut } else { known::iter };
+    let expr_behind_ref = ref_expr.expr()?;
+    let typ = sema.type_of_expr(&expr_behind_ref)?;
+    let scope = sema.scope(iterable.syntax());
+    let krate = scope.module()?.krate();
+    let traits_in_scope = scope.traits_in_scope();
+    let iter_trait = FamousDefs(sema, Some(krate)).core_iter_Iterator()?;
+    let has_wanted_method = typ.iterate_method_candidates(
+        sema.db,
+        krate,
+        &traits_in_scope,
+        Some(&wanted_method),
+        |_, func| {
+            if func.ret_type(sema.db).impls_trait(sema.db, iter_trait, &[]) {
+                return Some(());
+            }
+            None
+        },
+    );
+    has_wanted_method.and(Some((expr_behind_ref, wanted_method)))
+}
+
+/// Whether iterable implements core::Iterator
+pub(crate) fn impls_core_iter(
+    sema: &hir::Semantics<ide_db::RootDatabase>,
+    iterable: &ast::Expr,
+) -> bool {
+    let it_typ = if let Some(i) = sema.type_of_expr(iterable) {
+        i
+    } else {
+        return false;
+    };
+    let module = if let Some(m) = sema.scope(iterable.syntax()).module() {
+        m
+    } else {
+        return false;
+    };
+    let krate = module.krate();
+    if let Some(iter_trait) = FamousDefs(sema, Some(krate)).core_iter_Iterator() {
+        return it_typ.impls_trait(sema.db, iter_trait, &[]);
+    }
+    false
+}
@@ -0,0 +1,338 @@
+//! Generates the bodies of the methods of std traits that can be derived, the
+//! same way the derive macros would.
+
+use hir::{db::HirDatabase, Adt, StructKind};
+use syntax::{
+    ast::{self, edit::AstNodeEdit, edit::IndentLevel, make, NameOwner},
+    T,
+};
+
+/// Generates a body for `func`, the required method of the derivable trait at
+/// `trait_path`, implemented for `adt`. Returns `None` for other traits.
+pub(crate) fn gen_trait_fn_body(
+    db: &dyn HirDatabase,
+    func: &ast::Fn,
+    trait_path: &ast::Path,
+    adt: Adt,
+) -> Option<ast::BlockExpr> {
+    let trait_name = trait_path.segment()?.name_ref()?;
+    let fn_name = func.name()?;
+    let shape = AdtShape::new(db, adt)?;
+    match (trait_name.text(), fn_name.text()) {
+        ("Clone", "clone") => gen_clone_impl(&shape),
+        ("Debug", "fmt") => gen_debug_impl(&shape, &param_name(func, 0)?),
+        ("Display", "fmt") => gen_display_impl(&shape, &param_name(func, 0)?),
+        ("Hash", "hash") => gen_hash_impl(&shape, &param_name(func, 0)?),
+        ("PartialEq", "eq") => gen_partial_eq(&shape, &param_name(func, 0)?),
+        _ => None,
+    }
+}
+
+/// The parts of a struct or an enum variant the generated bodies care about.
+struct FieldsShape {
+    name: String,
+    kind: StructKind,
+    fields: Vec<String>,
+}
+
+enum AdtShape {
+    Struct(FieldsShape),
+    Enum(Vec<FieldsShape>),
+}
+
+impl AdtShape {
+    fn new(db: &dyn HirDatabase, adt: Adt) -> Option<AdtShape> {
+        let field_names = |fields: Vec<hir::Field>| {
+            fields.into_iter().map(|it| it.name(db).to_string()).collect()
+        };
+        let shape = match adt {
+            Adt::Struct(it) => AdtShape::Struct(FieldsShape {
+                name: it.name(db).to_string(),
+                kind: it.kind(db),
+                fields: field_names(it.fields(db)),
+            }),
+            Adt::Union(_) => return None,
+            Adt::Enum(it) => AdtShape::Enum(
+                it.variants(db)
+                    .into_iter()
+                    .map(|variant| FieldsShape {
+                        name: variant.name(db).to_string(),
+                        kind: variant.kind(db),
+                        fields: field_names(variant.fields(db)),
+                    })
+                    .collect(),
+            ),
+        };
+        Some(shape)
+    }
+}
+
+impl FieldsShape {
+    fn path(&self) -> ast::Path {
+        make::path_from_text(&format!("Self::{}", self.name))
+    }
+
+    /// A pattern matching the variant, binding its fields to `{prefix}{field}`.
+    fn pat(&self, prefix: &str) -> ast::Pat {
+        match self.kind {
+            StructKind::Record if prefix.is_empty() && !self.fields.is_empty() => {
+                let fields =
+                    self.fields.iter().map(|field| make::ident_pat(make::name(field)).into());
+                make::record_pat(self.path(), fields).into()
+            }
+            StructKind::Record => {
+                let fields = self.fields.iter().map(|field| {
+                    let binding = make::ident_pat(make::name(&format!("{}{}", prefix, field)));
+                    make::record_pat_field(make::name_ref(field), binding.into())
+                });
+                let fields = make::record_pat_field_list(fields);
+                make::record_pat_with_fields(self.path(), fields).into()
+            }
+            StructKind::Tuple => {
+                let fields = self
+                    .fields
+                    .iter()
+                    .map(|field| make::ident_pat(make::name(&tuple_binding(prefix, field))).into());
+                make::tuple_struct_pat(self.path(), fields).into()
+            }
+            StructKind::Unit => make::path_pat(self.path()),
+        }
+    }
+
+    fn binding(&self, prefix: &str, field: &str) -> ast::Expr {
+        let name = match self.kind {
+            StructKind::Tuple => tuple_binding(prefix, field),
+            _ => format!("{}{}", prefix, field),
+        };
+        path_expr(&name)
+    }
+}
+
+fn gen_clone_impl(shape: &AdtShape) -> Option<ast::BlockExpr> {
+    fn construct(
+        variant: &FieldsShape,
+        path: ast::Path,
+        field_value: impl Fn(&str) -> ast::Expr,
+    ) -> ast::Expr {
+        let clone =
+            |field: &str| make::expr_method_call(field_value(field), "clone", make::arg_list(None));
+        match variant.kind {
+            StructKind::Record => {
+                let fields = variant.fields.iter().map(|field| {
+                    make::record_expr_field(make::name_ref(field), Some(clone(field)))
+                });
+                make::record_expr(path, make::record_expr_field_list(fields)).into()
+            }
+            StructKind::Tuple => {
+                let args = variant.fields.iter().map(|field| clone(field));
+                make::expr_call(make::expr_path(path), make::arg_list(args))
+            }
+            StructKind::Unit => make::expr_path(path),
+        }
+    }
+
+    let body = match shape {
+        AdtShape::Struct(it) => construct(it, make::path_from_text("Self"), |field| {
+            make::expr_field(path_expr("self"), field)
+        }),
+        AdtShape::Enum(variants) => {
+            let arms = variants.iter().map(|variant| {
+                let expr = construct(variant, variant.path(), |field| variant.binding("", field));
+                make::match_arm(Some(variant.pat("")), expr)
+            });
+            match_self(arms)
+        }
+    };
+    Some(block(None, Some(body)))
+}
+
+fn gen_debug_impl(shape: &AdtShape, formatter: &str) -> Option<ast::BlockExpr> {
+    fn debug(
+        formatter: &str,
+        variant: &FieldsShape,
+        field_value: impl Fn(&str) -> ast::Expr,
+    ) -> ast::Expr {
+        let name = make::expr_literal(&format!("\"{}\"", variant.name)).into();
+        let formatter = path_expr(formatter);
+        let builder = match variant.kind {
+            StructKind::Record => "debug_struct",
+            StructKind::Tuple => "debug_tuple",
+            StructKind::Unit => {
+                return make::expr_method_call(formatter, "write_str", make::arg_list(Some(name)))
+            }
+        };
+        let mut expr = make::expr_method_call(formatter, builder, make::arg_list(Some(name)));
+        for field in &variant.fields {
+            let mut args = Vec::new();
+            if variant.kind == StructKind::Record {
+                args.push(make::expr_literal(&format!("\"{}\"", field)).into());
+            }
+            args.push(field_value(field));
+            expr = make::expr_method_call(expr, "field", make::arg_list(args));
+        }
+        make::expr_method_call(expr, "finish", make::arg_list(None))
+    }
+
+    let body = match shape {
+        AdtShape::Struct(it) => debug(formatter, it, |field| {
+            make::expr_ref(make::expr_field(path_expr("self"), field), false)
+        }),
+        AdtShape::Enum(variants) => {
+            let arms = variants.iter().map(|variant| {
+                let expr = debug(formatter, variant, |field| variant.binding("", field));
+                make::match_arm(Some(variant.pat("")), expr)
+            });
+            match_self(arms)
+        }
+    };
+    Some(block(None, Some(body)))
+}
+
+/// `Display` can't be derived, but a fieldless enum is usually displayed by
+/// the names of its variants.
+fn gen_display_impl(shape: &AdtShape, formatter: &str) -> Option<ast::BlockExpr> {
+    let variants = match shape {
+        AdtShape::Enum(variants)
+            if variants.iter().all(|variant| variant.kind == StructKind::Unit) =>
+        {
+            variants
+        }
+        _ => return None,
+    };
+    let arms = variants.iter().map(|variant| {
+        let name = make::expr_literal(&format!("\"{}\"", variant.name)).into();
+        let expr =
+            make::expr_method_call(path_expr(formatter), "write_str", make::arg_list(Some(name)));
+        make::match_arm(Some(variant.pat("")), expr)
+    });
+    Some(block(None, Some(match_self(arms))))
+}
+
+fn gen_hash_impl(shape: &AdtShape, state: &str) -> Option<ast::BlockExpr> {
+    let hash = |expr: ast::Expr| -> ast::Stmt {
+        let call = make::expr_method_call(expr, "hash", make::arg_list(Some(path_expr(state))));
+        make::expr_stmt(call).into()
+    };
+
+    let body = match shape {
+        AdtShape::Struct(it) => {
+            let stmts =
+                it.fields.iter().map(|field| hash(make::expr_field(path_expr("self"), field)));
+            block(stmts, None)
+        }
+        AdtShape::Enum(variants) => {
+            let mut stmts = vec![hash(discriminant("self"))];
+            let (with_fields, without_fields): (Vec<_>, Vec<_>) =
+                variants.iter().partition(|variant| !variant.fields.is_empty());
+            if !with_fields.is_empty() {
+                let mut arms: Vec<_> = with_fields
+                    .iter()
+                    .map(|variant| {
+                        let stmts =
+                            variant.fields.iter().map(|field| hash(variant.binding("", field)));
+                        let expr = block(stmts, None).indent(IndentLevel(1));
+                        make::match_arm(Some(variant.pat("")), expr.into())
+                    })
+                    .collect();
+                if !without_fields.is_empty() {
+                    arms.push(make::match_arm(
+                        Some(make::wildcard_pat().into()),
+                        make::expr_empty_block(),
+                    ));
+                }
+                let expr = make::expr_match(path_expr("self"), make::match_arm_list(arms));
+                stmts.push(make::expr_stmt(expr).into());
+            }
+            block(stmts, None)
+        }
+    };
+    Some(body)
+}
+
+fn gen_partial_eq(shape: &AdtShape, other: &str) -> Option<ast::BlockExpr> {
+    fn all_equal(
+        fields: &[String],
+        lhs: impl Fn(&str) -> ast::Expr,
+        rhs: impl Fn(&str) -> ast::Expr,
+    ) -> ast::Expr {
+        fields
+            .iter()
+            .map(|field| make::expr_bin_op(lhs(field), T![==], rhs(field)))
+            .fold(None, |acc, cmp| match acc {
+                Some(acc) => Some(make::expr_bin_op(acc, T![&&], cmp)),
+                None => Some(cmp),
+            })
+            .unwrap_or_else(|| make::expr_literal("true").into())
+    }
+
+    let body = match shape {
+        AdtShape::Struct(it) => all_equal(
+            &it.fields,
+            |field| make::expr_field(path_expr("self"), field),
+            |field| make::expr_field(path_expr(other), field),
+        ),
+        AdtShape::Enum(variants) if variants.is_empty() => match_self(None),
+        AdtShape::Enum(variants) => {
+            let same_variant = make::expr_bin_op(discriminant("self"), T![==], discriminant(other));
+            let mut arms: Vec<_> = variants
+                .iter()
+                .filter(|variant| !variant.fields.is_empty())
+                .map(|variant| {
+                    let pat = make::tuple_pat(vec![variant.pat("l_"), variant.pat("r_")]).into();
+                    let expr = all_equal(
+                        &variant.fields,
+                        |field| variant.binding("l_", field),
+                        |field| variant.binding("r_", field),
+                    );
+                    make::match_arm(Some(pat), expr)
+                })
+                .collect();
+            if arms.is_empty() {
+                same_variant
+            } else {
+                arms.push(make::match_arm(Some(make::wildcard_pat().into()), same_variant));
+                let scrutinee = make::expr_tuple(vec![path_expr("self"), path_expr(other)]);
+                make::expr_match(scrutinee, make::match_arm_list(arms))
+            }
+        }
+    };
+    Some(block(None, Some(body)))
+}
+
+fn param_name(func: &ast::Fn, idx: usize) -> Option<String> {
+    let param = func.param_list()?.params().nth(idx)?;
+    Some(param.pat()?.to_string())
+}
+
+fn tuple_binding(prefix: &str, field: &str) -> String {
+    if prefix.is_empty() {
+        format!("arg{}", field)
+    } else {
+        format!("{}{}", prefix, field)
+    }
+}
+
+fn path_expr(text: &str) -> ast::Expr {
+    make::expr_path(make::path_from_text(text))
+}
+
+fn discriminant(expr: &str) -> ast::Expr {
+    let func = path_expr("core::mem::discriminant");
+    make::expr_call(func, make::arg_list(Some(path_expr(expr))))
+}
+
+/// `match self { .. }`, or `match *self {}` when there is nothing to match on.
+fn match_self(arms: impl IntoIterator<Item = ast::MatchArm>) -> ast::Expr {
+    let arms: Vec<_> = arms.into_iter().collect();
+    let scrutinee = if arms.is_empty() {
+        make::expr_prefix(T![*], path_expr("self"))
+    } else {
+        path_expr("self")
+    };
+    make::expr_match(scrutinee, make::match_arm_list(arms))
+}
+
+fn block(stmts: impl IntoIterator<Item = ast::Stmt>, tail: Option<ast::Expr>) -> ast::BlockExpr {
+    let stmts = stmts.into_iter().map(|stmt| stmt.indent(IndentLevel(1)));
+    make::block_expr(stmts, tail.map(|tail| tail.indent(IndentLevel(1))))
+}
@@ -2,6 +2,7 @@
 
 pub(crate) mod attribute;
 pub(crate) mod dot;
+pub(crate) mod expected_type;
 pub(crate) mod record;
 pub(crate) mod pattern;
 pub(crate) mod fn_param;
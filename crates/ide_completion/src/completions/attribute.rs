@@ -1,11 +1,13 @@
 //! Completion for attributes
 //!
 //! This module uses a bit of static metadata to provide completions
-//! for built-in attributes.
+//! for built-in attributes, and the crate graph to complete `cfg` options.
+
+use std::iter;
 
 use itertools::Itertools;
 use rustc_hash::FxHashSet;
-use syntax::{ast, AstNode, T};
+use syntax::{ast, AstNode, SyntaxKind, SyntaxToken, T};
 
 use crate::{
     context::CompletionContext,
@@ -30,17 +32,28 @@ pub(crate) fn complete_attribute(acc: &mut Completions, ctx: &CompletionContext)
             } else if path == "allow" || path == "warn" || path == "deny" || path == "forbid" {
                 complete_lint(acc, ctx, token_tree.clone(), DEFAULT_LINT_COMPLETIONS);
                 complete_lint(acc, ctx, token_tree, CLIPPY_LINTS);
+            } else if path == "cfg" || path == "cfg_attr" {
+                complete_cfg(acc, ctx, token_tree)
+            } else if path == "doc" {
+                complete_doc(acc, ctx, attribute, token_tree)
             }
         }
         (_, Some(_token_tree)) => {}
-        _ => complete_attribute_start(acc, ctx, attribute),
+        _ => complete_attribute_start(acc, ctx, attribute, ATTRIBUTES),
     }
     Some(())
 }
 
-fn complete_attribute_start(acc: &mut Completions, ctx: &CompletionContext, attribute: &ast::Attr) {
+fn complete_attribute_start<'a>(
+    acc: &mut Completions,
+    ctx: &CompletionContext,
+    attribute: &ast::Attr,
+    attr_completions: impl IntoIterator<Item = &'a AttrCompletion>,
+) {
     let is_inner = attribute.kind() == ast::AttrKind::Inner;
-    for attr_completion in ATTRIBUTES.iter().filter(|compl| is_inner || !compl.prefer_inner) {
+    for attr_completion in
+        attr_completions.into_iter().filter(|compl| is_inner || !compl.prefer_inner)
+    {
         let mut item = CompletionItem::new(
             CompletionKind::Attribute,
             ctx.source_range(),
@@ -56,9 +69,7 @@ fn complete_attribute_start(acc: &mut Completions, ctx: &CompletionContext, attr
             item.insert_snippet(cap, snippet);
         }
 
-        if attribute.kind() == ast::AttrKind::Inner || !attr_completion.prefer_inner {
-            acc.add(item.build());
-        }
+        acc.add(item.build());
     }
 }
 
@@ -153,6 +164,137 @@ const ATTRIBUTES: &[AttrCompletion] = &[
     .prefer_inner(),
 ];
 
+/// https://doc.rust-lang.org/rustdoc/the-doc-attribute.html
+const DOC_KEYS: &[AttrCompletion] = &[
+    attr(r#"alias = "…""#, Some("alias"), Some(r#"alias = "${0:alias}""#)),
+    attr("cfg(…)", Some("cfg"), Some("cfg(${0:predicate})")),
+    attr("hidden", None, None),
+    attr(
+        r#"html_favicon_url = "…""#,
+        Some("html_favicon_url"),
+        Some(r#"html_favicon_url = "${0:url}""#),
+    )
+    .prefer_inner(),
+    attr(r#"html_logo_url = "…""#, Some("html_logo_url"), Some(r#"html_logo_url = "${0:url}""#))
+        .prefer_inner(),
+    attr("html_no_source", None, None).prefer_inner(),
+    attr(
+        r#"html_playground_url = "…""#,
+        Some("html_playground_url"),
+        Some(r#"html_playground_url = "${0:url}""#),
+    )
+    .prefer_inner(),
+    attr(r#"html_root_url = "…""#, Some("html_root_url"), Some(r#"html_root_url = "${0:url}""#))
+        .prefer_inner(),
+    attr("inline", None, None),
+    attr(
+        r#"issue_tracker_base_url = "…""#,
+        Some("issue_tracker_base_url"),
+        Some(r#"issue_tracker_base_url = "${0:url}""#),
+    )
+    .prefer_inner(),
+    attr("no_inline", None, None),
+    attr("test(…)", Some("test"), Some("test(${0:attr})")).prefer_inner(),
+];
+
+fn complete_doc(
+    acc: &mut Completions,
+    ctx: &CompletionContext,
+    attribute: &ast::Attr,
+    doc_input: ast::TokenTree,
+) {
+    // Only the keys of `doc(...)` itself, not of a nested `doc(test(...))`.
+    let is_top_level_key = previous_token(ctx).map_or(false, |token| {
+        matches!(token.kind(), T!['('] | T![,])
+            && token.parent().parent().map_or(false, |it| ast::Attr::can_cast(it.kind()))
+    });
+    if !is_top_level_key {
+        return;
+    }
+    if let Ok(existing_entries) = parse_comma_sep_input(doc_input) {
+        // The entries are like `hidden`, `alias="x"` or `cfg`, without the token tree.
+        let existing_keys: FxHashSet<&str> = existing_entries
+            .iter()
+            .filter_map(|it| it.split(|c: char| !c.is_alphanumeric() && c != '_').next())
+            .collect();
+        let doc_keys =
+            DOC_KEYS.iter().filter(|key| !existing_keys.contains(key.lookup.unwrap_or(key.label)));
+        complete_attribute_start(acc, ctx, attribute, doc_keys)
+    }
+}
+
+fn complete_cfg(acc: &mut Completions, ctx: &CompletionContext, cfg_input: ast::TokenTree) {
+    let krate = match ctx.krate {
+        Some(it) => it,
+        None => return,
+    };
+    // The attribute after the predicate of a `cfg_attr` isn't a cfg option.
+    let offset = ctx.position.offset;
+    let is_after_predicate = cfg_input
+        .syntax()
+        .children_with_tokens()
+        .any(|it| it.kind() == T![,] && it.text_range().end() <= offset);
+    if is_after_predicate {
+        return;
+    }
+    let previous = match previous_token(ctx) {
+        Some(it) => it,
+        None => return,
+    };
+    let potential_cfg = krate.potential_cfg(ctx.db);
+
+    match previous.kind() {
+        T!['('] | T![,] => {
+            for key in potential_cfg.get_cfg_keys().unique().sorted() {
+                let mut item = CompletionItem::new(
+                    CompletionKind::Attribute,
+                    ctx.source_range(),
+                    key.as_str(),
+                );
+                item.kind(CompletionItemKind::Attribute);
+                item.add_to(acc);
+            }
+        }
+        T![=] => {
+            let key = match significant_token_before(&previous) {
+                Some(it) if it.kind() == SyntaxKind::IDENT => it,
+                _ => return,
+            };
+            // Replace the whole string literal if the value is already quoted.
+            let (range, in_string) = match ctx.original_token.kind() {
+                SyntaxKind::STRING => (ctx.original_token.text_range(), true),
+                _ => (ctx.source_range(), false),
+            };
+            for value in potential_cfg.get_cfg_values(key.text()).unique().sorted() {
+                let quoted = format!("\"{}\"", value);
+                let mut item =
+                    CompletionItem::new(CompletionKind::Attribute, range, value.as_str());
+                item.kind(CompletionItemKind::Attribute).insert_text(quoted.clone());
+                if in_string {
+                    item.lookup_by(quoted);
+                }
+                item.add_to(acc);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// The token before the identifier or string at the caret, skipping whitespace.
+fn previous_token(ctx: &CompletionContext) -> Option<SyntaxToken> {
+    let token = &ctx.original_token;
+    let kind = token.kind();
+    if kind == SyntaxKind::IDENT || kind == SyntaxKind::STRING || kind.is_trivia() {
+        significant_token_before(token)
+    } else {
+        Some(token.clone())
+    }
+}
+
+fn significant_token_before(token: &SyntaxToken) -> Option<SyntaxToken> {
+    iter::successors(token.prev_token(), |it| it.prev_token()).find(|it| !it.kind().is_trivia())
+}
+
 fn complete_derive(acc: &mut Completions, ctx: &CompletionContext, derive_input: ast::TokenTree) {
     if let Ok(existing_derives) = parse_comma_sep_input(derive_input) {
         for derive_completion in DEFAULT_DERIVE_COMPLETIONS
@@ -174,7 +316,11 @@ fn complete_derive(acc: &mut Completions, ctx: &CompletionContext, derive_input:
             item.add_to(acc);
         }
 
-        for custom_derive_name in get_derive_names_in_scope(ctx).difference(&existing_derives) {
+        // Builtin derives in scope are already completed with their dependencies.
+        for custom_derive_name in get_derive_names_in_scope(ctx)
+            .difference(&existing_derives)
+            .filter(|&name| DEFAULT_DERIVE_COMPLETIONS.iter().all(|it| it.label != name))
+        {
             let mut item = CompletionItem::new(
                 CompletionKind::Attribute,
                 ctx.source_range(),
@@ -460,6 +606,123 @@ struct Test {}
         )
     }
 
+    #[test]
+    fn derive_completion_skips_builtin_derives_in_scope() {
+        check(
+            r#"
+//- /main.rs crate:main deps:core
+use core::{Clone, Copy};
+
+#[derive(Copy, $0)]
+struct Test {}
+
+//- /core.rs crate:core
+#[rustc_builtin_macro]
+pub macro Copy {}
+
+#[rustc_builtin_macro]
+pub macro Clone {}
+"#,
+            expect![[r#"
+                at Clone
+                at Debug
+                at Default
+                at Hash
+                at PartialEq
+                at PartialEq, Eq
+                at PartialEq, PartialOrd
+                at PartialEq, Eq, PartialOrd, Ord
+            "#]],
+        )
+    }
+
+    #[test]
+    fn cfg_key_completion() {
+        check(
+            r#"
+//- /main.rs cfg:test,feature=serde,feature=std
+#[cfg(all(unix, $0))]
+fn f() {}
+"#,
+            expect![[r#"
+                at feature
+                at test
+            "#]],
+        )
+    }
+
+    #[test]
+    fn cfg_feature_completion() {
+        check(
+            r#"
+//- /main.rs cfg:test,feature=serde,feature=std
+#[cfg(feature = $0)]
+fn f() {}
+"#,
+            expect![[r#"
+                at serde
+                at std
+            "#]],
+        )
+    }
+
+    #[test]
+    fn cfg_attr_feature_completion_in_string() {
+        check(
+            r#"
+//- /main.rs cfg:feature=serde,feature=std
+#[cfg_attr(feature = "s$0", derive(Debug))]
+struct S;
+"#,
+            expect![[r#"
+                at serde
+                at std
+            "#]],
+        );
+        check(
+            r#"
+//- /main.rs cfg:feature=serde,feature=std
+#[cfg_attr(feature = "std", $0)]
+struct S;
+"#,
+            expect![[r#""#]],
+        );
+    }
+
+    #[test]
+    fn doc_key_completion() {
+        check(
+            r#"
+#[doc(hidden, $0)]
+struct S;
+"#,
+            expect![[r#"
+                at alias = "…"
+                at cfg(…)
+                at inline
+                at no_inline
+            "#]],
+        );
+        check(
+            r#"
+#[doc(alias = "other", cfg(unix), $0)]
+struct S;
+"#,
+            expect![[r#"
+                at hidden
+                at inline
+                at no_inline
+            "#]],
+        );
+        check(
+            r#"
+#[doc(test($0))]
+struct S;
+"#,
+            expect![[r#""#]],
+        );
+    }
+
     #[test]
     fn test_attribute_completion() {
         check(
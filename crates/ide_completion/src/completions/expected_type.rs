@@ -0,0 +1,266 @@
+//! Completes values of the expected type, like `Foo::new()` or `local.field`
+//! when a `Foo` is expected.
+//!
+//! Locals and enum variants of the expected type are completed along with
+//! everything else in scope, and ranked by `CompletionRelevance`.
+
+use hir::{HasVisibility, HirDisplay, ModuleDef, Mutability, Type};
+use ide_db::{helpers::FamousDefs, SymbolKind};
+
+use crate::{
+    item::{Builder, CompletionItem, CompletionKind, CompletionRelevance},
+    CompletionContext, Completions,
+};
+
+pub(crate) fn complete_expected_type(acc: &mut Completions, ctx: &CompletionContext) {
+    if !ctx.is_trivial_path || !ctx.is_expr || ctx.is_call {
+        return;
+    }
+    let expected = match &ctx.expected_type {
+        Some(ty) if !ty.is_unknown() && !ty.is_unit() => ty,
+        _ => return,
+    };
+    let module = match ctx.scope.module() {
+        Some(it) => it,
+        None => return,
+    };
+    // For `&T`, values of `T` are offered too, with a reference added.
+    let ty = expected.remove_ref().unwrap_or_else(|| expected.clone());
+
+    complete_local_fields(acc, ctx, module, expected, &ty);
+    complete_constructors(acc, ctx, module, expected, &ty);
+    complete_default(acc, ctx, module, expected, &ty);
+}
+
+/// Fields of locals, like `config.path`.
+fn complete_local_fields(
+    acc: &mut Completions,
+    ctx: &CompletionContext,
+    module: hir::Module,
+    expected: &Type,
+    ty: &Type,
+) {
+    for (name, local) in &ctx.locals {
+        let local_ty = match local.ty(ctx.db).autoderef(ctx.db).last() {
+            Some(it) => it,
+            None => continue,
+        };
+        for (field, field_ty) in local_ty.fields(ctx.db) {
+            if !field.is_visible_from(ctx.db, module) || (&field_ty != ty && &field_ty != expected)
+            {
+                continue;
+            }
+            let label = format!("{}.{}", name, field.name(ctx.db));
+            let mut item = CompletionItem::new(CompletionKind::Magic, ctx.source_range(), label);
+            item.kind(SymbolKind::Field).detail(field_ty.display(ctx.db).to_string());
+            set_type_match(&mut item, expected, &field_ty);
+            item.add_to(acc);
+        }
+    }
+}
+
+/// Associated functions without a `self` parameter returning the expected
+/// type, like `Foo::new()`.
+fn complete_constructors(
+    acc: &mut Completions,
+    ctx: &CompletionContext,
+    module: hir::Module,
+    expected: &Type,
+    ty: &Type,
+) -> Option<()> {
+    let adt = ty.as_adt()?;
+    let adt_path = module.find_use_path(ctx.db, ModuleDef::Adt(adt))?;
+    ty.clone().iterate_assoc_items(ctx.db, ctx.krate?, |item| {
+        let func = match item {
+            hir::AssocItem::Function(it) => it,
+            _ => return None::<()>,
+        };
+        let ret_ty = func.ret_type(ctx.db);
+        if func.self_param(ctx.db).is_some()
+            || ret_ty.as_adt() != Some(adt)
+            || !func.is_visible_from(ctx.db, module)
+        {
+            return None;
+        }
+        let path = format!("{}::{}", adt_path, func.name(ctx.db));
+        let has_params = !func.assoc_fn_params(ctx.db).is_empty();
+        let label = if has_params { format!("{}(…)", path) } else { format!("{}()", path) };
+        let mut item = CompletionItem::new(CompletionKind::Magic, ctx.source_range(), label);
+        item.kind(SymbolKind::Function)
+            .lookup_by(path.clone())
+            .detail(format!("-> {}", ret_ty.display(ctx.db)));
+        match ctx.config.snippet_cap {
+            Some(cap) if has_params => {
+                item.insert_snippet(cap, format!("{}($0)", path)).trigger_call_info();
+            }
+            Some(cap) => {
+                item.insert_snippet(cap, format!("{}()$0", path));
+            }
+            None => {
+                item.insert_text(format!("{}()", path));
+            }
+        }
+        set_type_match(&mut item, expected, &ret_ty);
+        item.add_to(acc);
+        None
+    });
+    Some(())
+}
+
+/// `Default::default()`, if the expected type implements `Default`.
+fn complete_default(
+    acc: &mut Completions,
+    ctx: &CompletionContext,
+    module: hir::Module,
+    expected: &Type,
+    ty: &Type,
+) -> Option<()> {
+    let default_trait = FamousDefs(&ctx.sema, ctx.krate).core_default_Default()?;
+    if !ty.impls_trait(ctx.db, default_trait, &[]) {
+        return None;
+    }
+    let path = module.find_use_path(ctx.db, ModuleDef::Trait(default_trait))?;
+    let label = format!("{}::default()", path);
+    let mut item = CompletionItem::new(CompletionKind::Magic, ctx.source_range(), label);
+    item.kind(SymbolKind::Function)
+        .lookup_by(format!("{}::default", path))
+        .detail(format!("-> {}", ty.display(ctx.db)));
+    set_type_match(&mut item, expected, ty);
+    item.add_to(acc);
+    Some(())
+}
+
+fn set_type_match(item: &mut Builder, expected: &Type, ty: &Type) {
+    if ty == expected {
+        item.set_relevance(CompletionRelevance {
+            exact_type_match: true,
+            ..CompletionRelevance::default()
+        });
+    } else {
+        let mutability =
+            if expected.is_mutable_reference() { Mutability::Mut } else { Mutability::Shared };
+        item.ref_match(mutability);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{expect, Expect};
+    use ide_db::helpers::FamousDefs;
+
+    use crate::{
+        test_utils::{self, completion_list},
+        CompletionKind,
+    };
+
+    fn check(ra_fixture: &str, expect: Expect) {
+        let actual = completion_list(
+            &format!("//- /main.rs crate:main deps:core{}{}", ra_fixture, FamousDefs::FIXTURE),
+            CompletionKind::Magic,
+        );
+        expect.assert_eq(&actual);
+    }
+
+    fn check_edit(what: &str, ra_fixture_before: &str, ra_fixture_after: &str) {
+        test_utils::check_edit(
+            what,
+            &format!(
+                "//- /main.rs crate:main deps:core{}{}",
+                ra_fixture_before,
+                FamousDefs::FIXTURE
+            ),
+            ra_fixture_after,
+        );
+    }
+
+    #[test]
+    fn completes_constructors_fields_and_default() {
+        check(
+            r#"
+struct Config { verbose: bool }
+impl Config {
+    fn new() -> Self { Config { verbose: false } }
+    fn with_verbose(verbose: bool) -> Config { Config { verbose } }
+    fn verbose(&self) -> bool { self.verbose }
+}
+impl core::default::Default for Config {
+    fn default() -> Self { Config::new() }
+}
+struct App { config: Config, name: u32 }
+fn run(config: Config) {}
+fn main() {
+    let app = App { config: Config::new(), name: 0 };
+    run($0);
+}
+"#,
+            expect![[r#"
+                fd app.config       Config
+                fn Config::new()    -> Config
+                fn Config::with_verbose(…) -> Config
+                fn Default::default() -> Config
+            "#]],
+        );
+    }
+
+    #[test]
+    fn completes_values_for_references() {
+        check(
+            r#"
+struct Point { x: u32 }
+impl Point {
+    fn origin() -> Point { Point { x: 0 } }
+}
+struct Line { start: Point, end: Point }
+fn draw(line: &Line) {
+    let p: &Point = $0;
+}
+"#,
+            expect![[r#"
+                fd line.start      Point
+                fd line.end        Point
+                fn Point::origin() -> Point
+            "#]],
+        );
+    }
+
+    #[test]
+    fn inserts_constructor_call() {
+        check_edit(
+            "Point::new",
+            r#"
+struct Point { x: u32 }
+impl Point {
+    fn new(x: u32) -> Point { Point { x } }
+}
+fn main() {
+    let p: Point = $0;
+}
+"#,
+            r#"
+struct Point { x: u32 }
+impl Point {
+    fn new(x: u32) -> Point { Point { x } }
+}
+fn main() {
+    let p: Point = Point::new($0);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn no_completions_without_expected_type() {
+        check(
+            r#"
+struct Point { x: u32 }
+impl Point {
+    fn new(x: u32) -> Point { Point { x } }
+}
+fn main() {
+    let p = $0;
+}
+"#,
+            expect![[""]],
+        );
+    }
+}
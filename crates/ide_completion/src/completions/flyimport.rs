@@ -21,6 +21,9 @@
 //! ```
 //!
 //! Also completes associated items, that require trait imports.
+//! After a dot, the methods of the traits implemented by the receiver are proposed,
+//! also when they are reached through `Deref` or auto-referencing the receiver.
+//! The completions which need an import are ranked below the ones already in scope.
 //! If any unresolved and/or partially-qualified path predeces the input, it will be taken into account.
 //! Currently, only the imports with their import path ending with the whole qialifier will be proposed
 //! (no fuzzy matching for qualifier).
@@ -225,7 +228,9 @@ mod tests {
 
     use crate::{
         item::CompletionKind,
-        test_utils::{check_edit, check_edit_with_config, completion_list, TEST_CONFIG},
+        test_utils::{
+            check_edit, check_edit_with_config, completion_list, get_all_items, test_config,
+        },
     };
 
     fn check(ra_fixture: &str, expect: Expect) {
@@ -513,6 +518,96 @@ fn main() {
         );
     }
 
+    #[test]
+    fn trait_method_fuzzy_completion_through_deref_and_autoref() {
+        check_edit(
+            "read_all",
+            r#"
+//- /lib.rs crate:dep
+pub mod io {
+    pub trait Read {
+        fn read_all(&mut self) -> usize;
+    }
+    pub struct File;
+    impl Read for File {
+        fn read_all(&mut self) -> usize { 0 }
+    }
+}
+
+//- /main.rs crate:main deps:dep
+#[lang = "deref"]
+pub trait Deref {
+    type Target;
+    fn deref(&self) -> &Self::Target;
+}
+
+struct Buffered(dep::io::File);
+impl Deref for Buffered {
+    type Target = dep::io::File;
+    fn deref(&self) -> &dep::io::File { &self.0 }
+}
+
+fn main() {
+    let mut file = Buffered(dep::io::File);
+    file.rea$0
+}
+"#,
+            r#"
+use dep::io::Read;
+
+#[lang = "deref"]
+pub trait Deref {
+    type Target;
+    fn deref(&self) -> &Self::Target;
+}
+
+struct Buffered(dep::io::File);
+impl Deref for Buffered {
+    type Target = dep::io::File;
+    fn deref(&self) -> &dep::io::File { &self.0 }
+}
+
+fn main() {
+    let mut file = Buffered(dep::io::File);
+    file.read_all()$0
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn trait_methods_requiring_import_are_ranked_lower() {
+        let items = get_all_items(
+            test_config(),
+            r#"
+//- /lib.rs crate:dep
+pub mod test_mod {
+    pub trait TestTrait {
+        fn random_method(&self);
+    }
+    pub struct TestStruct {}
+    impl TestStruct {
+        pub fn rapid_method(&self) {}
+    }
+    impl TestTrait for TestStruct {
+        fn random_method(&self) {}
+    }
+}
+
+//- /main.rs crate:main deps:dep
+fn main() {
+    let test_struct = dep::test_mod::TestStruct {};
+    test_struct.ra$0
+}
+"#,
+        );
+        let relevance =
+            |name: &str| items.iter().find(|it| it.label().starts_with(name)).unwrap().relevance();
+        assert!(relevance("random_method").requires_import);
+        assert!(!relevance("rapid_method").requires_import);
+        assert!(relevance("random_method").score() < relevance("rapid_method").score());
+    }
+
     #[test]
     fn no_trait_type_fuzzy_completion() {
         check(
@@ -733,7 +828,7 @@ use crate::foo::bar;
 fn main() {
     Ite$0
 }"#;
-        let mut config = TEST_CONFIG;
+        let mut config = test_config();
 
         config.insert_use.prefix_kind = hir::PrefixKind::ByCrate;
         check_edit_with_config(
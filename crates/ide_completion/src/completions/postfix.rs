@@ -256,6 +256,8 @@ pub(crate) fn complete_postfix(acc: &mut Completions, ctx: &CompletionContext) {
         }
     }
 
+    add_custom_postfix_completions(acc, ctx, cap, &dot_receiver, &receiver_ty, &receiver_text);
+
     if let ast::Expr::Literal(literal) = dot_receiver.clone() {
         if let Some(literal_text) = ast::String::cast(literal.token()) {
             add_format_like_completions(acc, ctx, &dot_receiver, cap, &literal_text);
@@ -283,6 +285,37 @@ fn include_references(initial_element: &ast::Expr) -> ast::Expr {
     resulting_element
 }
 
+fn add_custom_postfix_completions(
+    acc: &mut Completions,
+    ctx: &CompletionContext,
+    cap: SnippetCap,
+    receiver: &ast::Expr,
+    receiver_ty: &hir::Type,
+    receiver_text: &str,
+) {
+    for snippet in ctx.config.snippets.iter().filter(|it| !it.postfix_triggers.is_empty()) {
+        if !snippet.is_applicable_to(ctx, receiver_ty) {
+            continue;
+        }
+        let mut edit = postfix_edit(ctx, receiver, &snippet.postfix_snippet(receiver_text));
+        let import_edit = match snippet.import_edit(ctx) {
+            Some(it) => it,
+            None => continue,
+        };
+        if edit.union(import_edit).is_err() {
+            continue;
+        }
+        for trigger in snippet.postfix_triggers.iter() {
+            let mut item =
+                CompletionItem::new(CompletionKind::Postfix, ctx.source_range(), trigger);
+            item.set_detail(snippet.description.clone())
+                .kind(CompletionItemKind::Snippet)
+                .snippet_edit(cap, edit.clone());
+            item.add_to(acc);
+        }
+    }
+}
+
 fn postfix_snippet(
     ctx: &CompletionContext,
     cap: SnippetCap,
@@ -291,24 +324,29 @@ fn postfix_snippet(
     detail: &str,
     snippet: &str,
 ) -> Builder {
-    let edit = {
-        let receiver_syntax = receiver.syntax();
-        let receiver_range = ctx.sema.original_range(receiver_syntax).range;
-        let delete_range = TextRange::new(receiver_range.start(), ctx.source_range().end());
-        TextEdit::replace(delete_range, snippet.to_string())
-    };
+    let edit = postfix_edit(ctx, receiver, snippet);
     let mut item = CompletionItem::new(CompletionKind::Postfix, ctx.source_range(), label);
     item.detail(detail).kind(CompletionItemKind::Snippet).snippet_edit(cap, edit);
     item
 }
 
+/// Replaces the receiver and everything typed after it with `snippet`.
+fn postfix_edit(ctx: &CompletionContext, receiver: &ast::Expr, snippet: &str) -> TextEdit {
+    let receiver_range = ctx.sema.original_range(receiver.syntax()).range;
+    let delete_range = TextRange::new(receiver_range.start(), ctx.source_range().end());
+    TextEdit::replace(delete_range, snippet.to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use expect_test::{expect, Expect};
 
     use crate::{
-        test_utils::{check_edit, completion_list},
-        CompletionKind,
+        test_utils::{
+            check_edit, check_edit_with_config, completion_list, completion_list_with_config,
+            test_config,
+        },
+        CompletionConfig, CompletionKind, Snippet, SnippetScope,
     };
 
     fn check(ra_fixture: &str, expect: Expect) {
@@ -575,4 +613,71 @@ fn main() {
             r#"fn main() { log::error!("{}", 2+2) }"#,
         );
     }
+
+    fn custom_snippet(
+        trigger: &str,
+        body: &str,
+        requires: &[&str],
+        receiver_implements: Option<&str>,
+    ) -> CompletionConfig {
+        let snippet = Snippet::new(
+            &[],
+            &[trigger.to_string()],
+            &[body.to_string()],
+            "",
+            &requires.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            receiver_implements,
+            SnippetScope::Expr,
+        );
+        CompletionConfig { snippets: vec![snippet.unwrap()], ..test_config() }
+    }
+
+    #[test]
+    fn custom_postfix_completion_adds_imports() {
+        check_edit_with_config(
+            custom_snippet("arc", "Arc::new(${receiver})", &["std::sync::Arc"], None),
+            "arc",
+            r#"
+//- /main.rs crate:main deps:std
+fn main() {
+    let n = 92;
+    let x = n.$0
+}
+//- /std.rs crate:std
+pub mod sync {
+    pub struct Arc<T>(T);
+}
+"#,
+            r#"
+use std::sync::Arc;
+
+fn main() {
+    let n = 92;
+    let x = Arc::new(n)
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn custom_postfix_completion_checks_receiver_trait() {
+        let config = custom_snippet("greet", "${receiver}.greet()", &[], Some("Greet"));
+        let fixture = |receiver: &str| {
+            format!(
+                r#"
+trait Greet {{ fn greet(&self); }}
+struct S;
+struct T;
+impl Greet for S {{ fn greet(&self) {{}} }}
+fn main() {{ {}.$0 }}
+"#,
+                receiver
+            )
+        };
+        let actual =
+            completion_list_with_config(config.clone(), &fixture("S"), CompletionKind::Postfix);
+        assert!(actual.contains("sn greet"));
+        let actual = completion_list_with_config(config, &fixture("T"), CompletionKind::Postfix);
+        assert!(!actual.contains("sn greet"));
+    }
 }
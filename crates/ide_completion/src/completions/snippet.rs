@@ -1,10 +1,11 @@
 //! This file provides snippet completions, like `pd` => `eprintln!(...)`.
 
 use ide_db::helpers::SnippetCap;
+use text_edit::TextEdit;
 
 use crate::{
     item::Builder, CompletionContext, CompletionItem, CompletionItemKind, CompletionKind,
-    Completions,
+    Completions, SnippetScope,
 };
 
 fn snippet(ctx: &CompletionContext, cap: SnippetCap, label: &str, snippet: &str) -> Builder {
@@ -24,6 +25,7 @@ pub(crate) fn complete_expr_snippet(acc: &mut Completions, ctx: &CompletionConte
 
     snippet(ctx, cap, "pd", "eprintln!(\"$0 = {:?}\", $0);").add_to(acc);
     snippet(ctx, cap, "ppd", "eprintln!(\"$0 = {:#?}\", $0);").add_to(acc);
+    add_custom_completions(acc, ctx, cap, SnippetScope::Expr);
 }
 
 pub(crate) fn complete_item_snippet(acc: &mut Completions, ctx: &CompletionContext) {
@@ -68,13 +70,45 @@ fn ${1:feature}() {
 
     let item = snippet(ctx, cap, "macro_rules", "macro_rules! $1 {\n\t($2) => {\n\t\t$0\n\t};\n}");
     item.add_to(acc);
+
+    add_custom_completions(acc, ctx, cap, SnippetScope::Item);
+}
+
+/// Snippets from the `rust-analyzer.completion.snippets` setting.
+fn add_custom_completions(
+    acc: &mut Completions,
+    ctx: &CompletionContext,
+    cap: SnippetCap,
+    scope: SnippetScope,
+) {
+    for snippet in ctx.config.snippets.iter().filter(|it| it.scope == scope) {
+        let mut edit = TextEdit::replace(ctx.source_range(), snippet.snippet().to_string());
+        let import_edit = match snippet.import_edit(ctx) {
+            Some(it) => it,
+            None => continue,
+        };
+        if edit.union(import_edit).is_err() {
+            continue;
+        }
+        for trigger in snippet.prefix_triggers.iter() {
+            let mut item =
+                CompletionItem::new(CompletionKind::Snippet, ctx.source_range(), trigger);
+            item.set_detail(snippet.description.clone())
+                .kind(CompletionItemKind::Snippet)
+                .snippet_edit(cap, edit.clone());
+            item.add_to(acc);
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use expect_test::{expect, Expect};
 
-    use crate::{test_utils::completion_list, CompletionKind};
+    use crate::{
+        test_utils::{check_edit_with_config, completion_list, test_config},
+        CompletionConfig, CompletionKind, Snippet, SnippetScope,
+    };
 
     fn check(ra_fixture: &str, expect: Expect) {
         let actual = completion_list(ra_fixture, CompletionKind::Snippet);
@@ -114,4 +148,38 @@ mod tests {
             "#]],
         )
     }
+
+    #[test]
+    fn completes_custom_snippets_with_imports() {
+        let snippet = Snippet::new(
+            &["hashmap".to_string()],
+            &[],
+            &["HashMap::new()".to_string()],
+            "Create a new `HashMap`",
+            &["std::collections::HashMap".to_string()],
+            None,
+            SnippetScope::Expr,
+        );
+        check_edit_with_config(
+            CompletionConfig { snippets: vec![snippet.unwrap()], ..test_config() },
+            "hashmap",
+            r#"
+//- /main.rs crate:main deps:std
+fn main() {
+    let map = $0;
+}
+//- /std.rs crate:std
+pub mod collections {
+    pub struct HashMap;
+}
+"#,
+            r#"
+use std::collections::HashMap;
+
+fn main() {
+    let map = HashMap::new();
+}
+"#,
+        );
+    }
 }
@@ -45,12 +45,12 @@ mod tests {
     use expect_test::{expect, Expect};
 
     use crate::{
-        test_utils::{check_edit, completion_list_with_config, TEST_CONFIG},
+        test_utils::{check_edit, completion_list_with_config, test_config},
         CompletionConfig, CompletionKind,
     };
 
     fn check(ra_fixture: &str, expect: Expect) {
-        check_with_config(TEST_CONFIG, ra_fixture, expect);
+        check_with_config(test_config(), ra_fixture, expect);
     }
 
     fn check_with_config(config: CompletionConfig, ra_fixture: &str, expect: Expect) {
@@ -6,6 +6,8 @@
 
 use ide_db::helpers::{insert_use::InsertUseConfig, SnippetCap};
 
+use crate::snippet::Snippet;
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct CompletionConfig {
     pub enable_postfix_completions: bool,
@@ -14,4 +16,5 @@ pub struct CompletionConfig {
     pub add_call_argument_snippets: bool,
     pub snippet_cap: Option<SnippetCap>,
     pub insert_use: InsertUseConfig,
+    pub snippets: Vec<Snippet>,
 }
@@ -159,6 +159,15 @@ pub struct CompletionRelevance {
     /// }
     /// ```
     pub is_local: bool,
+    /// This is set for items which need an import to be added, like the
+    /// methods of traits which aren't in scope yet:
+    ///
+    /// ```
+    /// fn f(file: std::fs::File) {
+    ///     file.read_to$0 // needs `use std::io::Read;`
+    /// }
+    /// ```
+    pub requires_import: bool,
 }
 
 impl CompletionRelevance {
@@ -183,6 +192,10 @@ impl CompletionRelevance {
         if self.is_local {
             score += 1;
         }
+        // Rank the items which are already in scope higher.
+        if !self.requires_import {
+            score += 1;
+        }
 
         score
     }
@@ -191,7 +204,7 @@ impl CompletionRelevance {
     /// some threshold such that we think it is especially likely
     /// to be relevant.
     pub fn is_relevant(&self) -> bool {
-        self.score() > 0
+        self.score() > CompletionRelevance::default().score()
     }
 }
 
@@ -403,6 +416,8 @@ impl Builder {
         let mut label = self.label;
         let mut lookup = self.lookup;
         let mut insert_text = self.insert_text;
+        let mut relevance = self.relevance;
+        relevance.requires_import = self.import_to_add.is_some();
 
         if let Some(original_path) = self
             .import_to_add
@@ -439,7 +454,7 @@ impl Builder {
             completion_kind: self.completion_kind,
             deprecated: self.deprecated,
             trigger_call_info: self.trigger_call_info.unwrap_or(false),
-            relevance: self.relevance,
+            relevance,
             ref_match: self.ref_match,
             import_to_add: self.import_to_add,
         }
@@ -566,6 +581,7 @@ mod tests {
         // This test asserts that the relevance score for these items is ascending, and
         // that any items in the same vec have the same score.
         let expected_relevance_order = vec![
+            vec![CompletionRelevance { requires_import: true, ..CompletionRelevance::default() }],
             vec![CompletionRelevance::default()],
             vec![
                 CompletionRelevance { exact_name_match: true, ..CompletionRelevance::default() },
@@ -586,6 +602,7 @@ mod tests {
                 exact_name_match: true,
                 exact_type_match: true,
                 is_local: true,
+                requires_import: false,
             }],
         ];
 
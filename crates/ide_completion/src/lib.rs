@@ -10,6 +10,7 @@ mod test_utils;
 mod render;
 
 mod completions;
+mod snippet;
 
 use completions::flyimport::position_for_import;
 use ide_db::{
@@ -24,6 +25,7 @@ use crate::{completions::Completions, context::CompletionContext, item::Completi
 pub use crate::{
     config::CompletionConfig,
     item::{CompletionItem, CompletionItemKind, CompletionRelevance, ImportEdit, InsertTextFormat},
+    snippet::{Snippet, SnippetScope},
 };
 
 //FIXME: split the following feature into fine-grained features.
@@ -41,6 +43,11 @@ pub use crate::{
 // When completing a function call, `()` are automatically inserted. If a function
 // takes arguments, the cursor is positioned inside the parenthesis.
 //
+// When a value of a known type is expected, like a function argument or the
+// initializer of a `let` with a type annotation, values of that type are
+// offered and ranked first: constructors like `Foo::new()`, fields of locals
+// like `app.config`, and `Default::default()` if the type implements it.
+//
 // There are postfix completions, which can be triggered by typing something like
 // `foo().if`. The word after `.` determines postfix completion. Possible variants are:
 //
@@ -75,6 +82,9 @@ pub use crate::{
 // }
 // ```
 //
+// More postfix and prefix snippets can be defined with the
+// `rust-analyzer.completion.snippets` setting.
+//
 // And the auto import completions, enabled with the `rust-analyzer.completion.autoimport.enable` setting and the corresponding LSP client capabilities.
 // Those are the additional completion options with automatic `use` import and options from all project importable items,
 // fuzzy matched agains the completion imput.
@@ -123,6 +133,7 @@ pub fn completions(
     completions::qualified_path::complete_qualified_path(&mut acc, &ctx);
     completions::unqualified_path::complete_unqualified_path(&mut acc, &ctx);
     completions::dot::complete_dot(&mut acc, &ctx);
+    completions::expected_type::complete_expected_type(&mut acc, &ctx);
     completions::record::complete_record(&mut acc, &ctx);
     completions::pattern::complete_pattern(&mut acc, &ctx);
     completions::postfix::complete_postfix(&mut acc, &ctx);
@@ -166,7 +177,7 @@ pub fn resolve_completion_edits(
 
 #[cfg(test)]
 mod tests {
-    use crate::test_utils::{self, TEST_CONFIG};
+    use crate::test_utils::{self, test_config};
 
     struct DetailAndDocumentation<'a> {
         detail: &'a str,
@@ -175,7 +186,7 @@ mod tests {
 
     fn check_detail_and_documentation(ra_fixture: &str, expected: DetailAndDocumentation) {
         let (db, position) = test_utils::position(ra_fixture);
-        let config = TEST_CONFIG;
+        let config = test_config();
         let completions: Vec<_> = crate::completions(&db, &config, position).unwrap().into();
         for item in completions {
             if item.detail() == Some(expected.detail) {
@@ -190,7 +201,7 @@ mod tests {
 
     fn check_no_completion(ra_fixture: &str) {
         let (db, position) = test_utils::position(ra_fixture);
-        let config = TEST_CONFIG;
+        let config = test_config();
 
         let completions: Option<Vec<String>> = crate::completions(&db, &config, position)
             .and_then(|completions| {
@@ -332,7 +332,7 @@ mod tests {
     use itertools::Itertools;
 
     use crate::{
-        test_utils::{check_edit, do_completion, get_all_items, TEST_CONFIG},
+        test_utils::{check_edit, do_completion, get_all_items, test_config},
         CompletionKind, CompletionRelevance,
     };
 
@@ -355,7 +355,7 @@ mod tests {
             format!("[{}]", relevance_factors)
         }
 
-        let actual = get_all_items(TEST_CONFIG, ra_fixture)
+        let actual = get_all_items(test_config(), ra_fixture)
             .into_iter()
             .filter(|it| it.completion_kind == CompletionKind::Reference)
             .flat_map(|it| {
@@ -1046,6 +1046,7 @@ fn main() {
                             exact_name_match: true,
                             exact_type_match: false,
                             is_local: true,
+                            requires_import: false,
                         },
                         ref_match: "&mut ",
                     },
@@ -134,7 +134,7 @@ impl<'a> FunctionRender<'a> {
 #[cfg(test)]
 mod tests {
     use crate::{
-        test_utils::{check_edit, check_edit_with_config, TEST_CONFIG},
+        test_utils::{check_edit, check_edit_with_config, test_config},
         CompletionConfig,
     };
 
@@ -232,7 +232,7 @@ fn main() { S::foo(${1:&self})$0 }
     fn suppress_arg_snippets() {
         cov_mark::check!(suppress_arg_snippets);
         check_edit_with_config(
-            CompletionConfig { add_call_argument_snippets: false, ..TEST_CONFIG },
+            CompletionConfig { add_call_argument_snippets: false, ..test_config() },
             "with_args",
             r#"
 fn with_args(x: i32, y: String) {}
@@ -0,0 +1,171 @@
+//! User defined snippets, configured with `rust-analyzer.completion.snippets`.
+//!
+//! A snippet is completed by its prefix triggers like a keyword, and by its
+//! postfix triggers after a `.`, with the receiver substituted for
+//! `${receiver}`. The paths it requires are imported when it is completed,
+//! and a postfix snippet can be limited to receivers implementing a trait.
+
+use hir::{ItemInNs, ModuleDef, PathKind, PathResolution};
+use ide_db::helpers::{
+    insert_use::{insert_use, ImportScope},
+    mod_path_to_ast,
+};
+use itertools::Itertools;
+use syntax::{algo, ast};
+use text_edit::TextEdit;
+
+use crate::context::CompletionContext;
+
+/// Where a snippet can be completed by its prefix triggers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SnippetScope {
+    Item,
+    Expr,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Snippet {
+    pub prefix_triggers: Box<[String]>,
+    pub postfix_triggers: Box<[String]>,
+    pub description: Option<String>,
+    pub scope: SnippetScope,
+    snippet: String,
+    requires: Box<[String]>,
+    receiver_implements: Option<String>,
+}
+
+impl Snippet {
+    /// Returns `None` if the snippet can't be triggered anywhere, or if one of
+    /// the paths is malformed.
+    pub fn new(
+        prefix_triggers: &[String],
+        postfix_triggers: &[String],
+        snippet: &[String],
+        description: &str,
+        requires: &[String],
+        receiver_implements: Option<&str>,
+        scope: SnippetScope,
+    ) -> Option<Snippet> {
+        if prefix_triggers.is_empty() && postfix_triggers.is_empty() {
+            return None;
+        }
+        // The receiver is an expression, so the whole snippet has to be one.
+        if !postfix_triggers.is_empty() && scope != SnippetScope::Expr {
+            return None;
+        }
+        if receiver_implements.is_some() && postfix_triggers.is_empty() {
+            return None;
+        }
+        let mut paths = requires.iter().map(String::as_str).chain(receiver_implements);
+        if paths.any(|path| ast::Path::parse(path).is_err()) {
+            return None;
+        }
+
+        Some(Snippet {
+            prefix_triggers: prefix_triggers.into(),
+            postfix_triggers: postfix_triggers.into(),
+            description: Some(description.to_string()).filter(|it| !it.is_empty()),
+            scope,
+            snippet: snippet.iter().join("\n"),
+            requires: requires.into(),
+            receiver_implements: receiver_implements.map(ToString::to_string),
+        })
+    }
+
+    pub(crate) fn snippet(&self) -> &str {
+        &self.snippet
+    }
+
+    pub(crate) fn postfix_snippet(&self, receiver: &str) -> String {
+        self.snippet.replace("${receiver}", receiver)
+    }
+
+    /// Whether the snippet can be completed after a receiver of type `ty`.
+    pub(crate) fn is_applicable_to(&self, ctx: &CompletionContext, ty: &hir::Type) -> bool {
+        let path = match &self.receiver_implements {
+            Some(it) => it,
+            None => return true,
+        };
+        match resolve(ctx, path) {
+            Some(PathResolution::Def(ModuleDef::Trait(trait_))) => {
+                ty.impls_trait(ctx.db, trait_, &[])
+            }
+            _ => false,
+        }
+    }
+
+    /// The edit adding the `use` items for the required paths which aren't in
+    /// scope yet. Returns `None` if one of the paths can't be resolved.
+    pub(crate) fn import_edit(&self, ctx: &CompletionContext) -> Option<TextEdit> {
+        let mut res = TextEdit::builder();
+        if self.requires.is_empty() {
+            return Some(res.finish());
+        }
+        let module = ctx.scope.module()?;
+        let original_scope =
+            ImportScope::find_insert_use_container(&ctx.original_token.parent(), &ctx.sema)?;
+        // Each import is inserted into the result of the previous one, so that
+        // imports merged into the same `use` item don't conflict.
+        let mut scope = original_scope.clone();
+        for path in self.requires.iter() {
+            let item = match resolve(ctx, path)? {
+                PathResolution::Def(def) => ItemInNs::from(def),
+                PathResolution::Macro(mac) => ItemInNs::from(mac),
+                _ => return None,
+            };
+            let import_path = module.find_use_path(ctx.db, item)?;
+            if import_path.kind == PathKind::Plain && import_path.segments().len() == 1 {
+                continue;
+            }
+            let rewriter = insert_use(&scope, mod_path_to_ast(&import_path), ctx.config.insert_use);
+            scope = ImportScope::from(rewriter.rewrite(scope.as_syntax_node()))?;
+        }
+        algo::diff(original_scope.as_syntax_node(), scope.as_syntax_node())
+            .into_text_edit(&mut res);
+        Some(res.finish())
+    }
+}
+
+fn resolve(ctx: &CompletionContext, path: &str) -> Option<PathResolution> {
+    let path = ast::Path::parse(path).ok()?;
+    ctx.scope.speculative_resolve(&path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(items: &[&str]) -> Vec<String> {
+        items.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn rejects_invalid_snippets() {
+        let body = strings(&["Box::pin(${receiver})"]);
+        let new = |prefix: &[&str], postfix: &[&str], requires: &[&str], scope| {
+            Snippet::new(
+                &strings(prefix),
+                &strings(postfix),
+                &body,
+                "",
+                &strings(requires),
+                None,
+                scope,
+            )
+        };
+        assert!(new(&["pin"], &["pin"], &["std::boxed::Box"], SnippetScope::Expr).is_some());
+        assert!(new(&[], &[], &[], SnippetScope::Expr).is_none());
+        assert!(new(&[], &["pin"], &[], SnippetScope::Item).is_none());
+        assert!(new(&["pin"], &[], &["std::boxed::"], SnippetScope::Expr).is_none());
+        assert!(Snippet::new(
+            &strings(&["pin"]),
+            &[],
+            &body,
+            "",
+            &[],
+            Some("core::future::Future"),
+            SnippetScope::Expr,
+        )
+        .is_none());
+    }
+}
@@ -16,18 +16,21 @@ use test_utils::{assert_eq_text, RangeOrOffset};
 
 use crate::{item::CompletionKind, CompletionConfig, CompletionItem};
 
-pub(crate) const TEST_CONFIG: CompletionConfig = CompletionConfig {
-    enable_postfix_completions: true,
-    enable_imports_on_the_fly: true,
-    add_call_parenthesis: true,
-    add_call_argument_snippets: true,
-    snippet_cap: SnippetCap::new(true),
-    insert_use: InsertUseConfig {
-        merge: Some(MergeBehavior::Full),
-        prefix_kind: PrefixKind::Plain,
-        group: true,
-    },
-};
+pub(crate) fn test_config() -> CompletionConfig {
+    CompletionConfig {
+        enable_postfix_completions: true,
+        enable_imports_on_the_fly: true,
+        add_call_parenthesis: true,
+        add_call_argument_snippets: true,
+        snippet_cap: SnippetCap::new(true),
+        insert_use: InsertUseConfig {
+            merge: Some(MergeBehavior::Full),
+            prefix_kind: PrefixKind::Plain,
+            group: true,
+        },
+        snippets: Vec::new(),
+    }
+}
 
 /// Creates analysis from a multi-file fixture, returns positions marked with $0.
 pub(crate) fn position(ra_fixture: &str) -> (RootDatabase, FilePosition) {
@@ -43,7 +46,7 @@ pub(crate) fn position(ra_fixture: &str) -> (RootDatabase, FilePosition) {
 }
 
 pub(crate) fn do_completion(code: &str, kind: CompletionKind) -> Vec<CompletionItem> {
-    do_completion_with_config(TEST_CONFIG, code, kind)
+    do_completion_with_config(test_config(), code, kind)
 }
 
 pub(crate) fn do_completion_with_config(
@@ -58,7 +61,7 @@ pub(crate) fn do_completion_with_config(
 }
 
 pub(crate) fn completion_list(code: &str, kind: CompletionKind) -> String {
-    completion_list_with_config(TEST_CONFIG, code, kind)
+    completion_list_with_config(test_config(), code, kind)
 }
 
 pub(crate) fn completion_list_with_config(
@@ -98,7 +101,7 @@ fn monospace_width(s: &str) -> usize {
 }
 
 pub(crate) fn check_edit(what: &str, ra_fixture_before: &str, ra_fixture_after: &str) {
-    check_edit_with_config(TEST_CONFIG, what, ra_fixture_before, ra_fixture_after)
+    check_edit_with_config(test_config(), what, ra_fixture_before, ra_fixture_after)
 }
 
 pub(crate) fn check_edit_with_config(
@@ -117,6 +117,13 @@ pub mod option {
     }
 }
 
+pub mod result {
+    pub enum Result<T, E> {
+        Ok(T),
+        Err(E),
+    }
+}
+
 pub mod prelude {
     pub use crate::{
         cmp::Ord,
@@ -22,18 +22,19 @@
 
 use std::{
     cmp::Ordering,
+    collections::BinaryHeap,
     fmt,
     hash::{Hash, Hasher},
-    mem,
+    iter, mem,
     sync::Arc,
 };
 
 use base_db::{
     salsa::{self, ParallelDatabase},
-    CrateId, FileId, SourceDatabaseExt, SourceRootId,
+    CrateId, FileId, SourceDatabase, SourceDatabaseExt, SourceRootId,
 };
 use fst::{self, Streamer};
-use hir::db::DefDatabase;
+use hir::{db::DefDatabase, Semantics};
 use rayon::prelude::*;
 use rustc_hash::{FxHashMap, FxHashSet};
 use syntax::{
@@ -49,6 +50,9 @@ use crate::RootDatabase;
 pub struct Query {
     query: String,
     lowercased: String,
+    /// The lowercased segments the container path of a symbol has to match.
+    path: Vec<String>,
+    kinds: Vec<FileSymbolKind>,
     only_types: bool,
     libs: bool,
     exact: bool,
@@ -56,11 +60,20 @@ pub struct Query {
 }
 
 impl Query {
+    /// In a `::`-separated query like `http::Client::send`, the last segment
+    /// is matched against the names of the symbols, and the others against
+    /// the paths of the modules and items containing them.
     pub fn new(query: String) -> Query {
-        let lowercased = query.to_lowercase();
+        let mut segments: Vec<&str> = query.split("::").map(str::trim).collect();
+        let name = segments.pop().unwrap_or_default().to_string();
+        let path =
+            segments.into_iter().filter(|it| !it.is_empty()).map(str::to_lowercase).collect();
+        let lowercased = name.to_lowercase();
         Query {
-            query,
+            query: name,
             lowercased,
+            path,
+            kinds: Vec::new(),
             only_types: false,
             libs: false,
             exact: false,
@@ -72,6 +85,12 @@ impl Query {
         self.only_types = true;
     }
 
+    /// Only returns the symbols of this kind, or of the other kinds passed
+    /// here. Record fields are only returned when asked for this way.
+    pub fn kind(&mut self, kind: FileSymbolKind) {
+        self.kinds.push(kind);
+    }
+
     pub fn libs(&mut self) {
         self.libs = true;
     }
@@ -149,11 +168,24 @@ impl<DB: ParallelDatabase> Clone for Snap<salsa::Snapshot<DB>> {
 //
 // - `Foo` searches for `Foo` type in the current workspace
 // - `foo#` searches for `foo` function in the current workspace
-// - `Foo*` searches for `Foo` type among dependencies, including `stdlib`
-// - `foo#*` searches for `foo` function among dependencies
+// - `Foo*` searches for `Foo` type in the workspace and among dependencies, including `stdlib`
+// - `foo#*` searches for `foo` function in the workspace and among dependencies
+//
+// That is, `#` switches from "types" to all symbols, `*` adds the dependencies to
+// the current workspace. The workspace symbols are still searched with `*`, and
+// rank before the ones from dependencies.
 //
-// That is, `#` switches from "types" to all symbols, `*` switches from the current
-// workspace to dependencies.
+// The name matches if the query is a subsequence of it, ignoring case, so `HCS`
+// finds `HttpClientSettings`. A query like `http::Client::send` also matches the
+// preceding segments against the crate, modules, types and traits containing the
+// symbol. A `fn:`, `trait:`, `const:`, `macro:` or `field:` prefix limits the
+// search to symbols of that kind, so it implies `#`, like in `fn:send`; record
+// fields are only searched this way.
+//
+// Exact matches come first, then prefixes and matches of the camelCase or
+// snake_case word starts, then other subsequences. Among those, symbols from the
+// workspace come before the ones from dependencies, and the top level items
+// before the ones nested in other items.
 //
 // |===
 // | Editor  | Shortcut
@@ -163,26 +195,22 @@ impl<DB: ParallelDatabase> Clone for Snap<salsa::Snapshot<DB>> {
 pub fn world_symbols(db: &RootDatabase, query: Query) -> Vec<FileSymbol> {
     let _p = profile::span("world_symbols").detail(|| query.query.clone());
 
-    let tmp1;
-    let tmp2;
-    let buf: Vec<&SymbolIndex> = if query.libs {
-        tmp1 = db.library_symbols();
-        tmp1.values().collect()
-    } else {
-        let mut files = Vec::new();
-        for &root in db.local_roots().iter() {
-            let sr = db.source_root(root);
-            files.extend(sr.iter())
-        }
+    let mut files = Vec::new();
+    for &root in db.local_roots().iter() {
+        let sr = db.source_root(root);
+        files.extend(sr.iter())
+    }
 
-        let snap = Snap(db.snapshot());
-        tmp2 = files
-            .par_iter()
-            .map_with(snap, |db, &file_id| db.0.file_symbols(file_id))
-            .collect::<Vec<_>>();
-        tmp2.iter().map(|it| &**it).collect()
-    };
-    query.search(&buf)
+    let snap = Snap(db.snapshot());
+    let local = files
+        .par_iter()
+        .map_with(snap, |db, &file_id| db.0.file_symbols(file_id))
+        .collect::<Vec<_>>();
+    let local = local.iter().map(|it| &**it).collect::<Vec<_>>();
+
+    let library_symbols = if query.libs { Some(db.library_symbols()) } else { None };
+    let libs = library_symbols.iter().flat_map(|it| it.values()).collect::<Vec<_>>();
+    query.search(db, &local, &libs)
 }
 
 pub fn crate_symbols(db: &RootDatabase, krate: CrateId, query: Query) -> Vec<FileSymbol> {
@@ -206,7 +234,7 @@ pub fn crate_symbols(db: &RootDatabase, krate: CrateId, query: Query) -> Vec<Fil
         .collect::<Vec<_>>();
     let buf = buf.iter().map(|it| &**it).collect::<Vec<_>>();
 
-    query.search(&buf)
+    query.search(db, &buf, &[])
 }
 
 pub fn index_resolve(db: &RootDatabase, name: &str) -> Vec<FileSymbol> {
@@ -309,36 +337,171 @@ impl SymbolIndex {
 }
 
 impl Query {
-    pub(crate) fn search(self, indices: &[&SymbolIndex]) -> Vec<FileSymbol> {
+    pub(crate) fn search(
+        self,
+        db: &RootDatabase,
+        local: &[&SymbolIndex],
+        libs: &[&SymbolIndex],
+    ) -> Vec<FileSymbol> {
+        let indices = local.iter().chain(libs).collect::<Vec<_>>();
         let mut op = fst::map::OpBuilder::new();
         for file_symbols in indices.iter() {
             let automaton = fst::automaton::Subsequence::new(&self.lowercased);
             op = op.add(file_symbols.map.search(automaton))
         }
+        // Only the `limit` best symbols are kept. The max-heap has the worst
+        // one on top.
+        let sema = Semantics::new(db);
+        let mut module_paths = FxHashMap::default();
+        let mut heap = BinaryHeap::new();
+        let mut order = 0;
         let mut stream = op.union();
-        let mut res = Vec::new();
-        while let Some((_, indexed_values)) = stream.next() {
+        'search: while let Some((_, indexed_values)) = stream.next() {
             for indexed_value in indexed_values {
                 let symbol_index = &indices[indexed_value.index];
                 let (start, end) = SymbolIndex::map_value_to_range(indexed_value.value);
 
-                for symbol in &symbol_index.symbols[start..end] {
+                for (idx, symbol) in symbol_index.symbols[start..end].iter().enumerate() {
                     if self.only_types && !symbol.kind.is_type() {
                         continue;
                     }
+                    if self.kinds.is_empty() {
+                        if symbol.kind == FileSymbolKind::Field {
+                            continue;
+                        }
+                    } else if !self.kinds.contains(&symbol.kind) {
+                        continue;
+                    }
                     if self.exact && symbol.name != self.query {
                         continue;
                     }
 
-                    res.push(symbol.clone());
-                    if res.len() >= self.limit {
-                        return res;
+                    // The paths are only computed for the symbols with a matching name.
+                    if !self.path.is_empty() {
+                        let module_path = module_paths
+                            .entry(symbol.file_id)
+                            .or_insert_with(|| module_path(&sema, symbol.file_id));
+                        let path = container_path(db, symbol);
+                        if !self.matches_path(module_path.iter().chain(&path)) {
+                            continue;
+                        }
+                    }
+
+                    let is_lib = indexed_value.index >= local.len();
+                    let is_nested = symbol.container_name.is_some();
+                    let key = (self.match_rank(&symbol.name), is_lib, is_nested);
+                    // The order breaks the ties, so they stay in the order of the index.
+                    heap.push((key, order, indexed_value.index, start + idx));
+                    order += 1;
+                    if heap.len() > self.limit {
+                        heap.pop();
+                    }
+                    // Nothing can rank better than exact, top level, workspace symbols.
+                    if heap.len() == self.limit
+                        && heap.peek().map(|(key, ..)| *key) == Some((0, false, false))
+                    {
+                        break 'search;
                     }
                 }
             }
         }
-        res
+
+        heap.into_sorted_vec()
+            .into_iter()
+            .map(|(_, _, index, idx)| indices[index].symbols[idx].clone())
+            .collect()
+    }
+
+    /// Whether every segment of the query path is a prefix of a segment of
+    /// `container_path`, in order and ignoring case.
+    fn matches_path<'a>(&self, mut container_path: impl Iterator<Item = &'a SmolStr>) -> bool {
+        self.path
+            .iter()
+            .all(|segment| container_path.any(|it| it.to_lowercase().starts_with(segment.as_str())))
+    }
+
+    /// How well `name` matches the query, lower is better. The index search
+    /// already made sure that the query is a subsequence of the name.
+    fn match_rank(&self, name: &str) -> u8 {
+        if name == self.query {
+            return 0;
+        }
+        let lowercased = name.to_lowercase();
+        if lowercased == self.lowercased {
+            1
+        } else if lowercased.starts_with(&self.lowercased) {
+            2
+        } else if matches_word_starts(name, &self.lowercased) {
+            3
+        } else {
+            4
+        }
+    }
+}
+
+/// Whether the (lowercased) query is made of the starts of the camelCase or
+/// snake_case words of `name`, like `hcs` or `httpclset` for
+/// `HttpClientSettings`.
+fn matches_word_starts(name: &str, query: &str) -> bool {
+    let mut query = query.chars().peekable();
+    let mut prev: Option<char> = None;
+    let mut continues_match = false;
+    for c in name.chars() {
+        let next = match query.peek() {
+            Some(&it) => it,
+            None => break,
+        };
+        let is_word_start = match prev {
+            None => true,
+            Some(prev) => (prev == '_' && c != '_') || (c.is_uppercase() && !prev.is_uppercase()),
+        };
+        continues_match =
+            (continues_match || is_word_start) && c.to_lowercase().eq(iter::once(next));
+        if continues_match {
+            query.next();
+        }
+        prev = Some(c);
     }
+    query.peek().is_none()
+}
+
+/// The names of the items containing `symbol` in its file, including the
+/// self types of `impl` blocks.
+fn container_path(db: &RootDatabase, symbol: &FileSymbol) -> Vec<SmolStr> {
+    let root = db.parse(symbol.file_id).tree();
+    let mut res = symbol
+        .ptr
+        .to_node(root.syntax())
+        .ancestors()
+        .skip(1)
+        .filter_map(|node| {
+            to_symbol(&node).map(|(name, ..)| name).or_else(|| impl_self_ty_name(&node))
+        })
+        .collect::<Vec<_>>();
+    res.reverse();
+    res
+}
+
+/// The crate name followed by the names of the modules leading to `file_id`.
+fn module_path(sema: &Semantics<RootDatabase>, file_id: FileId) -> Vec<SmolStr> {
+    let module = match sema.to_module_def(file_id) {
+        Some(it) => it,
+        None => return Vec::new(),
+    };
+    let db = sema.db;
+    let mut res = module
+        .path_to_root(db)
+        .into_iter()
+        .map(|module| match module.name(db) {
+            Some(name) => SmolStr::new(name.to_string()),
+            None => module
+                .krate()
+                .display_name(db)
+                .map_or_else(SmolStr::default, |name| SmolStr::new(name.to_string())),
+        })
+        .collect::<Vec<_>>();
+    res.reverse();
+    res
 }
 
 /// The actual data that is stored in the index. It should be as compact as
@@ -358,6 +521,7 @@ pub struct FileSymbol {
 pub enum FileSymbolKind {
     Const,
     Enum,
+    Field,
     Function,
     Macro,
     Module,
@@ -407,6 +571,14 @@ fn source_file_to_file_symbols(source_file: &SourceFile, file_id: FileId) -> Vec
     symbols
 }
 
+fn impl_self_ty_name(node: &SyntaxNode) -> Option<SmolStr> {
+    let self_ty = ast::Impl::cast(node.clone())?.self_ty()?;
+    match self_ty {
+        ast::Type::PathType(it) => Some(it.path()?.segment()?.name_ref()?.text().into()),
+        _ => None,
+    }
+}
+
 fn to_symbol(node: &SyntaxNode) -> Option<(SmolStr, SyntaxNodePtr, TextRange)> {
     fn decl<N: NameOwner>(node: N) -> Option<(SmolStr, SyntaxNodePtr, TextRange)> {
         let name = node.name()?;
@@ -428,6 +600,7 @@ fn to_symbol(node: &SyntaxNode) -> Option<(SmolStr, SyntaxNodePtr, TextRange)> {
             ast::Static(it) => decl(it),
             ast::MacroRules(it) => decl(it),
             ast::Union(it) => decl(it),
+            ast::RecordField(it) => decl(it),
             _ => None,
         }
     }
@@ -447,6 +620,7 @@ fn to_file_symbol(node: &SyntaxNode, file_id: FileId) -> Option<FileSymbol> {
             STATIC => FileSymbolKind::Static,
             MACRO_RULES => FileSymbolKind::Macro,
             UNION => FileSymbolKind::Union,
+            RECORD_FIELD => FileSymbolKind::Field,
             kind => unreachable!("{:?}", kind),
         },
         range: node.text_range(),
@@ -456,3 +630,136 @@ fn to_file_symbol(node: &SyntaxNode, file_id: FileId) -> Option<FileSymbol> {
         container_name: None,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use base_db::fixture::ChangeFixture;
+    use expect_test::{expect, Expect};
+
+    use super::*;
+
+    fn check(ra_fixture: &str, query: Query, expect: Expect) {
+        let mut db = RootDatabase::default();
+        db.apply_change(ChangeFixture::parse(ra_fixture).change);
+        let actual = world_symbols(&db, query)
+            .into_iter()
+            .map(|symbol| {
+                let path = container_path(&db, &symbol).into_iter().map(|it| format!("{}::", it));
+                format!("{:?} {}{}\n", symbol.kind, path.collect::<String>(), symbol.name)
+            })
+            .collect::<String>();
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn ranks_exact_matches_and_word_starts_first() {
+        check(
+            r#"
+struct HighScores;
+struct HttpClientSettings;
+struct Hcs;
+fn hcs() {}
+"#,
+            Query::new("hcs".to_string()),
+            expect![[r#"
+                Function hcs
+                Struct Hcs
+                Struct HttpClientSettings
+                Struct HighScores
+            "#]],
+        );
+    }
+
+    #[test]
+    fn keeps_the_best_matches_within_the_limit() {
+        let mut query = Query::new("foo".to_string());
+        query.limit(2);
+        check(
+            r#"
+struct Foobar;
+struct FooBaz;
+mod m {
+    struct Foo;
+}
+struct Foo;
+"#,
+            query,
+            expect![[r#"
+                Struct Foo
+                Struct m::Foo
+            "#]],
+        );
+    }
+
+    #[test]
+    fn matches_container_paths() {
+        let fixture = r#"
+//- /main.rs crate:main
+mod http {
+    pub struct Client;
+    impl Client {
+        pub fn send(&self) {}
+    }
+    pub fn send() {}
+}
+mod ftp {
+    pub struct Client;
+    impl Client {
+        pub fn send(&self) {}
+    }
+}
+"#;
+        check(
+            fixture,
+            Query::new("http::Client::send".to_string()),
+            expect![[r#"
+                Function http::Client::send
+            "#]],
+        );
+        check(
+            fixture,
+            Query::new("main::send".to_string()),
+            expect![[r#"
+                Function http::Client::send
+                Function http::send
+                Function ftp::Client::send
+            "#]],
+        );
+        let mut query = Query::new("main::send".to_string());
+        query.limit(2);
+        check(
+            fixture,
+            query,
+            expect![[r#"
+                Function http::Client::send
+                Function http::send
+            "#]],
+        );
+    }
+
+    #[test]
+    fn filters_by_kind() {
+        let fixture = r#"
+struct S { count: usize }
+const COUNT: usize = 0;
+fn count() {}
+"#;
+        check(
+            fixture,
+            Query::new("count".to_string()),
+            expect![[r#"
+                Function count
+                Const COUNT
+            "#]],
+        );
+        let mut query = Query::new("count".to_string());
+        query.kind(FileSymbolKind::Field);
+        check(
+            fixture,
+            query,
+            expect![[r#"
+                Field S::count
+            "#]],
+        );
+    }
+}
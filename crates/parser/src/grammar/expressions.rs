@@ -156,6 +156,15 @@ pub(super) fn stmt(p: &mut Parser, with_semi: StmtWithSemi) {
         }
         if p.eat(T![=]) {
             expressions::expr_with_attrs(p);
+
+            // test let_else
+            // fn foo() {
+            //     let Some(x) = opt else { return };
+            //     let Ok(y) = res else { panic!() };
+            // }
+            if p.eat(T![else]) {
+                block_expr(p);
+            }
         }
 
         match with_semi {
@@ -323,6 +323,7 @@ fn project_json_to_crate_graph(
                     file_id,
                     krate.edition,
                     krate.display_name.clone(),
+                    cfg_options.clone(),
                     cfg_options,
                     env,
                     proc_macro.unwrap_or_default(),
@@ -572,6 +573,11 @@ fn add_target_crate_root(
         opts
     };
 
+    let mut potential_cfg_options = cfg_options.clone();
+    for feature in pkg.features.keys() {
+        potential_cfg_options.insert_key_value("feature".into(), feature.into());
+    }
+
     let mut env = Env::default();
     if let Some(envs) = build_data.map(|it| &it.envs) {
         for (k, v) in envs {
@@ -591,6 +597,7 @@ fn add_target_crate_root(
         edition,
         Some(display_name),
         cfg_options,
+        potential_cfg_options,
         env,
         proc_macro,
     );
@@ -620,6 +627,7 @@ fn sysroot_to_crate_graph(
                 Edition::Edition2018,
                 Some(display_name),
                 cfg_options.clone(),
+                cfg_options.clone(),
                 env,
                 proc_macro,
             );
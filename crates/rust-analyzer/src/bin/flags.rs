@@ -2,8 +2,12 @@
 #![allow(unreachable_pub)]
 use std::{env, path::PathBuf};
 
+use ide::AssistScope;
 use ide_ssr::{SsrPattern, SsrRule};
-use rust_analyzer::cli::{BenchWhat, Position, Verbosity};
+use rust_analyzer::{
+    cli::{BenchWhat, Position, Verbosity},
+    Result,
+};
 use vfs::AbsPathBuf;
 
 xflags::xflags! {
@@ -118,6 +122,19 @@ xflags::xflags! {
             optional --debug snippet: String
         }
 
+        /// Apply an assist everywhere it is applicable in a file.
+        cmd apply-assist
+            /// The id of the assist, like `add_explicit_type`.
+            required assist: String
+            /// The file to apply the assist in.
+            required path: PathBuf
+        {
+            /// Apply the assist in the file's module and all of its submodules.
+            optional --module
+            /// Apply the assist in the whole crate of the file.
+            optional --whole-crate
+        }
+
         cmd proc-macro {}
     }
 }
@@ -146,6 +163,7 @@ pub enum RustAnalyzerCmd {
     Diagnostics(Diagnostics),
     Ssr(Ssr),
     Search(Search),
+    ApplyAssist(ApplyAssist),
     ProcMacro(ProcMacro),
 }
 
@@ -215,6 +233,15 @@ pub struct Search {
     pub debug: Option<String>,
 }
 
+#[derive(Debug)]
+pub struct ApplyAssist {
+    pub assist: String,
+    pub path: PathBuf,
+
+    pub module: bool,
+    pub whole_crate: bool,
+}
+
 #[derive(Debug)]
 pub struct ProcMacro;
 
@@ -253,3 +280,17 @@ impl AnalysisBench {
         }
     }
 }
+
+impl ApplyAssist {
+    pub(crate) fn scope(&self) -> Result<AssistScope> {
+        let scope = match (self.module, self.whole_crate) {
+            (false, false) => AssistScope::File,
+            (true, false) => AssistScope::Module,
+            (false, true) => AssistScope::Crate,
+            (true, true) => {
+                return Err("at most one of `--module` or `--whole-crate` can be set".into())
+            }
+        };
+        Ok(scope)
+    }
+}
@@ -117,6 +117,8 @@ pub fn server_capabilities(client_caps: &ClientCapabilities) -> ServerCapabiliti
         experimental: Some(json!({
             "joinLines": true,
             "ssr": true,
+            "changeSignature": true,
+            "applyAssistEverywhere": true,
             "onEnter": true,
             "parentModule": true,
             "runnables": {
@@ -3,6 +3,7 @@
 mod load_cargo;
 mod analysis_stats;
 mod analysis_bench;
+mod apply_assist;
 mod diagnostics;
 mod progress_report;
 mod ssr;
@@ -17,6 +18,7 @@ use vfs::Vfs;
 pub use self::{
     analysis_bench::{BenchCmd, BenchWhat, Position},
     analysis_stats::AnalysisStatsCmd,
+    apply_assist::ApplyAssistCmd,
     diagnostics::diagnostics,
     load_cargo::{load_workspace, load_workspace_at, LoadCargoConfig},
     ssr::{apply_ssr_rules, search_for_patterns},
@@ -113,6 +113,7 @@ impl BenchCmd {
                             prefix_kind: PrefixKind::Plain,
                             group: true,
                         },
+                        snippets: Vec::new(),
                     };
                     let res = do_work(&mut host, file_id, |analysis| {
                         analysis.completions(&options, file_position)
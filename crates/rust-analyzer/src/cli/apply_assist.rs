@@ -0,0 +1,51 @@
+//! Applies an assist everywhere it is applicable from the command line.
+
+use std::{env, path::PathBuf};
+
+use anyhow::{format_err, Result};
+use hir::PrefixKind;
+use ide::{AssistConfig, AssistScope};
+use ide_db::helpers::insert_use::{InsertUseConfig, MergeBehavior};
+use vfs::AbsPathBuf;
+
+use crate::cli::load_cargo::{load_workspace_at, LoadCargoConfig};
+
+pub struct ApplyAssistCmd {
+    pub assist_id: String,
+    pub path: PathBuf,
+    pub scope: AssistScope,
+}
+
+impl ApplyAssistCmd {
+    pub fn run(self) -> Result<()> {
+        let cargo_config = Default::default();
+        let load_cargo_config =
+            LoadCargoConfig { load_out_dirs_from_check: true, with_proc_macro: true };
+        let (host, vfs, _proc_macro) =
+            load_workspace_at(&env::current_dir()?, &cargo_config, &load_cargo_config, &|_| {})?;
+        let analysis = host.analysis();
+
+        let path = AbsPathBuf::assert(env::current_dir()?.join(&self.path)).into();
+        let file_id = vfs.file_id(&path).ok_or_else(|| format_err!("Can't find {}", path))?;
+
+        let config = AssistConfig {
+            snippet_cap: None,
+            allowed: None,
+            insert_use: InsertUseConfig {
+                merge: Some(MergeBehavior::Full),
+                prefix_kind: PrefixKind::Plain,
+                group: true,
+            },
+        };
+        let change =
+            analysis.apply_assist_everywhere(&config, &self.assist_id, file_id, self.scope)?;
+        for (file_id, edit) in change.source_file_edits {
+            if let Some(path) = vfs.file_path(file_id).as_path() {
+                let mut contents = analysis.file_text(file_id)?.to_string();
+                edit.apply(&mut contents);
+                std::fs::write(path, contents)?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -11,15 +11,19 @@ use std::{ffi::OsString, iter, path::PathBuf};
 
 use flycheck::FlycheckConfig;
 use hir::PrefixKind;
-use ide::{AssistConfig, CompletionConfig, DiagnosticsConfig, HoverConfig, InlayHintsConfig};
+use ide::{
+    AssistConfig, CompletionConfig, DiagnosticsConfig, HoverConfig, InlayHintsConfig, Snippet,
+    SnippetScope,
+};
 use ide_db::helpers::{
     insert_use::{InsertUseConfig, MergeBehavior},
     SnippetCap,
 };
+use itertools::Itertools;
 use lsp_types::{ClientCapabilities, MarkupKind};
 use project_model::{CargoConfig, ProjectJson, ProjectJsonData, ProjectManifest, RustcSource};
-use rustc_hash::FxHashSet;
-use serde::{de::DeserializeOwned, Deserialize};
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde::{de::DeserializeOwned, Deserialize, Deserializer};
 use vfs::AbsPathBuf;
 
 use crate::{
@@ -89,6 +93,12 @@ config_data! {
         /// Toggles the additional completions that automatically add imports when completed.
         /// Note that your client must specify the `additionalTextEdits` LSP client capability to truly have this feature enabled.
         completion_autoimport_enable: bool       = "true",
+        /// Custom completion snippets, by name. A snippet has `prefix` and
+        /// `postfix` triggers, a `body` in which `${receiver}` stands for the
+        /// expression a postfix snippet is completed on, the paths it `requires`
+        /// to be imported, the trait the receiver has to implement in
+        /// `receiverImplements`, and a `scope`, either `"expr"` or `"item"`.
+        completion_snippets: FxHashMap<String, SnippetDef> = "{}",
 
         /// Whether to show native rust-analyzer diagnostics.
         diagnostics_enable: bool                = "true",
@@ -212,6 +222,7 @@ pub struct Config {
     data: ConfigData,
     pub discovered_projects: Option<Vec<ProjectManifest>>,
     pub root_path: AbsPathBuf,
+    snippets: Vec<Snippet>,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -293,7 +304,13 @@ pub struct RunnablesConfig {
 
 impl Config {
     pub fn new(root_path: AbsPathBuf, caps: ClientCapabilities) -> Self {
-        Config { caps, data: ConfigData::default(), discovered_projects: None, root_path }
+        Config {
+            caps,
+            data: ConfigData::default(),
+            discovered_projects: None,
+            root_path,
+            snippets: Default::default(),
+        }
     }
     pub fn update(&mut self, json: serde_json::Value) {
         log::info!("updating config from JSON: {:#}", json);
@@ -301,6 +318,28 @@ impl Config {
             return;
         }
         self.data = ConfigData::from_json(json);
+
+        self.snippets.clear();
+        // Sorted, so that the completions don't come in hash map order.
+        for (name, def) in self.data.completion_snippets.iter().sorted_by_key(|(name, _)| *name) {
+            let scope = match def.scope {
+                SnippetScopeDef::Expr => SnippetScope::Expr,
+                SnippetScopeDef::Item => SnippetScope::Item,
+            };
+            let snippet = Snippet::new(
+                &def.prefix,
+                &def.postfix,
+                &def.body,
+                def.description.as_deref().unwrap_or(name),
+                &def.requires,
+                def.receiver_implements.as_deref(),
+                scope,
+            );
+            match snippet {
+                Some(snippet) => self.snippets.push(snippet),
+                None => log::error!("invalid completion snippet `{}`: {:?}", name, def),
+            }
+        }
     }
 
     pub fn json_schema() -> serde_json::Value {
@@ -597,6 +636,7 @@ impl Config {
             add_call_parenthesis: self.data.completion_addCallParenthesis,
             add_call_argument_snippets: self.data.completion_addCallArgumentSnippets,
             insert_use: self.insert_use_config(),
+            snippets: self.snippets.clone(),
             snippet_cap: SnippetCap::new(try_or!(
                 self.caps
                     .text_document
@@ -682,6 +722,49 @@ enum ImportPrefixDef {
     ByCrate,
 }
 
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+struct SnippetDef {
+    #[serde(deserialize_with = "single_or_array")]
+    prefix: Vec<String>,
+    #[serde(deserialize_with = "single_or_array")]
+    postfix: Vec<String>,
+    description: Option<String>,
+    #[serde(deserialize_with = "single_or_array")]
+    body: Vec<String>,
+    #[serde(deserialize_with = "single_or_array")]
+    requires: Vec<String>,
+    receiver_implements: Option<String>,
+    scope: SnippetScopeDef,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+enum SnippetScopeDef {
+    Expr,
+    Item,
+}
+
+impl Default for SnippetScopeDef {
+    fn default() -> Self {
+        SnippetScopeDef::Expr
+    }
+}
+
+fn single_or_array<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum SingleOrArray {
+        Single(String),
+        Array(Vec<String>),
+    }
+
+    Ok(match SingleOrArray::deserialize(deserializer)? {
+        SingleOrArray::Single(it) => vec![it],
+        SingleOrArray::Array(it) => it,
+    })
+}
+
 macro_rules! _config_data {
     (struct $name:ident {
         $(
@@ -845,6 +928,9 @@ fn field_props(field: &str, ty: &str, doc: &[&str], default: &str) -> serde_json
                 "Force import paths to be absolute by always starting them with `crate` or the crate name they refer to."
             ],
         },
+        "FxHashMap<String, SnippetDef>" => set! {
+            "type": "object",
+        },
         "Vec<ManifestOrProjectJson>" => set! {
             "type": "array",
             "items": { "type": ["string", "object"] },
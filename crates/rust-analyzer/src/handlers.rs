@@ -8,8 +8,9 @@ use std::{
 };
 
 use ide::{
-    AnnotationConfig, FileId, FilePosition, FileRange, HoverAction, HoverGotoTypeData, Query,
-    RangeInfo, Runnable, RunnableKind, SearchScope, SourceChange, TextEdit,
+    AnnotationConfig, AssistScope, FileId, FilePosition, FileRange, FileSymbolKind, HoverAction,
+    HoverGotoTypeData, Query, RangeInfo, Runnable, RunnableKind, SearchScope, SignatureParam,
+    SourceChange, TextEdit,
 };
 use ide_db::SymbolKind;
 use itertools::Itertools;
@@ -364,14 +365,19 @@ pub(crate) fn handle_workspace_symbol(
     params: lsp_types::WorkspaceSymbolParams,
 ) -> Result<Option<Vec<SymbolInformation>>> {
     let _p = profile::span("handle_workspace_symbol");
-    let all_symbols = params.query.contains('#');
-    let libs = params.query.contains('*');
+    let (kind, query) = split_kind_filter(&params.query);
+    // A kind filter asks for symbols which aren't types, like `#` does.
+    let all_symbols = query.contains('#') || kind.is_some();
+    let libs = query.contains('*');
     let query = {
-        let query: String = params.query.chars().filter(|&c| c != '#' && c != '*').collect();
+        let query: String = query.chars().filter(|&c| c != '#' && c != '*').collect();
         let mut q = Query::new(query);
         if !all_symbols {
             q.only_types();
         }
+        if let Some(kind) = kind {
+            q.kind(kind);
+        }
         if libs {
             q.libs();
         }
@@ -387,6 +393,23 @@ pub(crate) fn handle_workspace_symbol(
 
     return Ok(Some(res));
 
+    /// Splits a kind prefix like `fn:` off the query.
+    fn split_kind_filter(query: &str) -> (Option<FileSymbolKind>, &str) {
+        let (prefix, rest) = match split_once(query, ':') {
+            Some(it) if !it.1.starts_with(':') => it,
+            _ => return (None, query),
+        };
+        let kind = match prefix.trim() {
+            "fn" => FileSymbolKind::Function,
+            "trait" => FileSymbolKind::Trait,
+            "const" => FileSymbolKind::Const,
+            "macro" => FileSymbolKind::Macro,
+            "field" => FileSymbolKind::Field,
+            _ => return (None, query),
+        };
+        (Some(kind), rest)
+    }
+
     fn exec_query(snap: &GlobalStateSnapshot, query: Query) -> Result<Vec<SymbolInformation>> {
         let mut res = Vec::new();
         for nav in snap.analysis.symbol_search(query)? {
@@ -1190,6 +1213,49 @@ pub(crate) fn handle_ssr(
     to_proto::workspace_edit(&snap, source_change)
 }
 
+pub(crate) fn handle_change_signature(
+    snap: GlobalStateSnapshot,
+    params: lsp_ext::ChangeSignatureParams,
+) -> Result<lsp_types::WorkspaceEdit> {
+    let _p = profile::span("handle_change_signature");
+    let position = from_proto::file_position(&snap, params.position)?;
+    let params: Vec<SignatureParam> = params
+        .params
+        .into_iter()
+        .map(|param| match param {
+            lsp_ext::SignatureParam::Existing { index } => SignatureParam::Existing(index),
+            lsp_ext::SignatureParam::New { pattern, ty, default } => {
+                SignatureParam::New { pattern, ty, default }
+            }
+        })
+        .collect();
+    let source_change = snap
+        .analysis
+        .change_signature(position, &params)?
+        .map_err(to_proto::change_signature_error)?;
+    to_proto::workspace_edit(&snap, source_change)
+}
+
+pub(crate) fn handle_apply_assist_everywhere(
+    snap: GlobalStateSnapshot,
+    params: lsp_ext::ApplyAssistEverywhereParams,
+) -> Result<lsp_types::WorkspaceEdit> {
+    let _p = profile::span("handle_apply_assist_everywhere");
+    let file_id = from_proto::file_id(&snap, &params.text_document.uri)?;
+    let scope = match params.scope {
+        lsp_ext::AssistScope::File => AssistScope::File,
+        lsp_ext::AssistScope::Module => AssistScope::Module,
+        lsp_ext::AssistScope::Crate => AssistScope::Crate,
+    };
+    let source_change = snap.analysis.apply_assist_everywhere(
+        &snap.config.assist(),
+        &params.assist_id,
+        file_id,
+        scope,
+    )?;
+    to_proto::workspace_edit(&snap, source_change)
+}
+
 pub(crate) fn publish_diagnostics(
     snap: &GlobalStateSnapshot,
     file_id: FileId,
@@ -241,6 +241,64 @@ pub struct SsrParams {
     pub selections: Vec<lsp_types::Range>,
 }
 
+pub enum ChangeSignature {}
+
+impl Request for ChangeSignature {
+    type Params = ChangeSignatureParams;
+    type Result = lsp_types::WorkspaceEdit;
+    const METHOD: &'static str = "experimental/changeSignature";
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeSignatureParams {
+    #[serde(flatten)]
+    pub position: lsp_types::TextDocumentPositionParams,
+
+    /// The parameters of the function, in their new order. Existing parameters that are not
+    /// listed are removed.
+    pub params: Vec<SignatureParam>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum SignatureParam {
+    /// The parameter currently at this index, not counting `self`.
+    Existing { index: usize },
+    /// A new parameter. Callers pass `default` for it.
+    New {
+        pattern: String,
+        #[serde(rename = "type")]
+        ty: String,
+        default: String,
+    },
+}
+
+pub enum ApplyAssistEverywhere {}
+
+impl Request for ApplyAssistEverywhere {
+    type Params = ApplyAssistEverywhereParams;
+    type Result = lsp_types::WorkspaceEdit;
+    const METHOD: &'static str = "experimental/applyAssistEverywhere";
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApplyAssistEverywhereParams {
+    pub text_document: TextDocumentIdentifier,
+    /// The id of the assist, as in the `id` of a code action, without the kind suffix.
+    pub assist_id: String,
+    pub scope: AssistScope,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum AssistScope {
+    File,
+    Module,
+    Crate,
+}
+
 pub enum StatusNotification {}
 
 #[derive(Serialize, Deserialize)]
@@ -542,6 +542,8 @@ impl GlobalState {
             )
             .on::<lsp_types::request::WillRenameFiles>(handlers::handle_will_rename_files)
             .on::<lsp_ext::Ssr>(handlers::handle_ssr)
+            .on::<lsp_ext::ChangeSignature>(handlers::handle_change_signature)
+            .on::<lsp_ext::ApplyAssistEverywhere>(handlers::handle_apply_assist_everywhere)
             .finish();
         Ok(())
     }
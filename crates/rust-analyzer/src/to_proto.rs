@@ -5,11 +5,11 @@ use std::{
 };
 
 use ide::{
-    Annotation, AnnotationKind, Assist, AssistKind, CallInfo, CompletionItem, CompletionItemKind,
-    CompletionRelevance, Documentation, FileId, FileRange, FileSystemEdit, Fold, FoldKind,
-    Highlight, HlMod, HlPunct, HlRange, HlTag, Indel, InlayHint, InlayKind, InsertTextFormat,
-    Markup, NavigationTarget, ReferenceAccess, RenameError, Runnable, Severity, SourceChange,
-    StructureNodeKind, SymbolKind, TextEdit, TextRange, TextSize,
+    Annotation, AnnotationKind, Assist, AssistKind, CallInfo, ChangeSignatureError, CompletionItem,
+    CompletionItemKind, CompletionRelevance, Documentation, FileId, FileRange, FileSystemEdit,
+    Fold, FoldKind, Highlight, HlMod, HlPunct, HlRange, HlTag, Indel, InlayHint, InlayKind,
+    InsertTextFormat, Markup, NavigationTarget, ReferenceAccess, RenameError, Runnable, Severity,
+    SourceChange, StructureNodeKind, SymbolKind, TextEdit, TextRange, TextSize,
 };
 use itertools::Itertools;
 use serde_json::to_value;
@@ -1068,6 +1068,10 @@ pub(crate) fn rename_error(err: RenameError) -> crate::LspError {
     crate::LspError { code: lsp_server::ErrorCode::InvalidParams as i32, message: err.to_string() }
 }
 
+pub(crate) fn change_signature_error(err: ChangeSignatureError) -> crate::LspError {
+    crate::LspError { code: lsp_server::ErrorCode::InvalidParams as i32, message: err.to_string() }
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
@@ -1108,6 +1112,7 @@ mod tests {
                         prefix_kind: PrefixKind::Plain,
                         group: true,
                     },
+                    snippets: Vec::new(),
                 },
                 ide_db::base_db::FilePosition { file_id, offset },
             )
@@ -1123,13 +1128,13 @@ mod tests {
                 (
                     "&arg",
                     Some(
-                        "fffffffa",
+                        "fffffff9",
                     ),
                 ),
                 (
                     "arg",
                     Some(
-                        "fffffffd",
+                        "fffffffc",
                     ),
                 ),
             ]
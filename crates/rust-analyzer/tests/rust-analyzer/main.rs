@@ -18,12 +18,12 @@ use lsp_types::{
     notification::DidOpenTextDocument,
     request::{
         CodeActionRequest, Completion, Formatting, GotoTypeDefinition, HoverRequest,
-        WillRenameFiles,
+        WillRenameFiles, WorkspaceSymbol,
     },
     CodeActionContext, CodeActionParams, CompletionParams, DidOpenTextDocumentParams,
     DocumentFormattingParams, FileRename, FormattingOptions, GotoDefinitionParams, HoverParams,
     PartialResultParams, Position, Range, RenameFilesParams, TextDocumentItem,
-    TextDocumentPositionParams, WorkDoneProgressParams,
+    TextDocumentPositionParams, WorkDoneProgressParams, WorkspaceSymbolParams,
 };
 use rust_analyzer::lsp_ext::{OnEnter, Runnables, RunnablesParams};
 use serde_json::json;
@@ -157,6 +157,123 @@ fn main() {}
     );
 }
 
+#[test]
+fn workspace_symbol_filters_by_kind() {
+    if skip_slow_tests() {
+        return;
+    }
+
+    let server = project(
+        r#"
+//- /Cargo.toml
+[package]
+name = "foo"
+version = "0.0.0"
+
+//- /src/lib.rs
+mod net {
+    pub struct Client { send_count: usize }
+    pub trait Send {}
+    pub const SEND: usize = 0;
+    pub fn send() {}
+}
+"#,
+    )
+    .wait_until_workspace_is_loaded();
+
+    let query = |query: &str| WorkspaceSymbolParams {
+        query: query.to_string(),
+        work_done_progress_params: WorkDoneProgressParams::default(),
+        partial_result_params: PartialResultParams::default(),
+    };
+
+    server.request::<WorkspaceSymbol>(
+        query("send"),
+        json!([
+            { "name": "Send", "kind": 11, "location": "{...}", "containerName": "net" }
+        ]),
+    );
+    server.request::<WorkspaceSymbol>(
+        query("send#"),
+        json!([
+            { "name": "Send", "kind": 11, "location": "{...}", "containerName": "net" },
+            { "name": "SEND", "kind": 14, "location": "{...}", "containerName": "net" },
+            { "name": "send", "kind": 12, "location": "{...}", "containerName": "net" }
+        ]),
+    );
+    server.request::<WorkspaceSymbol>(
+        query("fn:send"),
+        json!([
+            { "name": "send", "kind": 12, "location": "{...}", "containerName": "net" }
+        ]),
+    );
+    server.request::<WorkspaceSymbol>(
+        query("const:send"),
+        json!([
+            { "name": "SEND", "kind": 14, "location": "{...}", "containerName": "net" }
+        ]),
+    );
+    server.request::<WorkspaceSymbol>(
+        query("field:send"),
+        json!([
+            { "name": "send_count", "kind": 8, "location": "{...}", "containerName": "Client" }
+        ]),
+    );
+}
+
+#[test]
+fn workspace_symbol_matches_paths() {
+    if skip_slow_tests() {
+        return;
+    }
+
+    let server = project(
+        r#"
+//- /Cargo.toml
+[package]
+name = "foo"
+version = "0.0.0"
+
+//- /src/lib.rs
+mod http {
+    pub struct Client;
+    impl Client {
+        pub fn send(&self) {}
+    }
+}
+mod ftp {
+    pub struct Client;
+    impl Client {
+        pub fn send(&self) {}
+    }
+}
+"#,
+    )
+    .wait_until_workspace_is_loaded();
+
+    server.request::<WorkspaceSymbol>(
+        WorkspaceSymbolParams {
+            query: "http::Client::send#".to_string(),
+            work_done_progress_params: WorkDoneProgressParams::default(),
+            partial_result_params: PartialResultParams::default(),
+        },
+        json!([
+            {
+                "name": "send",
+                "kind": 12,
+                "location": {
+                    "uri": "file:///[..]/src/lib.rs",
+                    "range": {
+                        "start": { "line": 3, "character": 8 },
+                        "end": { "line": 3, "character": 29 }
+                    }
+                },
+                "containerName": "http"
+            }
+        ]),
+    );
+}
+
 #[test]
 fn test_format_document() {
     if skip_slow_tests() {
@@ -13,7 +13,7 @@ use crate::{
     ast::{
         self,
         make::{self, tokens},
-        AstNode, GenericParamsOwner, NameOwner, TypeBoundsOwner,
+        AstNode, GenericParamsOwner, NameOwner, TypeBoundsOwner, VisibilityOwner,
     },
     AstToken, Direction, InsertPosition, SmolStr, SyntaxElement, SyntaxKind,
     SyntaxKind::{ATTR, COMMENT, WHITESPACE},
@@ -47,6 +47,32 @@ impl ast::Fn {
         self.replace_children(single_node(old_body_or_semi), to_insert)
     }
 
+    #[must_use]
+    pub fn without_body(&self) -> ast::Fn {
+        let body = match self.body() {
+            Some(it) => it.syntax().clone(),
+            None => return self.clone(),
+        };
+        let start = match body.prev_sibling_or_token() {
+            Some(ws) if ws.kind() == WHITESPACE => ws,
+            _ => body.clone().into(),
+        };
+        self.replace_children(start..=body.into(), iter::once(make::token(T![;]).into()))
+    }
+
+    #[must_use]
+    pub fn without_visibility(&self) -> ast::Fn {
+        let vis = match self.visibility() {
+            Some(it) => it.syntax().clone(),
+            None => return self.clone(),
+        };
+        let end = match vis.next_sibling_or_token() {
+            Some(ws) if ws.kind() == WHITESPACE => ws,
+            _ => vis.clone().into(),
+        };
+        self.replace_children(vis.into()..=end, iter::empty())
+    }
+
     #[must_use]
     pub fn with_generic_param_list(&self, generic_args: ast::GenericParamList) -> ast::Fn {
         if let Some(old) = self.generic_param_list() {
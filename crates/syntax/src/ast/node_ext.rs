@@ -8,7 +8,7 @@ use parser::SyntaxKind;
 
 use crate::{
     ast::{self, support, AstNode, AstToken, AttrsOwner, NameOwner, SyntaxNode},
-    SmolStr, SyntaxElement, SyntaxToken, T,
+    Direction, SmolStr, SyntaxElement, SyntaxToken, T,
 };
 
 impl ast::Lifetime {
@@ -417,6 +417,19 @@ pub enum FieldKind {
     Index(SyntaxToken),
 }
 
+impl ast::LetStmt {
+    pub fn else_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.syntax, T![else])
+    }
+
+    /// The diverging block of a `let PAT = EXPR else { ... };` statement.
+    pub fn let_else(&self) -> Option<ast::BlockExpr> {
+        self.else_token()?
+            .siblings_with_tokens(Direction::Next)
+            .find_map(|it| it.into_node().and_then(ast::BlockExpr::cast))
+    }
+}
+
 impl ast::FieldExpr {
     pub fn index_token(&self) -> Option<SyntaxToken> {
         self.syntax